@@ -0,0 +1,148 @@
+use reqwest::Url;
+use tracing_subscriber::EnvFilter;
+
+use libzulip::{
+    build_info,
+    config::{ApiKey, ClientConfig, UserAgent},
+    events::{get_events::EventKind, register::RegisterQueue},
+    messages::{
+        emoji_reaction::EmojiSelector,
+        send_message::{ChannelMessageTarget, Message},
+    },
+    Client,
+};
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    // grab auth stuff from env
+    let email = std::env::var("ZULIP_EMAIL").unwrap();
+    let api_key = std::env::var("ZULIP_PERSONAL_KEY").unwrap();
+    let server_address = Url::try_from("https://libz.zulipchat.com").unwrap(); // change if u want
+
+    // setup logging
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::new(format!(
+            "info,{}=trace",
+            build_info::PKG_NAME
+        )))
+        .init();
+
+    // make the client
+    let client = Client::new(
+        ClientConfig::builder()
+            .user_agent(UserAgent::new("client_name", "version"))
+            .api_key(ApiKey::new(api_key))
+            .email(email)
+            .server_address(server_address)
+            .build()
+            .unwrap(),
+    )
+    .await
+    .unwrap();
+
+    // make a queue, then get rid of it
+    let resp = client
+        .register_queue(RegisterQueue {
+            event_types: Some(vec!["message".into()]),
+            ..RegisterQueue::default()
+        })
+        .await
+        .unwrap();
+    let queue_id = resp.queue_id.unwrap();
+    client.delete_queue(&queue_id).await.unwrap();
+    client.delete_queue(&queue_id).await.unwrap_err(); // it's already gone!
+
+    // one request should be enough to know who's around, and where we are
+    let state = client
+        .register_full_state(Some(vec!["realm_user".into(), "subscription".into()]))
+        .await
+        .unwrap();
+    client
+        .delete_queue(state.queue_id.as_ref().unwrap())
+        .await
+        .unwrap();
+    let own_user = client.get_own_user().await.unwrap();
+    assert!(state
+        .realm_users
+        .unwrap()
+        .iter()
+        .any(|user| user.user_id == own_user.user_id));
+    assert!(state.subscriptions.is_some());
+    assert!(state.unread_msgs.is_none()); // we didn't ask for it
+
+    // the server should tell us the names of its emoji
+    let resp = client
+        .register_queue(RegisterQueue {
+            event_types: Some(vec!["message".into()]),
+            fetch_event_types: Some(vec!["realm".into()]),
+            ..RegisterQueue::default()
+        })
+        .await
+        .unwrap();
+    client.delete_queue(&resp.queue_id.unwrap()).await.unwrap();
+    let emoji_data = client
+        .get_server_emoji_reaction_data(&resp.server_emoji_data_url.unwrap())
+        .await
+        .unwrap();
+    assert_eq!(emoji_data.name("1f600"), Some("grinning"));
+    assert_eq!(
+        libzulip::messages::emoji_reaction::unicode_emoji(emoji_data.code("grinning").unwrap())
+            .as_deref(),
+        Some("😀")
+    );
+
+    // our bundled names should match the server's
+    for emoji in ["👍", "❤️", "🎉", "🇺🇸"] {
+        let selector = EmojiSelector::from_unicode_str(emoji).unwrap();
+        assert_eq!(
+            emoji_data.name(selector.emoji_code.as_deref().unwrap()),
+            Some(selector.emoji_name.as_str()),
+            "{emoji}"
+        );
+    }
+
+    // deleting a message should show up in a new queue
+    let resp = client
+        .register_queue(RegisterQueue {
+            event_types: Some(vec!["delete_message".into()]),
+            ..RegisterQueue::default()
+        })
+        .await
+        .unwrap();
+    let queue_id = resp.queue_id.unwrap();
+    let msg_id = client
+        .send_message(&Message::Channel {
+            to: ChannelMessageTarget::Name("general".into()),
+            content: "this is about to be deleted".into(),
+            topic: "events".into(),
+            queue_id: "".into(),
+            local_id: "".into(),
+        })
+        .await
+        .unwrap()
+        .id;
+    client.delete_message(msg_id).await.unwrap();
+
+    let events = client
+        .get_events(&queue_id, resp.last_event_id, false)
+        .await
+        .unwrap();
+    let deleted = events
+        .into_iter()
+        .find_map(|event| match event.kind {
+            EventKind::DeleteMessage(deleted) => Some(deleted),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(deleted.message_ids, vec![msg_id]);
+    client.delete_queue(&queue_id).await.unwrap();
+
+    // the unread counts should add up
+    let unread = client.get_unread_counts().await.unwrap();
+    let listed = unread.streams.values().map(Vec::len).sum::<usize>()
+        + unread.pms.values().map(Vec::len).sum::<usize>()
+        + unread.huddles.values().map(Vec::len).sum::<usize>();
+    assert!(listed as u64 <= unread.count);
+
+    tracing::info!("assertions passed! :D");
+}
@@ -1,3 +1,4 @@
+use futures::TryStreamExt as _;
 use reqwest::Url;
 use tempfile::NamedTempFile;
 use tracing_subscriber::EnvFilter;
@@ -6,11 +7,18 @@ use uuid::Uuid;
 use libzulip::{
     build_info,
     config::{ApiKey, ClientConfig, MessagesConfig, UserAgent},
+    error::{MessageError, ZulipError, ZulipErrorCode},
     messages::{
+        attachments::Attachment,
         edit_message::EditedMessage,
         emoji_reaction::EmojiSelector,
-        send_message::{ChannelMessageTarget, Message},
+        fetch_single_message::ContentFormat,
+        get_messages::{Anchor, GetAllMessages, GetMessages},
+        resolve_topic::RESOLVED_TOPIC_PREFIX,
+        send_message::{ChannelMessageTarget, DirectMessageTarget, Message},
+        update_message_flags::{FlagOp, MessageFlag},
     },
+    narrow::{NameOrId, Narrow, NarrowBuilder, NarrowKind, NarrowNegation},
     Client,
 };
 
@@ -30,16 +38,18 @@ async fn main() {
         .init();
 
     // make the client
-    let client = Client::new(ClientConfig {
-        user_agent: UserAgent::new("client_name", "version"),
-        api_key: ApiKey::new(api_key),
-        email,
-        server_address,
-        messages: MessagesConfig {
-            read_by_sender: true,
-        },
-        server_settings_cache_interval: None,
-    })
+    let client = Client::new(
+        ClientConfig::builder()
+            .user_agent(UserAgent::new("client_name", "version"))
+            .api_key(ApiKey::new(api_key))
+            .email(email)
+            .server_address(server_address)
+            .messages(MessagesConfig {
+                read_by_sender: true,
+            })
+            .build()
+            .unwrap(),
+    )
     .await
     .unwrap();
 
@@ -49,6 +59,9 @@ async fn main() {
 
     // ok now run things
     send_message(&client, &uuid, "hello world!".into()).await;
+    send_to_missing_channel(&client, &uuid).await;
+    send_direct_message(&client, &uuid).await;
+    send_legacy_stream_message(&client, &uuid).await;
     file_upload(&client, &uuid).await;
     edit_message(&client, &uuid).await;
     delete_message(&client, &uuid).await;
@@ -56,6 +69,17 @@ async fn main() {
     remove_emoji_reaction(&client, &uuid).await;
     fetch_message(&client, &uuid).await;
     render_message(&client).await;
+    move_topic(&client, &uuid).await;
+    resolve_topic(&client, &uuid).await;
+    mark_as_read(&client, &uuid).await;
+    search_messages(&client, &uuid).await;
+    messages_around_anchor(&client, &uuid).await;
+    all_messages(&client, &uuid).await;
+    star_topic(&client, &uuid).await;
+    mark_unread_from(&client, &uuid).await;
+    messages_by_ids(&client, &uuid).await;
+    delete_topic(&client, &uuid).await;
+    attachments(&client, &uuid).await;
 }
 
 #[tracing::instrument(skip_all)]
@@ -76,6 +100,27 @@ async fn send_message(client: &Client, uuid: &Uuid, msg: String) -> u64 {
     resp.id
 }
 
+#[tracing::instrument(skip_all)]
+async fn send_direct_message(client: &Client, uuid: &Uuid) {
+    let me = client.get_own_user().await.unwrap();
+
+    // dm ourselves by id, then by email. both should work
+    for to in [
+        DirectMessageTarget::Ids(vec![me.user_id]),
+        DirectMessageTarget::Emails(vec![me.email.clone()]),
+    ] {
+        client
+            .send_message(&Message::Direct {
+                to,
+                content: format!("talking to myself (`{uuid}`)"),
+                queue_id: "".into(),
+                local_id: "".into(),
+            })
+            .await
+            .unwrap();
+    }
+}
+
 #[tracing::instrument(skip_all)]
 async fn file_upload(client: &Client, uuid: &Uuid) {
     // make a file and write stuff to it
@@ -122,6 +167,79 @@ async fn file_upload(client: &Client, uuid: &Uuid) {
     tracing::info!("assertions passed! :D");
 }
 
+#[tracing::instrument(skip_all)]
+async fn send_to_missing_channel(client: &Client, uuid: &Uuid) {
+    let err = client
+        .send_message(&Message::Channel {
+            to: ChannelMessageTarget::Name(format!("nowhere {uuid}")),
+            content: "hello? anyone?".into(),
+            topic: "greetings".into(),
+            queue_id: "".into(),
+            local_id: "".into(),
+        })
+        .await
+        .unwrap_err();
+
+    // we should know exactly why it failed
+    assert!(matches!(
+        err,
+        ZulipError::MessageError(MessageError::SendFailed {
+            code: ZulipErrorCode::StreamDoesNotExist,
+            ..
+        })
+    ));
+
+    // empty and huge messages shouldn't even make it to the server
+    for (content, too_long) in [(" \n ".to_string(), false), ("a".repeat(10_001), true)] {
+        let err = client
+            .send_message(&Message::Channel {
+                to: ChannelMessageTarget::Name("general".into()),
+                content,
+                topic: "greetings".into(),
+                queue_id: "".into(),
+                local_id: "".into(),
+            })
+            .await
+            .unwrap_err();
+
+        match err {
+            ZulipError::MessageError(MessageError::ContentTooLong { length, max }) => {
+                assert!(too_long);
+                assert_eq!(length, 10_001);
+                assert!(max < length);
+            }
+            ZulipError::MessageError(MessageError::EmptyContent) => assert!(!too_long),
+            err => panic!("{err}"),
+        }
+    }
+
+    tracing::info!("assertions passed! :D");
+}
+
+#[tracing::instrument(skip_all)]
+async fn send_legacy_stream_message(client: &Client, uuid: &Uuid) {
+    // the old `stream` type should still land in the channel
+    let resp = client
+        .send_message(&Message::Stream {
+            to: ChannelMessageTarget::Name("general".into()),
+            content: format!("sent the old way! (`{uuid}`)"),
+            topic: "greetings".into(),
+            queue_id: "".into(),
+            local_id: "".into(),
+        })
+        .await
+        .unwrap();
+
+    let msg = client
+        .fetch_single_message(resp.id, ContentFormat::RawMarkdown, false)
+        .await
+        .unwrap()
+        .message;
+    assert!(msg.stream_id.is_some());
+
+    tracing::info!("assertions passed! :D");
+}
+
 #[tracing::instrument(skip_all)]
 async fn edit_message(client: &Client, uuid: &Uuid) {
     // let's send another message, then edit it
@@ -134,6 +252,7 @@ async fn edit_message(client: &Client, uuid: &Uuid) {
         send_notification_to_new_thread: Some(true),
         content: Some(format!("edited baby! {uuid}")),
         stream_id: None,
+        propagate_mode: None,
     };
 
     client.edit_message(edited_message).await.unwrap();
@@ -180,6 +299,25 @@ async fn add_emoji_reaction(client: &Client, uuid: &Uuid) {
         .await
         .unwrap();
 
+    // names with spaces get fixed up...
+    client
+        .add_emoji_reaction(msg_id, EmojiSelector::new_from_name("thumbs up"))
+        .await
+        .unwrap();
+
+    // ...but junk never reaches the server
+    let err = client
+        .add_emoji_reaction(msg_id, EmojiSelector::new_from_name("Not An Emoji!"))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::MessageError(MessageError::InvalidEmojiName { .. })
+        ),
+        "{err}"
+    );
+
     tracing::info!("all done! :D");
 }
 
@@ -205,10 +343,16 @@ async fn remove_emoji_reaction(client: &Client, uuid: &Uuid) {
         .unwrap();
 
     // uhh didn't mean to send a heart and it looks weird. better remove it
-    client
+    assert!(client
+        .remove_emoji_reaction(msg_id, selector.clone())
+        .await
+        .unwrap());
+
+    // it's already gone, so there's nothing left to remove
+    assert!(!client
         .remove_emoji_reaction(msg_id, selector)
         .await
-        .unwrap();
+        .unwrap());
 }
 
 #[tracing::instrument(skip_all)]
@@ -226,18 +370,50 @@ async fn fetch_message(client: &Client, uuid: &Uuid) {
 
     // grab its info
     let msg = client
-        .fetch_single_message(msg_id, false)
+        .fetch_single_message(msg_id, ContentFormat::RawMarkdown, false)
         .await
         .unwrap()
         .message;
 
     // check its contents and reaction
     assert_eq!(msg.content, format!("{} (`{uuid}`)", MSG_CONTENT));
+    assert!(
+        msg.flags.iter().any(|f| f == "read"),
+        "`read_by_sender` is on, so we've read our own message"
+    );
     assert_eq!(
         msg.reactions.unwrap().first().unwrap().emoji_name,
         CRAB_EMOJI
     );
 
+    // or just the reactions
+    let reactions = client.get_message_reactions(msg_id).await.unwrap();
+    assert_eq!(
+        reactions
+            .iter()
+            .map(|r| r.emoji_name.as_str())
+            .collect::<Vec<_>>(),
+        [CRAB_EMOJI]
+    );
+
+    // even when it's rendered, we can still see the markdown
+    let rendered = client
+        .fetch_single_message(msg_id, ContentFormat::RenderedHtml, true)
+        .await
+        .unwrap()
+        .message;
+    assert_ne!(rendered.content, msg.content);
+    assert_eq!(rendered.raw_content.as_deref(), Some(msg.content.as_str()));
+
+    // or just the markdown on its own
+    let markdown = client.get_message_markdown(msg_id).await.unwrap();
+    assert_eq!(markdown, format!("{} (`{uuid}`)", MSG_CONTENT));
+
+    // or both at once
+    let contents = client.get_message_contents(msg_id).await.unwrap();
+    assert_eq!(contents.content, rendered.content);
+    assert_eq!(contents.raw_content, markdown);
+
     tracing::info!("assertions passed! :D");
 }
 
@@ -260,3 +436,489 @@ async fn render_message(client: &Client) {
 
     tracing::info!("assertions passed! :D");
 }
+
+#[tracing::instrument(skip_all)]
+async fn move_topic(client: &Client, uuid: &Uuid) {
+    // make a topic with a couple messages in it
+    let topic = format!("move me {uuid}");
+    let mut msg_ids = Vec::new();
+    for content in ["first!", "second!"] {
+        let msg_id = client
+            .send_message(&Message::Channel {
+                to: ChannelMessageTarget::Name("general".into()),
+                content: content.into(),
+                topic: topic.clone(),
+                queue_id: "".into(),
+                local_id: "".into(),
+            })
+            .await
+            .unwrap()
+            .id;
+        msg_ids.push(msg_id);
+    }
+
+    let stream_id = client
+        .fetch_single_message(msg_ids[0], ContentFormat::RawMarkdown, false)
+        .await
+        .unwrap()
+        .message
+        .stream_id
+        .unwrap();
+
+    // rename it in place
+    let new_topic = format!("moved {uuid}");
+    client
+        .move_topic(stream_id, &topic, stream_id, Some(new_topic.clone()), false)
+        .await
+        .unwrap();
+
+    // both messages should've come along
+    for msg_id in msg_ids {
+        let msg = client
+            .fetch_single_message(msg_id, ContentFormat::RawMarkdown, false)
+            .await
+            .unwrap()
+            .message;
+        assert_eq!(msg.subject, new_topic);
+    }
+
+    // and the old topic should be gone
+    client
+        .move_topic(stream_id, &topic, stream_id, None, false)
+        .await
+        .unwrap_err();
+
+    tracing::info!("assertions passed! :D");
+}
+
+#[tracing::instrument(skip_all)]
+async fn resolve_topic(client: &Client, uuid: &Uuid) {
+    let topic = format!("resolve me {uuid}");
+    let msg_id = client
+        .send_message(&Message::Channel {
+            to: ChannelMessageTarget::Name("general".into()),
+            content: "is this fixed yet?".into(),
+            topic: topic.clone(),
+            queue_id: "".into(),
+            local_id: "".into(),
+        })
+        .await
+        .unwrap()
+        .id;
+    let stream_id = client
+        .fetch_single_message(msg_id, ContentFormat::RawMarkdown, false)
+        .await
+        .unwrap()
+        .message
+        .stream_id
+        .unwrap();
+
+    // it's not resolved yet, so unresolving does nothing
+    let resp = client.unresolve_topic(stream_id, &topic).await.unwrap();
+    assert!(resp.is_none());
+
+    // resolve it once...
+    let resp = client.resolve_topic(stream_id, &topic).await.unwrap();
+    assert!(resp.is_some());
+
+    let resolved = format!("{RESOLVED_TOPIC_PREFIX}{topic}");
+    let msg = client
+        .fetch_single_message(msg_id, ContentFormat::RawMarkdown, false)
+        .await
+        .unwrap()
+        .message;
+    assert_eq!(msg.subject, resolved);
+
+    // ...but not twice
+    let resp = client.resolve_topic(stream_id, &resolved).await.unwrap();
+    assert!(resp.is_none());
+
+    // now put it back
+    client
+        .unresolve_topic(stream_id, &resolved)
+        .await
+        .unwrap()
+        .unwrap();
+    let msg = client
+        .fetch_single_message(msg_id, ContentFormat::RawMarkdown, false)
+        .await
+        .unwrap()
+        .message;
+    assert_eq!(msg.subject, topic);
+
+    tracing::info!("assertions passed! :D");
+}
+
+#[tracing::instrument(skip_all)]
+async fn mark_as_read(client: &Client, uuid: &Uuid) {
+    let msg_id = send_message(client, uuid, "`mark_as_read`.".into()).await;
+    let stream_id = client
+        .fetch_single_message(msg_id, ContentFormat::RawMarkdown, false)
+        .await
+        .unwrap()
+        .message
+        .stream_id
+        .unwrap();
+
+    client
+        .mark_topic_as_read(stream_id, "greetings")
+        .await
+        .unwrap();
+    client.mark_stream_as_read(stream_id).await.unwrap();
+
+    // keep going until the server finishes
+    while !client.mark_all_as_read().await.unwrap() {}
+
+    // a channel that doesn't exist can't be read
+    client.mark_stream_as_read(u64::MAX).await.unwrap_err();
+
+    tracing::info!("assertions passed! :D");
+}
+
+#[tracing::instrument(skip_all)]
+async fn search_messages(client: &Client, uuid: &Uuid) {
+    send_message(client, uuid, "`search_messages`.".into()).await;
+
+    // every message we've sent has the uuid in it
+    let resp = client
+        .get_messages(GetMessages {
+            anchor: Anchor::Newest,
+            num_before: 10,
+            narrow: NarrowBuilder::new().keyword(uuid.to_string()).build(),
+            ..GetMessages::default()
+        })
+        .await
+        .unwrap();
+    assert!(!resp.messages.is_empty());
+
+    // and the server should point it out
+    for msg in resp.messages {
+        let highlighted = msg.match_content.unwrap();
+        assert!(highlighted.contains("highlight"), "{highlighted}");
+        assert!(msg.match_topic.is_some());
+    }
+
+    tracing::info!("assertions passed! :D");
+}
+
+#[tracing::instrument(skip_all)]
+async fn messages_around_anchor(client: &Client, uuid: &Uuid) {
+    // fill up a topic, then grab the context around the middle message
+    let topic = format!("anchors {uuid}");
+    let mut msg_ids = Vec::new();
+    for i in 0..11 {
+        let msg_id = client
+            .send_message(&Message::Channel {
+                to: ChannelMessageTarget::Name("general".into()),
+                content: format!("message {i}"),
+                topic: topic.clone(),
+                queue_id: "".into(),
+                local_id: "".into(),
+            })
+            .await
+            .unwrap()
+            .id;
+        msg_ids.push(msg_id);
+    }
+    let middle = msg_ids[5];
+
+    let query = GetMessages {
+        anchor: Anchor::MessageId(middle),
+        num_before: 5,
+        num_after: 5,
+        narrow: NarrowBuilder::new()
+            .channel(NameOrId::Name("general".into()))
+            .topic(&topic)
+            .build(),
+        ..GetMessages::default()
+    };
+
+    let resp = client.get_messages(query.clone()).await.unwrap();
+    assert_eq!(resp.anchor, middle);
+    assert_eq!(resp.found_anchor, Some(true));
+    assert_eq!(
+        resp.messages.iter().map(|m| m.id).collect::<Vec<_>>(),
+        msg_ids
+    );
+
+    // now without the anchor itself
+    let resp = client
+        .get_messages(GetMessages {
+            include_anchor: false,
+            ..query
+        })
+        .await
+        .unwrap();
+    assert_eq!(resp.messages.len(), 10);
+    assert!(resp.messages.iter().all(|m| m.id != middle));
+
+    // the server should say where the first unread message is
+    let resp = client
+        .get_messages(GetMessages {
+            anchor: Anchor::FirstUnread,
+            num_before: 0,
+            num_after: 1,
+            ..GetMessages::default()
+        })
+        .await
+        .unwrap();
+    assert_ne!(resp.anchor, 0);
+
+    tracing::info!("assertions passed! :D");
+}
+
+#[tracing::instrument(skip_all)]
+async fn all_messages(client: &Client, uuid: &Uuid) {
+    // fill a topic with a bit more than two pages
+    let topic = format!("all messages {uuid}");
+    let mut msg_ids = Vec::new();
+    for i in 0..7 {
+        let msg_id = client
+            .send_message(&Message::Channel {
+                to: ChannelMessageTarget::Name("general".into()),
+                content: format!("message {i}"),
+                topic: topic.clone(),
+                queue_id: "".into(),
+                local_id: "".into(),
+            })
+            .await
+            .unwrap()
+            .id;
+        msg_ids.push(msg_id);
+    }
+
+    let narrow = NarrowBuilder::new()
+        .channel(NameOrId::Name("general".into()))
+        .topic(&topic)
+        .build();
+    let all = client
+        .get_all_messages(
+            narrow,
+            GetAllMessages {
+                page_size: 3,
+                ..GetAllMessages::default()
+            },
+        )
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+
+    // everything, newest first
+    msg_ids.reverse();
+    assert_eq!(all.iter().map(|m| m.id).collect::<Vec<_>>(), msg_ids);
+
+    tracing::info!("assertions passed! :D");
+}
+
+#[tracing::instrument(skip_all)]
+async fn star_topic(client: &Client, uuid: &Uuid) {
+    let topic = format!("starred {uuid}");
+    for i in 0..3 {
+        client
+            .send_message(&Message::Channel {
+                to: ChannelMessageTarget::Name("general".into()),
+                content: format!("star me {i}"),
+                topic: topic.clone(),
+                queue_id: "".into(),
+                local_id: "".into(),
+            })
+            .await
+            .unwrap();
+    }
+    let narrow = NarrowBuilder::new()
+        .channel(NameOrId::Name("general".into()))
+        .topic(&topic)
+        .build();
+
+    // star the whole topic at once
+    let resp = client
+        .update_message_flags_for_narrow(
+            narrow.clone(),
+            FlagOp::Add,
+            MessageFlag::Starred,
+            Anchor::Oldest,
+            0,
+            100,
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.processed_count, 3);
+    assert_eq!(resp.updated_count, 3);
+    assert!(resp.found_oldest && resp.found_newest);
+
+    // doing it again shouldn't change anything
+    let resp = client
+        .update_message_flags_for_narrow(
+            narrow,
+            FlagOp::Add,
+            MessageFlag::Starred,
+            Anchor::Oldest,
+            0,
+            100,
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.processed_count, 3);
+    assert_eq!(resp.updated_count, 0);
+
+    tracing::info!("assertions passed! :D");
+}
+
+#[tracing::instrument(skip_all)]
+async fn messages_by_ids(client: &Client, uuid: &Uuid) {
+    let mut ids = Vec::new();
+    for i in 0..3 {
+        ids.push(send_message(client, uuid, format!("grab me by my ID! ({i})")).await);
+    }
+
+    // nobody can see a deleted message
+    client.delete_message(ids[1]).await.unwrap();
+
+    let messages = client.get_messages_by_ids(&ids).await.unwrap();
+    let found = messages
+        .iter()
+        .map(|msg| msg.as_ref().map(|msg| msg.id))
+        .collect::<Vec<_>>();
+    assert_eq!(found, vec![Some(ids[0]), None, Some(ids[2])]);
+}
+
+async fn mark_unread_from(client: &Client, uuid: &Uuid) {
+    let topic = format!("unread from here {uuid}");
+    let mut ids = Vec::new();
+    for i in 0..3 {
+        let resp = client
+            .send_message(&Message::Channel {
+                to: ChannelMessageTarget::Name("general".into()),
+                content: format!("message {i}"),
+                topic: topic.clone(),
+                queue_id: "".into(),
+                local_id: "".into(),
+            })
+            .await
+            .unwrap();
+        ids.push(resp.id);
+    }
+
+    // the message's conversation is its channel and topic
+    let second = client
+        .fetch_single_message(ids[1], ContentFormat::RawMarkdown, true)
+        .await
+        .unwrap()
+        .message;
+    assert_eq!(
+        second.conversation(),
+        vec![Narrow::new(
+            NarrowKind::ChannelWithTopic {
+                channel: NameOrId::Id(second.stream_id.unwrap()),
+                topic: NameOrId::Name(topic.clone()),
+            },
+            NarrowNegation::Normal,
+        )]
+    );
+
+    // `read_by_sender` is on, so all three start out read. only the last two
+    // should become unread
+    assert_eq!(client.mark_unread_from(ids[1]).await.unwrap(), 2);
+    for (id, read) in ids.into_iter().zip([true, false, false]) {
+        let msg = client
+            .fetch_single_message(id, ContentFormat::RawMarkdown, true)
+            .await
+            .unwrap()
+            .message;
+        assert_eq!(msg.flags.iter().any(|flag| flag == "read"), read);
+    }
+
+    tracing::info!("assertions passed! :D");
+}
+
+#[tracing::instrument(skip_all)]
+async fn delete_topic(client: &Client, uuid: &Uuid) {
+    tracing::info!("this check might break if you don't have admin perms in this server. so make sure u have them! :D");
+
+    let topic = format!("delete me {uuid}");
+    let msg_id = client
+        .send_message(&Message::Channel {
+            to: ChannelMessageTarget::Name("general".into()),
+            content: "this whole topic should be deleted...".into(),
+            topic: topic.clone(),
+            queue_id: "".into(),
+            local_id: "".into(),
+        })
+        .await
+        .unwrap()
+        .id;
+    let stream_id = client
+        .fetch_single_message(msg_id, ContentFormat::RawMarkdown, false)
+        .await
+        .unwrap()
+        .message
+        .stream_id
+        .unwrap();
+
+    // keep going until the server finishes
+    while !client.delete_topic(stream_id, &topic).await.unwrap() {}
+
+    client
+        .fetch_single_message(msg_id, ContentFormat::RawMarkdown, false)
+        .await
+        .unwrap_err();
+
+    tracing::info!("assertions passed! :D");
+}
+
+#[tracing::instrument(skip_all)]
+async fn attachments(client: &Client, uuid: &Uuid) {
+    // upload a file, but never use it
+    let temp_file = NamedTempFile::new().unwrap();
+    tokio::fs::write(temp_file.path(), format!("orphaned file {uuid}"))
+        .await
+        .unwrap();
+    let up_resp = client.upload_file(temp_file.path()).await.unwrap();
+
+    // it should show up in our uploads...
+    let find_it = |attachments: Vec<Attachment>| {
+        attachments
+            .into_iter()
+            .find(|a| up_resp.url.ends_with(&a.path_id))
+    };
+    let attachment = find_it(client.get_attachments().await.unwrap()).unwrap();
+    assert!(attachment.messages.is_empty());
+
+    // ...until we clean it up
+    client.delete_attachment(attachment.id).await.unwrap();
+    assert!(find_it(client.get_attachments().await.unwrap()).is_none());
+    client.delete_attachment(attachment.id).await.unwrap_err();
+
+    // when a send fails after its upload, we should hear about the orphan
+    let msg = Message::Channel {
+        to: ChannelMessageTarget::Name(format!("nowhere {uuid}")),
+        content: "here's a file!".into(),
+        topic: "files".into(),
+        queue_id: "".into(),
+        local_id: "".into(),
+    };
+    let resp = client
+        .reupload_and_resend(temp_file.path(), &msg, false)
+        .await
+        .unwrap();
+    assert!(resp.sent.is_err());
+    let orphan = resp.orphaned_path_id().unwrap();
+    let attachment = client
+        .get_attachments()
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|a| a.path_id == orphan)
+        .unwrap();
+    client.delete_attachment(attachment.id).await.unwrap();
+
+    // ...or have it cleaned up for us
+    let resp = client
+        .reupload_and_resend(temp_file.path(), &msg, true)
+        .await
+        .unwrap();
+    assert!(resp.orphan_deleted);
+    assert_eq!(resp.orphaned_path_id(), None);
+
+    tracing::info!("assertions passed! :D");
+}
@@ -1,9 +1,11 @@
+use std::time::Duration;
+
 use reqwest::Url;
 use tracing_subscriber::EnvFilter;
 
 use libzulip::{
     build_info,
-    config::{ApiKey, ClientConfig, MessagesConfig, UserAgent},
+    config::{ApiKey, ClientConfig, MessagesConfig, RootCertificate, UserAgent},
     Client,
 };
 
@@ -22,25 +24,78 @@ async fn main() {
         )))
         .init();
 
+    // trusting an extra CA shouldn't get in the way
+    let with_ca = ClientConfig::builder()
+        .user_agent(UserAgent::new("client_name", "version"))
+        .api_key(ApiKey::new(&api_key))
+        .email(&email)
+        .server_address(server_address.clone())
+        .root_certificate(RootCertificate::PemFile(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/examples/certs/test_ca.pem").into(),
+        ));
+    Client::new(with_ca.build().unwrap()).await.unwrap();
+
     // make the client
-    let mut client = Client::new(ClientConfig {
-        user_agent: UserAgent::new("client_name", "version"),
-        api_key: ApiKey::new(api_key),
-        email,
-        server_address,
-        messages: MessagesConfig {
-            read_by_sender: true,
-        },
-        server_settings_cache_interval: None,
-    })
+    let client = Client::new(
+        ClientConfig::builder()
+            .user_agent(UserAgent::new("client_name", "version"))
+            .api_key(ApiKey::new(&api_key))
+            .email(&email)
+            .server_address(server_address)
+            .messages(MessagesConfig {
+                read_by_sender: true,
+            })
+            .build()
+            .unwrap(),
+    )
     .await
     .unwrap();
 
+    // the real server should be up
+    client.check_connection().await.unwrap();
+
+    // grab everything we'd need at startup
+    let bootstrap = client.bootstrap().await.unwrap();
+    assert_eq!(bootstrap.own_user.email, client.conf.email);
+    assert!(bootstrap.server_settings.zulip_feature_level > 0);
+    assert!(bootstrap
+        .subscriptions
+        .iter()
+        .all(|sub| sub.color.starts_with('#')));
+
     // grab the settings!
     let resp = client.server_settings_cache.get().await.unwrap();
     dbg!(resp);
 
+    // turn off caching, then grab them again
+    let cache = &client.server_settings_cache;
+    cache.set_refresh_interval(Duration::ZERO).await;
+    assert_eq!(cache.refresh_interval().await, Duration::ZERO);
+    cache.get().await.unwrap();
+
     // and linkifiers...
     let resp_linkifiers = client.linkifiers().await.unwrap();
     dbg!(resp_linkifiers);
+
+    // make, change, then remove a linkifier (you'll need admin perms for this)
+    let linkifier = client
+        .create_linkifier(
+            r"libz-(?P<id>[0-9]+)",
+            "https://github.com/onkoe/unnamed_zulip_client/issues/{id}",
+        )
+        .await
+        .unwrap();
+    client
+        .update_linkifier(
+            linkifier.id,
+            r"libzulip-(?P<id>[0-9]+)",
+            "https://github.com/onkoe/unnamed_zulip_client/issues/{id}",
+        )
+        .await
+        .unwrap();
+    client.delete_linkifier(linkifier.id).await.unwrap();
+
+    // and custom emoji!
+    let resp_emoji = client.get_realm_emoji().await.unwrap();
+    dbg!(resp_emoji);
 }
@@ -0,0 +1,89 @@
+use reqwest::Url;
+use tracing_subscriber::EnvFilter;
+
+use libzulip::{
+    build_info,
+    config::{ApiKey, ClientConfig, UserAgent},
+    error::{StreamError, ZulipError},
+    streams::get_streams::GetStreamsOptions,
+    Client,
+};
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    // grab auth stuff from env
+    let email = std::env::var("ZULIP_EMAIL").unwrap();
+    let api_key = std::env::var("ZULIP_PERSONAL_KEY").unwrap();
+    let server_address = Url::try_from("https://libz.zulipchat.com").unwrap(); // change if u want
+
+    // setup logging
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::new(format!(
+            "info,{}=trace",
+            build_info::PKG_NAME
+        )))
+        .init();
+
+    // make the client
+    let client = Client::new(
+        ClientConfig::builder()
+            .user_agent(UserAgent::new("client_name", "version"))
+            .api_key(ApiKey::new(api_key))
+            .email(email)
+            .server_address(server_address)
+            .build()
+            .unwrap(),
+    )
+    .await
+    .unwrap();
+
+    // look up the general channel by name, then by id
+    let general_id = client.get_stream_id("general").await.unwrap();
+    let general = client.get_stream(general_id).await.unwrap();
+    assert_eq!(general.stream_id, general_id);
+    assert_eq!(general.name, "general");
+
+    // this one shouldn't exist
+    let err = client
+        .get_stream_id("this channel doesn't exist")
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ZulipError::StreamError(StreamError::StreamNotFound { .. })
+    ));
+    client.get_stream(u64::MAX).await.unwrap_err();
+
+    // general should have an address for posting by email
+    let address = client.get_stream_email_address(general_id).await.unwrap();
+    assert!(address.contains('@'), "{address}");
+
+    // general is public, so it's listed by default
+    let streams = client
+        .get_streams(GetStreamsOptions::default())
+        .await
+        .unwrap();
+    assert!(streams.contains(&general));
+
+    // but not when we only ask for the defaults... unless it's a default
+    let defaults = client
+        .get_streams(GetStreamsOptions {
+            include_public: false,
+            include_subscribed: false,
+            include_default: true,
+            ..GetStreamsOptions::default()
+        })
+        .await
+        .unwrap();
+    assert!(defaults.len() <= streams.len());
+
+    // we're in general, so we count as one of its subscribers
+    let subs = client.get_subscribed_streams(true).await.unwrap();
+    let general_sub = subs
+        .iter()
+        .find(|sub| sub.stream.stream_id == general_id)
+        .unwrap();
+    assert!(general_sub.subscriber_count().unwrap() >= 1);
+
+    tracing::info!("assertions passed! :D");
+}
@@ -0,0 +1,291 @@
+use reqwest::Url;
+use tracing_subscriber::EnvFilter;
+
+use libzulip::{
+    build_info,
+    config::{ApiKey, ClientConfig, Credentials, MessagesConfig, UserAgent},
+    error::{FileError, MessageError, UserError, ZulipError},
+    messages::{
+        emoji_reaction::ReactionType,
+        fetch_single_message::ContentFormat,
+        send_message::{ChannelMessageTarget, Message},
+    },
+    users::{
+        bots::{BotType, CreateBot},
+        presence::PresenceStatus,
+        update_status::StatusUpdate,
+    },
+    Client,
+};
+use uuid::Uuid;
+
+/// A 1x1 PNG, for testing uploads.
+const TINY_PNG: [u8; 70] = [
+    0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44, 0x52,
+    0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1f, 0x15, 0xc4,
+    0x89, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9c, 0x63, 0xf8, 0xdf, 0xc0, 0xf0,
+    0x1f, 0x00, 0x06, 0x80, 0x02, 0x7f, 0x10, 0x4c, 0x1b, 0xe1, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45,
+    0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+];
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    // grab auth stuff from env
+    let email = std::env::var("ZULIP_EMAIL").unwrap();
+    let api_key = std::env::var("ZULIP_PERSONAL_KEY").unwrap();
+    let server_address = Url::try_from("https://libz.zulipchat.com").unwrap(); // change if u want
+
+    // setup logging
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::new(format!(
+            "info,{}=trace",
+            build_info::PKG_NAME
+        )))
+        .init();
+
+    // make the client
+    let client = Client::new(
+        ClientConfig::builder()
+            .user_agent(UserAgent::new("client_name", "version"))
+            .api_key(ApiKey::new(api_key))
+            .email(email)
+            .server_address(server_address)
+            .messages(MessagesConfig {
+                read_by_sender: true,
+            })
+            .build()
+            .unwrap(),
+    )
+    .await
+    .unwrap();
+
+    // grab everyone in the org
+    let users = client.get_users(false, false).await.unwrap();
+    assert!(
+        !users.is_empty(),
+        "there should always be at least one user"
+    );
+
+    // there's always a notification bot :D
+    assert!(users.iter().any(|u| u.is_bot), "zulip always has some bots");
+
+    // look one of them up by id and email
+    let first = users.first().unwrap();
+    let by_id = client.get_user(first.user_id, false).await.unwrap();
+    let by_email = client.get_user_by_email(&first.email, false).await.unwrap();
+    assert_eq!(by_id, by_email, "both lookups should find the same user");
+
+    // or grab a few at once
+    let ids: Vec<u64> = users.iter().take(3).map(|u| u.user_id).collect();
+    let by_ids = client.get_users_by_ids(&ids).await.unwrap();
+    assert_eq!(by_ids.len(), ids.len());
+    for user in users.iter().take(3) {
+        assert_eq!(by_ids[&user.user_id].email, user.email);
+    }
+
+    // every profile value should belong to one of the org's fields
+    let fields = client.get_custom_profile_fields().await.unwrap();
+    let with_profile = client.get_user(first.user_id, true).await.unwrap();
+    for field_id in with_profile.profile_data.unwrap().keys() {
+        assert!(fields.iter().any(|f| f.id.to_string() == *field_id));
+    }
+
+    // nobody should have this id...
+    let err = client.get_user(u64::MAX, false).await.unwrap_err();
+    assert!(matches!(
+        err,
+        ZulipError::UserError(UserError::UserNotFound { .. })
+    ));
+
+    // we should be in that list too
+    let me = client.get_own_user().await.unwrap();
+    assert!(users.iter().any(|u| u.user_id == me.user_id));
+    assert_eq!(me, client.refresh_own_user().await.unwrap());
+
+    // say we're here, then check that the server noticed
+    let ping = client
+        .update_presence(PresenceStatus::Active, true, true)
+        .await
+        .unwrap();
+    assert!(
+        ping.presences.is_none(),
+        "`ping_only` skips other presences"
+    );
+
+    let snapshot = client
+        .update_presence(PresenceStatus::Active, false, false)
+        .await
+        .unwrap();
+    assert!(snapshot.presences.unwrap().contains_key(&me.user_id));
+
+    let presence = client
+        .get_user_presence(me.user_id.to_string())
+        .await
+        .unwrap();
+    assert_eq!(
+        presence.get("aggregated").unwrap().status,
+        PresenceStatus::Active
+    );
+
+    // set a status with an emoji, then clear it
+    client
+        .update_status(StatusUpdate {
+            status_text: Some("testing libzulip".into()),
+            emoji_name: Some("crab".into()),
+            reaction_type: Some(ReactionType::UnicodeEmoji),
+            emoji_code: Some("1f980".into()),
+            ..StatusUpdate::default()
+        })
+        .await
+        .unwrap();
+    client.update_status(StatusUpdate::cleared()).await.unwrap();
+
+    // give ourselves a new avatar
+    let avatar = tempfile::Builder::new().suffix(".png").tempfile().unwrap();
+    tokio::fs::write(avatar.path(), TINY_PNG).await.unwrap();
+    let avatar_url = client.upload_own_avatar(avatar.path()).await.unwrap();
+    assert_eq!(client.get_avatar_url(me.user_id).await.unwrap(), avatar_url);
+
+    // text files aren't images...
+    let not_image = tempfile::Builder::new().suffix(".txt").tempfile().unwrap();
+    let err = client
+        .upload_own_avatar(not_image.path())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ZulipError::FileError(FileError::UnsupportedImageFormat(_))
+    ));
+
+    // ...and huge images are too big to send
+    if let Some(max_mib) = client
+        .server_settings_cache
+        .get()
+        .await
+        .unwrap()
+        .max_file_upload_size_mib
+    {
+        let huge = tempfile::Builder::new().suffix(".png").tempfile().unwrap();
+        huge.as_file().set_len(max_mib * 1024 * 1024 + 1).unwrap();
+        let err = client.upload_own_avatar(huge.path()).await.unwrap_err();
+        assert!(matches!(
+            err,
+            ZulipError::FileError(FileError::FileTooLarge { .. })
+        ));
+    }
+
+    // make a bot, then log in as it
+    let short_name = format!("libz-{}", &Uuid::new_v4().simple().to_string()[..8]);
+    let bot = client
+        .create_bot(CreateBot::new(
+            "libzulip test bot",
+            &short_name,
+            BotType::Generic,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        client.get_bot_api_key(bot.user_id).await.unwrap(),
+        bot.api_key
+    );
+
+    let bot_client = Client::new(
+        ClientConfig::builder()
+            .user_agent(UserAgent::new("client_name", "version"))
+            .api_key(bot.api_key.clone())
+            .email(&bot.email)
+            .server_address(client.conf.server_address.clone())
+            .build()
+            .unwrap(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        bot_client.get_own_user().await.unwrap().user_id,
+        bot.user_id
+    );
+
+    // the bot isn't an admin, so it can't delete our messages
+    assert!(!bot_client.can_delete_any_message().await.unwrap());
+    let msg_id = client
+        .send_message(&Message::Channel {
+            to: ChannelMessageTarget::Name("general".into()),
+            content: "bots can't delete this!".into(),
+            topic: short_name.clone(),
+            queue_id: "".into(),
+            local_id: "".into(),
+        })
+        .await
+        .unwrap()
+        .id;
+    let err = bot_client.delete_message(msg_id).await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::MessageError(MessageError::DeletionNotAllowed { .. })
+        ),
+        "{err}"
+    );
+    client.delete_message(msg_id).await.unwrap();
+
+    // we can post as the bot without its own client, too
+    let bot_credentials = Credentials {
+        email: bot.email.clone(),
+        api_key: bot.api_key.clone(),
+    };
+    let msg_id = client
+        .send_incoming_webhook(
+            &bot_credentials,
+            "posted with the bot's key!",
+            ChannelMessageTarget::Name("general".into()),
+            &short_name,
+        )
+        .await
+        .unwrap()
+        .id;
+    let msg = client
+        .fetch_single_message(msg_id, ContentFormat::RawMarkdown, false)
+        .await
+        .unwrap()
+        .message;
+    assert_eq!(msg.sender_id, bot.user_id);
+
+    // a new key should lock out the old one
+    let new_key = client.regenerate_bot_api_key(bot.user_id).await.unwrap();
+    assert_ne!(new_key, bot.api_key);
+    bot_client.refresh_own_user().await.unwrap_err();
+
+    // we don't own ourselves
+    let err = client.get_bot_api_key(me.user_id).await.unwrap_err();
+    assert!(matches!(
+        err,
+        ZulipError::UserError(UserError::BotNotFound { .. })
+    ));
+
+    // put ourselves in a group, then swap in the bot
+    let group_id = client
+        .create_user_group(short_name.as_str(), "testing libzulip", &[me.user_id])
+        .await
+        .unwrap();
+    client
+        .update_user_group_members(group_id, &[bot.user_id], &[me.user_id])
+        .await
+        .unwrap();
+    client
+        .update_user_group(group_id, None, Some("bots only!".into()))
+        .await
+        .unwrap();
+
+    let group = client
+        .get_user_groups()
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|g| g.id == group_id)
+        .unwrap();
+    assert_eq!(group.members, vec![bot.user_id]);
+    assert_eq!(group.description, "bots only!");
+    assert!(!group.is_system_group);
+
+    tracing::info!("assertions passed! :D");
+}
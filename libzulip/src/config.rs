@@ -1,9 +1,22 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
-use reqwest::Url;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Url,
+};
 use tokio::sync::RwLock;
+use zeroize::Zeroizing;
 
-use crate::build_info;
+use crate::{
+    build_info,
+    error::{AuthError, ConfigError, ResponseError, ZulipError, ZulipErrorCode},
+    Client,
+};
 
 #[derive(Clone, Debug)]
 pub struct ClientConfig {
@@ -16,35 +29,548 @@ pub struct ClientConfig {
     /// when the cache hasn't been updated for >= this duration, it'll be refreshed
     pub server_settings_cache_interval: Option<Arc<RwLock<Duration>>>,
 
+    /// how to retry failed requests. `None` means we never retry
+    pub retry: Option<RetryConfig>,
+
+    /// the max time a whole request can take, from connecting until the
+    /// response body is read. `None` means there's no limit
+    pub timeout: Option<Duration>,
+    /// the max time we'll wait to connect to the server. `None` means there's
+    /// no limit
+    pub connect_timeout: Option<Duration>,
+    /// which proxy requests go through
+    pub proxy: ProxyConfig,
+    /// extra certificate authorities to trust, like a self-hosted server's
+    /// private CA
+    pub root_certificates: Vec<RootCertificate>,
+    /// whether to accept any TLS certificate, even invalid ones. see
+    /// [`ClientConfigBuilder::danger_accept_invalid_certs`] before using this!
+    pub danger_accept_invalid_certs: bool,
+    /// extra headers sent with every request, like a token for an auth
+    /// gateway in front of the server. these don't replace the user agent or
+    /// the API key auth. their values are kept out of `Debug` output
+    pub default_headers: HashMap<String, DefaultHeaderValue>,
+    /// whether to only speak HTTP/1.1, instead of upgrading to HTTP/2 when
+    /// the server can
+    pub http1_only: bool,
+    /// whether plaintext `http://` addresses are allowed. see
+    /// [`ClientConfigBuilder::allow_http`]
+    pub allow_http: bool,
+
     // ok now all the little configs for modules
     pub messages: MessagesConfig,
 }
 
+impl ClientConfig {
+    /// Starts building a `ClientConfig`.
+    ///
+    /// See [`ClientConfigBuilder`] for more info.
+    pub fn builder() -> ClientConfigBuilder {
+        ClientConfigBuilder::default()
+    }
+}
+
+impl ClientConfig {
+    /// Starts building a `ClientConfig` from a `.zuliprc` file, like the one
+    /// you can download from Zulip's settings. `~` is expanded to your home
+    /// directory.
+    ///
+    /// The file's `[api]` section needs `email`, `key`, and `site`. You'll
+    /// still need to set the user agent before building.
+    ///
+    /// ```
+    /// use std::io::Write as _;
+    ///
+    /// use libzulip::{
+    ///     config::{ClientConfig, UserAgent},
+    ///     error::ConfigError,
+    /// };
+    ///
+    /// let mut zuliprc = tempfile::NamedTempFile::new().unwrap();
+    /// write!(
+    ///     zuliprc,
+    ///     "[api]\nemail=bot@chat.example.com\nkey=abc123\nsite=https://chat.example.com\n"
+    /// )
+    /// .unwrap();
+    ///
+    /// let conf = ClientConfig::from_zuliprc(zuliprc.path())
+    ///     .unwrap()
+    ///     .user_agent(UserAgent::new("client_name", "version"))
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(conf.email, "bot@chat.example.com");
+    /// assert_eq!(conf.api_key.get(), "abc123");
+    /// assert_eq!(conf.server_address.as_str(), "https://chat.example.com/");
+    ///
+    /// // without a key, there's nothing to log in with
+    /// let mut keyless = tempfile::NamedTempFile::new().unwrap();
+    /// write!(keyless, "[api]\nemail=bot@chat.example.com\nsite=chat.example.com\n").unwrap();
+    /// let err = ClientConfig::from_zuliprc(keyless.path()).unwrap_err();
+    /// assert!(matches!(err, ConfigError::MissingZuliprcKey { key: "key", .. }), "{err}");
+    /// ```
+    pub fn from_zuliprc<P: AsRef<Path>>(path: P) -> Result<ClientConfigBuilder, ConfigError> {
+        let path = expand_tilde(path.as_ref());
+        let api = read_zuliprc(&path)?;
+
+        let get = |key: &'static str| {
+            api.get(key).ok_or_else(|| ConfigError::MissingZuliprcKey {
+                path: path.display().to_string(),
+                key,
+            })
+        };
+
+        Ok(ClientConfig::builder()
+            .email(get("email")?)
+            .api_key(ApiKey::new(get("key")?))
+            .server_address(parse_site(get("site")?)?))
+    }
+}
+
+impl Client {
+    /// Starts building a `ClientConfig` from environment variables, like the
+    /// official Zulip clients do:
+    ///
+    /// - `ZULIP_EMAIL`: the user's Zulip API email address.
+    /// - `ZULIP_API_KEY`: the user's API key. `ZULIP_PERSONAL_KEY` works,
+    ///   too.
+    /// - `ZULIP_SITE`: the server's address. Without a scheme, `https://` is
+    ///   assumed.
+    ///
+    /// Anything that isn't set comes from a `.zuliprc` file instead (see
+    /// [`ClientConfig::from_zuliprc`]), if there is one. That's the file at
+    /// `ZULIP_CONFIG`, or `~/.zuliprc` otherwise.
+    ///
+    /// You'll still need to set the user agent before building.
+    ///
+    /// ```
+    /// use libzulip::{config::UserAgent, error::ConfigError, Client};
+    ///
+    /// // make sure there's no `~/.zuliprc` to fall back to
+    /// let home = tempfile::tempdir().unwrap();
+    /// std::env::set_var("HOME", home.path());
+    ///
+    /// std::env::set_var("ZULIP_EMAIL", "bot@chat.example.com");
+    /// std::env::set_var("ZULIP_API_KEY", "key");
+    /// std::env::set_var("ZULIP_SITE", "chat.example.com");
+    ///
+    /// let conf = Client::builder_from_env()
+    ///     .unwrap()
+    ///     .user_agent(UserAgent::new("client_name", "version"))
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(conf.email, "bot@chat.example.com");
+    /// assert_eq!(conf.server_address.as_str(), "https://chat.example.com/");
+    ///
+    /// std::env::remove_var("ZULIP_SITE");
+    /// let err = Client::builder_from_env().unwrap_err();
+    /// assert!(matches!(err, ConfigError::MissingEnvVar("ZULIP_SITE")), "{err}");
+    ///
+    /// // a `.zuliprc` can fill in the rest
+    /// std::fs::write(home.path().join(".zuliprc"), "[api]\nsite = chat.example.org\n").unwrap();
+    /// let conf = Client::builder_from_env()
+    ///     .unwrap()
+    ///     .user_agent(UserAgent::new("client_name", "version"))
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(conf.email, "bot@chat.example.com");
+    /// assert_eq!(conf.server_address.as_str(), "https://chat.example.org/");
+    /// ```
+    pub fn builder_from_env() -> Result<ClientConfigBuilder, ConfigError> {
+        let var = |name: &'static str| {
+            std::env::var(name)
+                .ok()
+                .filter(|value| !value.trim().is_empty())
+        };
+
+        // an explicit config has to be there, but `~/.zuliprc` is optional
+        let default_zuliprc = expand_tilde(Path::new("~/.zuliprc"));
+        let zuliprc = if let Some(path) = var("ZULIP_CONFIG") {
+            read_zuliprc(&expand_tilde(Path::new(&path)))?
+        } else if default_zuliprc.is_file() {
+            read_zuliprc(&default_zuliprc)?
+        } else {
+            HashMap::new()
+        };
+        let file = |key: &str| zuliprc.get(key).cloned();
+
+        let email = var("ZULIP_EMAIL")
+            .or_else(|| file("email"))
+            .ok_or(ConfigError::MissingEnvVar("ZULIP_EMAIL"))?;
+        let api_key = var("ZULIP_API_KEY")
+            .or_else(|| var("ZULIP_PERSONAL_KEY"))
+            .or_else(|| file("key"))
+            .ok_or(ConfigError::MissingEnvVar("ZULIP_API_KEY"))?;
+        let site = var("ZULIP_SITE")
+            .or_else(|| file("site"))
+            .ok_or(ConfigError::MissingEnvVar("ZULIP_SITE"))?;
+
+        Ok(ClientConfig::builder()
+            .email(email.trim())
+            .api_key(ApiKey::from_owned(api_key.trim().to_string()))
+            .server_address(parse_site(&site)?))
+    }
+
+    /// Makes a `Client` from environment variables. See
+    /// [`Client::builder_from_env`] for which ones.
+    #[tracing::instrument]
+    pub async fn from_env(user_agent: UserAgent) -> Result<Client, ZulipError> {
+        let conf = Self::builder_from_env()?.user_agent(user_agent).build()?;
+        Client::new(conf).await
+    }
+}
+
+/// Makes a [`ClientConfig`] without filling in every field yourself.
+///
+/// The server address, email, API key, and user agent are required. Every
+/// other field has a sensible default.
+#[derive(Clone, Debug, Default)]
+pub struct ClientConfigBuilder {
+    user_agent: Option<UserAgent>,
+    email: Option<String>,
+    api_key: Option<ApiKey>,
+    server_address: Option<Url>,
+
+    server_settings_cache_interval: Option<Arc<RwLock<Duration>>>,
+    retry: Option<RetryConfig>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: ProxyConfig,
+    root_certificates: Vec<RootCertificate>,
+    danger_accept_invalid_certs: bool,
+    default_headers: HashMap<String, DefaultHeaderValue>,
+    http1_only: bool,
+    allow_http: bool,
+
+    messages: MessagesConfig,
+}
+
+impl ClientConfigBuilder {
+    /// Sets the user agent. Required.
+    pub fn user_agent(mut self, user_agent: UserAgent) -> Self {
+        self.user_agent = Some(user_agent);
+        self
+    }
+
+    /// Sets the Zulip API email address of the user. Required.
+    pub fn email<S: AsRef<str>>(mut self, email: S) -> Self {
+        self.email = Some(email.as_ref().to_string());
+        self
+    }
+
+    /// Sets the user's API key. Required.
+    pub fn api_key(mut self, api_key: ApiKey) -> Self {
+        self.api_key = Some(api_key);
+        self
+    }
+
+    /// Sets the address of the Zulip server, like `https://chat.zulip.org`.
+    /// Required.
+    pub fn server_address(mut self, server_address: Url) -> Self {
+        self.server_address = Some(server_address);
+        self
+    }
+
+    /// Sets how long the server settings are cached for. Defaults to
+    /// [`ServerSettingsCache::DEFAULT_CACHE_REFRESH_INTERVAL`](crate::organizations::ServerSettingsCache::DEFAULT_CACHE_REFRESH_INTERVAL).
+    pub fn server_settings_cache_interval(mut self, interval: Duration) -> Self {
+        self.server_settings_cache_interval = Some(Arc::new(RwLock::new(interval)));
+        self
+    }
+
+    /// Sets how failed requests are retried. By default, they aren't.
+    pub fn retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Sets the max time a whole request can take. By default, there's no
+    /// limit.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the max time we'll wait to connect to the server. By default,
+    /// there's no limit.
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Sets which proxy requests go through. By default, we use the system's
+    /// proxy settings.
+    pub fn proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    /// Trusts another certificate authority, on top of the usual ones.
+    ///
+    /// Use this for servers with certificates from a private CA. Can be
+    /// called more than once.
+    pub fn root_certificate(mut self, certificate: RootCertificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Sets whether to accept any TLS certificate, even invalid ones. Off by
+    /// default.
+    ///
+    /// # Danger
+    ///
+    /// This turns off the checks that make sure you're talking to the real
+    /// server. Anyone between you and the server could read and change your
+    /// requests, including your API key! Only use it against a local dev
+    /// server. For a server with a private CA, use
+    /// [`ClientConfigBuilder::root_certificate`] instead.
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.danger_accept_invalid_certs = accept;
+        self
+    }
+
+    /// Adds a header to send with every request. Can be called more than
+    /// once.
+    ///
+    /// This is for deployments that need something extra, like a token for
+    /// an auth gateway. Setting the same header twice keeps the last value.
+    /// The value is treated as a secret, so it won't show up in logs.
+    pub fn default_header<S: AsRef<str>>(mut self, name: S, value: S) -> Self {
+        self.default_headers
+            .insert(name.as_ref().to_string(), DefaultHeaderValue::new(value));
+        self
+    }
+
+    /// Sets whether to only speak HTTP/1.1. Off by default, so HTTP/2 is used
+    /// when the server supports it.
+    ///
+    /// HTTP/1.1 is easier to read in a packet capture, so this is handy for
+    /// debugging.
+    pub fn http1_only(mut self, http1_only: bool) -> Self {
+        self.http1_only = http1_only;
+        self
+    }
+
+    /// Sets whether the server address may use plaintext `http://`. Off by
+    /// default, so only `https://` addresses are accepted.
+    ///
+    /// Without TLS, anyone between you and the server can read your
+    /// requests, including your API key! Only use it against a local dev
+    /// server.
+    pub fn allow_http(mut self, allow_http: bool) -> Self {
+        self.allow_http = allow_http;
+        self
+    }
+
+    /// Sets the config for the `messages` module.
+    pub fn messages(mut self, messages: MessagesConfig) -> Self {
+        self.messages = messages;
+        self
+    }
+
+    /// Makes the `ClientConfig`, checking that all required fields were set
+    /// and that the server address and default headers are valid.
+    ///
+    /// ```
+    /// use libzulip::{
+    ///     config::{ApiKey, ClientConfig, UserAgent},
+    ///     error::ConfigError,
+    /// };
+    /// use reqwest::Url;
+    ///
+    /// let builder = ClientConfig::builder()
+    ///     .user_agent(UserAgent::new("client_name", "version"))
+    ///     .api_key(ApiKey::new("key"))
+    ///     .email("me@example.com")
+    ///     .server_address(Url::parse("http://localhost:9991").unwrap());
+    ///
+    /// // plaintext needs to be turned on first
+    /// let err = builder.clone().build().unwrap_err();
+    /// assert!(matches!(err, ConfigError::PlaintextServerAddress { .. }));
+    /// assert!(builder.allow_http(true).build().is_ok());
+    /// ```
+    pub fn build(self) -> Result<ClientConfig, ConfigError> {
+        header_map(&self.default_headers)?;
+
+        let server_address = self
+            .server_address
+            .ok_or(ConfigError::MissingField("server_address"))?;
+        check_scheme(&server_address, self.allow_http)?;
+
+        Ok(ClientConfig {
+            user_agent: self
+                .user_agent
+                .ok_or(ConfigError::MissingField("user_agent"))?,
+            email: self.email.ok_or(ConfigError::MissingField("email"))?,
+            api_key: self.api_key.ok_or(ConfigError::MissingField("api_key"))?,
+            server_address,
+
+            server_settings_cache_interval: self.server_settings_cache_interval,
+            retry: self.retry,
+            timeout: self.timeout,
+            connect_timeout: self.connect_timeout,
+            proxy: self.proxy,
+            root_certificates: self.root_certificates,
+            danger_accept_invalid_certs: self.danger_accept_invalid_certs,
+            default_headers: self.default_headers,
+            http1_only: self.http1_only,
+            allow_http: self.allow_http,
+
+            messages: self.messages,
+        })
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord)]
 pub struct UserAgent {
     s: String,
 }
 
-#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord)]
+/// The secret key used to authenticate with Zulip.
+///
+/// The key is wiped from memory when this is dropped, and it's never shown
+/// in `Debug` output (so it won't end up in your logs).
+#[derive(Clone, PartialEq, Eq)]
 pub struct ApiKey {
-    key: String,
+    key: Zeroizing<String>,
 }
 
 impl ApiKey {
-    // TODO: get from sso/etc.
-
     pub fn new<S: AsRef<str>>(key: S) -> Self {
         Self {
-            key: key.as_ref().into(),
+            key: Zeroizing::new(key.as_ref().into()),
         }
     }
 
+    /// Returns a copy of the key.
+    ///
+    /// Note that the returned `String` isn't wiped on drop, so try not to
+    /// keep it around.
     pub fn get(&self) -> String {
-        self.key.clone()
+        self.key.to_string()
+    }
+
+    /// Wraps a key we already own, so there's no stray copy to wipe.
+    pub(crate) fn from_owned(key: String) -> Self {
+        Self {
+            key: Zeroizing::new(key),
+        }
     }
 
     pub fn set<S: AsRef<str>>(&mut self, key: S) {
-        self.key = key.as_ref().to_string();
+        // the old key is wiped when it's dropped here
+        self.key = Zeroizing::new(key.as_ref().to_string());
+    }
+
+    /// Logs in with a username (usually an email) and password, grabbing the
+    /// user's API key.
+    ///
+    /// The request goes out with the connection settings in `builder`, like
+    /// its proxy, certificates, timeouts, and headers, so set those up first.
+    /// Its email and API key aren't needed. Once you have the key, finish the
+    /// same builder with it:
+    ///
+    /// ```no_run
+    /// # async fn run() -> Result<(), libzulip::error::ZulipError> {
+    /// use libzulip::config::{ApiKey, ClientConfig, UserAgent};
+    /// use reqwest::Url;
+    ///
+    /// let builder = ClientConfig::builder()
+    ///     .user_agent(UserAgent::new("my_client", "1.0"))
+    ///     .server_address(Url::parse("https://chat.example.com").unwrap());
+    ///
+    /// let credentials = ApiKey::fetch(&builder, "iago@zulip.com", "hunter2").await?;
+    /// let conf = builder
+    ///     .email(credentials.email)
+    ///     .api_key(credentials.api_key)
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// You only need to do this once - store the returned key and use it from
+    /// then on. If the credentials are wrong, you'll get an
+    /// `AuthError::InvalidCredentials`. Other failures, like a deactivated
+    /// user, give an `AuthError::FetchApiKeyFailed` with Zulip's code.
+    #[tracing::instrument(skip(builder, password))]
+    pub async fn fetch(
+        builder: &ClientConfigBuilder,
+        username: &str,
+        password: &str,
+    ) -> Result<Credentials, ZulipError> {
+        // we're logging in to get these, so any placeholder will do
+        let conf = builder
+            .clone()
+            .email(username)
+            .api_key(ApiKey::new(""))
+            .build()?;
+
+        let url = Client::make_api_url(&conf.server_address)
+            .await
+            .join("fetch_api_key")?;
+        let parameters = HashMap::from([("username", username), ("password", password)]);
+
+        let resp = Client::make_reqwest_client(&conf)
+            .await?
+            .post(url)
+            .form(&parameters)
+            .send()
+            .await
+            .map_err(|e| conf.proxy.blame(e.into()))?;
+        let resp = Client::check_rate_limit(resp).await?;
+
+        let resp = match Client::read_json::<FetchApiKeyResponse>(resp).await? {
+            Ok(FetchApiKeyResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+
+                if error.code() == ZulipErrorCode::AuthenticationFailed {
+                    tracing::debug!("the server didn't like those credentials.");
+                    return Err(AuthError::InvalidCredentials {
+                        username: username.to_string(),
+                    }
+                    .into());
+                }
+
+                return Err(AuthError::FetchApiKeyFailed {
+                    username: username.to_string(),
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        tracing::trace!("grabbed api key for `{}`!", resp.email);
+        Ok(Credentials {
+            email: resp.email,
+            api_key: ApiKey::from_owned(resp.api_key),
+        })
+    }
+}
+
+/// An email and API key pair, as given by [`ApiKey::fetch`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Credentials {
+    /// The user's Zulip API email address. Use this in `ClientConfig::email`.
+    pub email: String,
+    /// The user's API key.
+    pub api_key: ApiKey,
+}
+
+#[derive(serde::Deserialize)]
+struct FetchApiKeyResponse {
+    #[serde(flatten)]
+    error: Option<ResponseError>,
+    api_key: String,
+    email: String,
+}
+
+impl std::fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ApiKey(***)")
     }
 }
 
@@ -67,11 +593,341 @@ impl UserAgent {
     }
 }
 
+/// Controls how failed requests are retried.
+///
+/// Requests are retried when the server is rate limiting us (HTTP 429), when
+/// it has an error of its own (HTTP 5xx), or when we can't reach it at all.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord)]
+pub struct RetryConfig {
+    /// The max number of times a request is retried.
+    pub max_retries: u32,
+    /// How long to wait before the first retry. This doubles after each
+    /// attempt.
+    pub base_delay: Duration,
+    /// The longest we'll ever wait between attempts.
+    ///
+    /// If the server asks us to wait longer than this, we give up right
+    /// away with a `ZulipError::RateLimited` instead.
+    pub max_delay: Duration,
+    /// Whether to wait as long as the server asks when we're rate limited,
+    /// instead of using the usual backoff.
+    pub respect_retry_after: bool,
+    /// Whether to retry requests that aren't safe to repeat, like sending a
+    /// message. If the server handled the first attempt but we never heard
+    /// back, a retry would do it twice!
+    pub retry_non_idempotent: bool,
+}
+
+impl RetryConfig {
+    /// How long to wait before the retry following the given attempt
+    /// (starting at `0`).
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use libzulip::config::RetryConfig;
+    ///
+    /// let retry = RetryConfig {
+    ///     base_delay: Duration::from_secs(1),
+    ///     max_delay: Duration::from_secs(5),
+    ///     ..RetryConfig::default()
+    /// };
+    /// assert_eq!(retry.backoff(0), Duration::from_secs(1));
+    /// assert_eq!(retry.backoff(2), Duration::from_secs(4));
+    /// assert_eq!(retry.backoff(3), Duration::from_secs(5));
+    /// ```
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2_u32.saturating_pow(attempt))
+            .min(self.max_delay)
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(60),
+            respect_retry_after: true,
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+/// Which proxy requests go through.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ProxyConfig {
+    /// Use the system's proxy settings, from the `HTTP_PROXY`, `HTTPS_PROXY`,
+    /// and `NO_PROXY` environment variables.
+    #[default]
+    System,
+    /// Connect to the server directly, ignoring any system proxy.
+    Disabled,
+    /// Send all requests through the given proxy.
+    ///
+    /// Hosts in `NO_PROXY` still skip it.
+    Custom {
+        /// The proxy's address, like `http://proxy.example.com:8080`.
+        url: Url,
+        /// The credentials to log in to the proxy with, if it needs them.
+        auth: Option<ProxyAuth>,
+    },
+}
+
+impl ProxyConfig {
+    /// Makes `reqwest` use this proxy.
+    pub(crate) fn apply(
+        &self,
+        builder: reqwest::ClientBuilder,
+    ) -> Result<reqwest::ClientBuilder, ZulipError> {
+        match *self {
+            ProxyConfig::System => Ok(builder),
+            ProxyConfig::Disabled => Ok(builder.no_proxy()),
+            ProxyConfig::Custom { ref url, ref auth } => {
+                let mut proxy =
+                    reqwest::Proxy::all(url.clone())?.no_proxy(reqwest::NoProxy::from_env());
+
+                if let Some(auth) = auth {
+                    proxy = proxy.basic_auth(&auth.username, &auth.password);
+                }
+
+                Ok(builder.proxy(proxy))
+            }
+        }
+    }
+
+    /// Blames connection failures on our custom proxy, if we have one.
+    ///
+    /// All requests go through it, so we can't have reached the server.
+    pub(crate) fn blame(&self, error: ZulipError) -> ZulipError {
+        match (self, error) {
+            (ProxyConfig::Custom { url, .. }, ZulipError::ConnectionFailed(error)) => {
+                ZulipError::ProxyConnectionFailed {
+                    proxy: url.to_string(),
+                    error,
+                }
+            }
+            (_, error) => error,
+        }
+    }
+}
+
+/// A username and password for a proxy.
+///
+/// Like [`ApiKey`], the password is wiped from memory when this is dropped,
+/// and it's never shown in `Debug` output.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ProxyAuth {
+    pub username: String,
+    password: Zeroizing<String>,
+}
+
+impl ProxyAuth {
+    pub fn new<S: AsRef<str>>(username: S, password: S) -> Self {
+        Self {
+            username: username.as_ref().into(),
+            password: Zeroizing::new(password.as_ref().into()),
+        }
+    }
+}
+
+impl std::fmt::Debug for ProxyAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProxyAuth")
+            .field("username", &self.username)
+            .field("password", &"***")
+            .finish()
+    }
+}
+
+/// The value of a header sent with every request.
+///
+/// These often hold tokens for an auth gateway, so like [`ApiKey`], the value
+/// is wiped from memory when this is dropped, and it's never shown in `Debug`
+/// output.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DefaultHeaderValue {
+    value: Zeroizing<String>,
+}
+
+impl DefaultHeaderValue {
+    pub fn new<S: AsRef<str>>(value: S) -> Self {
+        Self {
+            value: Zeroizing::new(value.as_ref().into()),
+        }
+    }
+
+    /// Returns the value.
+    pub fn get(&self) -> &str {
+        &self.value
+    }
+}
+
+impl std::fmt::Debug for DefaultHeaderValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DefaultHeaderValue(***)")
+    }
+}
+
+/// A certificate authority to trust, in PEM format.
+///
+/// Bundles with more than one certificate work, too.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RootCertificate {
+    /// The certificate itself.
+    Pem(Vec<u8>),
+    /// A path to a file holding the certificate.
+    PemFile(PathBuf),
+}
+
+impl RootCertificate {
+    /// Reads and parses the certificate(s).
+    pub(crate) async fn load(&self) -> Result<Vec<reqwest::Certificate>, ConfigError> {
+        let (certificate, pem) = match *self {
+            RootCertificate::Pem(ref pem) => ("<given bytes>".to_string(), pem.clone()),
+            RootCertificate::PemFile(ref path) => {
+                let certificate = path.display().to_string();
+                match tokio::fs::read(path).await {
+                    Ok(pem) => (certificate, pem),
+                    Err(e) => {
+                        return Err(ConfigError::InvalidCertificate {
+                            certificate,
+                            error: e.to_string(),
+                        })
+                    }
+                }
+            }
+        };
+
+        // `from_pem` happily takes garbage, so look for the certificates ourselves
+        match reqwest::Certificate::from_pem_bundle(&pem) {
+            Ok(certs) if !certs.is_empty() => Ok(certs),
+            Ok(_) => Err(ConfigError::InvalidCertificate {
+                certificate,
+                error: "there were no PEM certificates in it.".into(),
+            }),
+            Err(e) => Err(ConfigError::InvalidCertificate {
+                certificate,
+                error: e.to_string(),
+            }),
+        }
+    }
+}
+
+/// Reads the `[api]` section of a `.zuliprc` file.
+///
+/// These are INI files, as read by Python's `configparser`. Keys are
+/// lowercase, and values can follow either `=` or `:`.
+fn read_zuliprc(path: &Path) -> Result<HashMap<String, String>, ConfigError> {
+    let contents = std::fs::read_to_string(path).map_err(|e| ConfigError::ZuliprcUnreadable {
+        path: path.display().to_string(),
+        error: e.to_string(),
+    })?;
+
+    let mut section = None;
+    let mut api = None;
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = Some(name.trim().to_string());
+            if name.trim() == "api" {
+                api.get_or_insert_with(HashMap::new);
+            }
+            continue;
+        }
+
+        let (Some("api"), Some(api)) = (section.as_deref(), api.as_mut()) else {
+            continue;
+        };
+        if let Some((key, value)) = line.split_once(['=', ':']) {
+            api.insert(key.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    api.ok_or_else(|| ConfigError::MissingZuliprcSection {
+        path: path.display().to_string(),
+        section: "api",
+    })
+}
+
+/// Swaps a leading `~` for the user's home directory.
+fn expand_tilde(path: &Path) -> PathBuf {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"));
+
+    match (path.strip_prefix("~"), home) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Turns a Zulip "site", like `chat.zulip.org`, into the server's address.
+///
+/// Sites without a scheme use `https://`.
+fn parse_site(site: &str) -> Result<Url, ConfigError> {
+    let site = site.trim();
+    let address = if site.contains("://") {
+        site.to_string()
+    } else {
+        format!("https://{site}")
+    };
+
+    Url::parse(&address).map_err(|e| ConfigError::InvalidServerAddress {
+        address,
+        error: e.to_string(),
+    })
+}
+
+/// Checks that the server address is one we're willing to talk to: `https`,
+/// or `http` when `allow_http` is on.
+pub(crate) fn check_scheme(server_address: &Url, allow_http: bool) -> Result<(), ConfigError> {
+    match server_address.scheme() {
+        "https" => Ok(()),
+        "http" if allow_http => Ok(()),
+        "http" => Err(ConfigError::PlaintextServerAddress {
+            address: server_address.to_string(),
+        }),
+        scheme => Err(ConfigError::UnsupportedScheme {
+            scheme: scheme.to_string(),
+        }),
+    }
+}
+
+/// Turns the configured default headers into something `reqwest` can use,
+/// checking that each name and value is allowed in a header.
+pub(crate) fn header_map(
+    headers: &HashMap<String, DefaultHeaderValue>,
+) -> Result<HeaderMap, ConfigError> {
+    let mut map = HeaderMap::with_capacity(headers.len());
+
+    for (name, value) in headers {
+        let invalid = |error: String| ConfigError::InvalidHeader {
+            name: name.clone(),
+            error,
+        };
+
+        let header_name =
+            HeaderName::from_bytes(name.as_bytes()).map_err(|e| invalid(e.to_string()))?;
+        let mut header_value =
+            HeaderValue::from_str(value.get()).map_err(|e| invalid(e.to_string()))?;
+        header_value.set_sensitive(true);
+        map.insert(header_name, header_value);
+    }
+
+    Ok(map)
+}
+
 //
 // module configs
 //
 
-#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord)]
+#[derive(Clone, Debug, Default, PartialEq, PartialOrd, Eq, Ord)]
 pub struct MessagesConfig {
+    /// Whether messages we send should be marked as read for us.
+    ///
+    /// Needs Zulip 8.0 (feature level 236). Older servers ignore this, and
+    /// leave our messages unread.
     pub read_by_sender: bool,
 }
@@ -16,6 +16,22 @@ pub struct ResponseError {
 }
 
 impl ResponseError {
+    /// Returns the error's code as a [`ZulipErrorCode`], which you can match
+    /// on.
+    pub fn code(&self) -> ZulipErrorCode {
+        ZulipErrorCode::from(self.code.as_str())
+    }
+
+    /// Returns the error's code exactly as the server sent it.
+    pub fn raw_code(&self) -> &str {
+        &self.code
+    }
+
+    /// Returns the human-readable error message from the server.
+    pub fn msg(&self) -> &str {
+        &self.msg
+    }
+
     /// Creates a `tracing::warn!` if any of the given parameters were ignored.
     ///
     /// Please run this function if you get this type, as it shows the user
@@ -34,19 +50,241 @@ impl std::fmt::Display for ResponseError {
     }
 }
 
+/// A machine-readable error code that Zulip includes with error responses.
+///
+/// Use this to branch on what went wrong instead of comparing strings. Codes
+/// this crate doesn't know about end up in `Other`.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[non_exhaustive]
+pub enum ZulipErrorCode {
+    /// A generic error. Check the message for details.
+    BadRequest,
+    /// A required parameter wasn't given.
+    RequestVariableMissing,
+    /// A parameter had an invalid value.
+    RequestVariableInvalid,
+    /// A parameter's JSON couldn't be parsed.
+    InvalidJson,
+    /// An uploaded image couldn't be used.
+    BadImage,
+    /// The given narrow doesn't make sense to the server.
+    BadNarrow,
+    /// The event queue has expired or never existed. You'll need to register a new one.
+    BadEventQueueId,
+    /// The given channel doesn't exist (or you can't see it).
+    StreamDoesNotExist,
+    /// You aren't allowed to act on behalf of the given user.
+    UnauthorizedPrincipal,
+    /// The API key is wrong. Check your credentials!
+    InvalidApiKey,
+    /// This endpoint needs authentication.
+    UnauthenticatedUser,
+    /// The user making the request has been deactivated.
+    UserDeactivated,
+    /// The organization has been deactivated.
+    RealmDeactivated,
+    /// The given username or password was wrong.
+    AuthenticationFailed,
+    /// This organization doesn't allow password authentication.
+    PasswordAuthDisabled,
+    /// You don't have permission to do this.
+    Unauthorized,
+    /// You've made too many requests. Wait a bit, then try again.
+    RateLimitHit,
+    /// The server took too long to handle the request.
+    RequestTimeout,
+    /// You've already reacted with that emoji.
+    ReactionAlreadyExists,
+    /// You haven't reacted with that emoji.
+    ReactionDoesNotExist,
+    /// The messages are too old to be moved by you.
+    MoveMessagesTimeLimitExceeded,
+    /// No organization exists at the given subdomain.
+    NonexistentSubdomain,
+    /// The server is still starting up.
+    ServerNotReady,
+    /// A code we don't have a variant for. It's kept as-is.
+    Other(String),
+}
+
+impl ZulipErrorCode {
+    /// Returns the code as Zulip writes it, like `RATE_LIMIT_HIT`.
+    pub fn as_str(&self) -> &str {
+        match *self {
+            ZulipErrorCode::BadRequest => "BAD_REQUEST",
+            ZulipErrorCode::RequestVariableMissing => "REQUEST_VARIABLE_MISSING",
+            ZulipErrorCode::RequestVariableInvalid => "REQUEST_VARIABLE_INVALID",
+            ZulipErrorCode::InvalidJson => "INVALID_JSON",
+            ZulipErrorCode::BadImage => "BAD_IMAGE",
+            ZulipErrorCode::BadNarrow => "BAD_NARROW",
+            ZulipErrorCode::BadEventQueueId => "BAD_EVENT_QUEUE_ID",
+            ZulipErrorCode::StreamDoesNotExist => "STREAM_DOES_NOT_EXIST",
+            ZulipErrorCode::UnauthorizedPrincipal => "UNAUTHORIZED_PRINCIPAL",
+            ZulipErrorCode::InvalidApiKey => "INVALID_API_KEY",
+            ZulipErrorCode::UnauthenticatedUser => "UNAUTHENTICATED_USER",
+            ZulipErrorCode::UserDeactivated => "USER_DEACTIVATED",
+            ZulipErrorCode::RealmDeactivated => "REALM_DEACTIVATED",
+            ZulipErrorCode::AuthenticationFailed => "AUTHENTICATION_FAILED",
+            ZulipErrorCode::PasswordAuthDisabled => "PASSWORD_AUTH_DISABLED",
+            ZulipErrorCode::Unauthorized => "UNAUTHORIZED",
+            ZulipErrorCode::RateLimitHit => "RATE_LIMIT_HIT",
+            ZulipErrorCode::RequestTimeout => "REQUEST_TIMEOUT",
+            ZulipErrorCode::ReactionAlreadyExists => "REACTION_ALREADY_EXISTS",
+            ZulipErrorCode::ReactionDoesNotExist => "REACTION_DOES_NOT_EXIST",
+            ZulipErrorCode::MoveMessagesTimeLimitExceeded => "MOVE_MESSAGES_TIME_LIMIT_EXCEEDED",
+            ZulipErrorCode::NonexistentSubdomain => "NONEXISTENT_SUBDOMAIN",
+            ZulipErrorCode::ServerNotReady => "SERVER_NOT_READY",
+            ZulipErrorCode::Other(ref code) => code,
+        }
+    }
+}
+
+impl From<&str> for ZulipErrorCode {
+    fn from(code: &str) -> Self {
+        match code {
+            "BAD_REQUEST" => ZulipErrorCode::BadRequest,
+            "REQUEST_VARIABLE_MISSING" => ZulipErrorCode::RequestVariableMissing,
+            "REQUEST_VARIABLE_INVALID" => ZulipErrorCode::RequestVariableInvalid,
+            "INVALID_JSON" => ZulipErrorCode::InvalidJson,
+            "BAD_IMAGE" => ZulipErrorCode::BadImage,
+            "BAD_NARROW" => ZulipErrorCode::BadNarrow,
+            "BAD_EVENT_QUEUE_ID" => ZulipErrorCode::BadEventQueueId,
+            "STREAM_DOES_NOT_EXIST" => ZulipErrorCode::StreamDoesNotExist,
+            "UNAUTHORIZED_PRINCIPAL" => ZulipErrorCode::UnauthorizedPrincipal,
+            "INVALID_API_KEY" => ZulipErrorCode::InvalidApiKey,
+            "UNAUTHENTICATED_USER" => ZulipErrorCode::UnauthenticatedUser,
+            "USER_DEACTIVATED" => ZulipErrorCode::UserDeactivated,
+            "REALM_DEACTIVATED" => ZulipErrorCode::RealmDeactivated,
+            "AUTHENTICATION_FAILED" => ZulipErrorCode::AuthenticationFailed,
+            "PASSWORD_AUTH_DISABLED" => ZulipErrorCode::PasswordAuthDisabled,
+            "UNAUTHORIZED" => ZulipErrorCode::Unauthorized,
+            "RATE_LIMIT_HIT" => ZulipErrorCode::RateLimitHit,
+            "REQUEST_TIMEOUT" => ZulipErrorCode::RequestTimeout,
+            "REACTION_ALREADY_EXISTS" => ZulipErrorCode::ReactionAlreadyExists,
+            "REACTION_DOES_NOT_EXIST" => ZulipErrorCode::ReactionDoesNotExist,
+            "MOVE_MESSAGES_TIME_LIMIT_EXCEEDED" => ZulipErrorCode::MoveMessagesTimeLimitExceeded,
+            "NONEXISTENT_SUBDOMAIN" => ZulipErrorCode::NonexistentSubdomain,
+            "SERVER_NOT_READY" => ZulipErrorCode::ServerNotReady,
+            other => ZulipErrorCode::Other(other.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for ZulipErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// The main error type for this crate.
 #[derive(Debug, Error)]
 pub enum ZulipError {
     #[error("Error with API request. err: {_0}")]
-    ReqwestError(#[from] reqwest::Error),
+    ReqwestError(reqwest::Error),
+    #[error("The request to Zulip timed out.")]
+    Timeout,
+
+    #[error("The operation was cancelled.")]
+    Cancelled,
+    #[error("Couldn't connect to the server. err: {_0}")]
+    ConnectionFailed(reqwest::Error),
+    #[error("Couldn't connect to the server through the proxy at `{proxy}`. err: {error}")]
+    ProxyConnectionFailed {
+        proxy: String,
+        error: reqwest::Error,
+    },
+    #[error("The server doesn't support this feature. (needs feature level {required}, has {available})")]
+    FeatureUnsupported { required: u64, available: u64 },
     #[error("Serialization of an object failed. err: {_0}")]
     SerdeJsonError(#[from] serde_json::Error),
     #[error("An error occured involving file transfer. err: {_0}")]
     FileError(#[from] FileError),
     #[error("The given URL didn't parse correctly. err: {_0}")]
     UrlParseError(#[from] url::ParseError),
+    #[error("Hit the server's rate limit. Try again in {retry_after:?}.")]
+    RateLimited { retry_after: std::time::Duration },
+    #[error("{_0}")]
+    ConfigError(#[from] ConfigError),
+    #[error("{_0}")]
+    AuthError(#[from] AuthError),
+    #[error("{_0}")]
+    EventError(#[from] EventError),
     #[error("{_0}")]
     MessageError(#[from] MessageError),
+    #[error("{_0}")]
+    NarrowError(#[from] NarrowError),
+    #[error("{_0}")]
+    StreamError(#[from] StreamError),
+    #[error("{_0}")]
+    UserError(#[from] UserError),
+    #[error("{_0}")]
+    UserGroupError(#[from] UserGroupError),
+    #[error("{_0}")]
+    OrganizationError(#[from] OrganizationError),
+}
+
+impl From<reqwest::Error> for ZulipError {
+    fn from(error: reqwest::Error) -> Self {
+        // timeouts and connection failures get their own variants, as callers
+        // often want to retry them
+        if error.is_timeout() {
+            ZulipError::Timeout
+        } else if error.is_connect() {
+            ZulipError::ConnectionFailed(error)
+        } else {
+            ZulipError::ReqwestError(error)
+        }
+    }
+}
+
+/// Errors when making a client's config.
+#[derive(Clone, Debug, Error)]
+pub enum ConfigError {
+    #[error("The required config field `{_0}` wasn't set.")]
+    MissingField(&'static str),
+
+    #[error("Couldn't load the root certificate from `{certificate}`. {error}")]
+    InvalidCertificate { certificate: String, error: String },
+
+    #[error("The default header `{name}` isn't a valid HTTP header. {error}")]
+    InvalidHeader { name: String, error: String },
+
+    #[error(
+        "The server address `{address}` doesn't use TLS. Turn on `allow_http` to use it anyway."
+    )]
+    PlaintextServerAddress { address: String },
+
+    #[error("Server addresses must use `https` (or `http`), not `{scheme}`.")]
+    UnsupportedScheme { scheme: String },
+
+    #[error("The server address `{address}` isn't a valid URL. {error}")]
+    InvalidServerAddress { address: String, error: String },
+
+    #[error("The environment variable `{_0}` wasn't set.")]
+    MissingEnvVar(&'static str),
+
+    #[error("Couldn't read the zuliprc at `{path}`. {error}")]
+    ZuliprcUnreadable { path: String, error: String },
+
+    #[error("The zuliprc at `{path}` doesn't have an `[{section}]` section.")]
+    MissingZuliprcSection { path: String, section: &'static str },
+
+    #[error("The zuliprc at `{path}` doesn't set `{key}` in its `[api]` section.")]
+    MissingZuliprcKey { path: String, key: &'static str },
+}
+
+/// Errors when authenticating with Zulip.
+#[derive(Clone, Debug, Error)]
+pub enum AuthError {
+    #[error("The username or password for `{username}` was incorrect.")]
+    InvalidCredentials { username: String },
+
+    #[error("Failed to grab the API key for `{username}`. {error}")]
+    FetchApiKeyFailed {
+        username: String,
+        code: ZulipErrorCode,
+        error: String,
+    },
 }
 
 /// Errors from file upload/download.
@@ -64,16 +302,58 @@ pub enum FileError {
     FileNameNotFound(String),
     #[error("Failed to attach file to request. (path: `{_0}`)")]
     AttachSerializeFailed(String),
+    #[error("The server rejected the uploaded file. (path: `{path}`, {error})")]
+    UploadRejected { path: String, error: String },
+    #[error("The given file isn't an image type that Zulip accepts. (path: `{_0}`)")]
+    UnsupportedImageFormat(String),
+}
+
+/// Errors when working with event queues.
+#[derive(Clone, Debug, Error)]
+pub enum EventError {
+    #[error("Failed to register an event queue. {error}")]
+    RegisterFailed { code: ZulipErrorCode, error: String },
+
+    #[error("Failed to get events from the queue `{queue_id}`. {error}")]
+    GetEventsFailed {
+        queue_id: String,
+        code: ZulipErrorCode,
+        error: String,
+    },
+
+    #[error("Failed to delete the event queue `{queue_id}`. {error}")]
+    DeleteQueueFailed {
+        queue_id: String,
+        code: ZulipErrorCode,
+        error: String,
+    },
 }
 
 /// Errors when performing messaging tasks.
 #[derive(Clone, Debug, Error)]
 pub enum MessageError {
     #[error("Failed to send the given message. content: `{content}`. {error}")]
-    SendFailed { content: String, error: String },
+    SendFailed {
+        content: String,
+        code: ZulipErrorCode,
+        error: String,
+    },
+
+    #[error("Can't send a message with no content.")]
+    EmptyContent,
+
+    #[error("The message is {length} characters long, but the server only takes {max}.")]
+    ContentTooLong { length: u64, max: u64 },
 
     #[error("Failed to delete the message with ID `{id}`. {error}")]
-    DeletionFailed { id: u64, error: String },
+    DeletionFailed {
+        id: u64,
+        code: ZulipErrorCode,
+        error: String,
+    },
+
+    #[error("You aren't allowed to delete the message with ID `{id}`. {error}")]
+    DeletionNotAllowed { id: u64, error: String },
 
     #[error(
         "Couldn't add an emoji reaction to message `{msg_id}` with emoji name `{emoji_name}`. {error}"
@@ -81,6 +361,7 @@ pub enum MessageError {
     AddEmojiFailed {
         msg_id: u64,
         emoji_name: String,
+        code: ZulipErrorCode,
         error: String,
     },
 
@@ -90,15 +371,247 @@ pub enum MessageError {
     RemoveEmojiFailed {
         msg_id: u64,
         emoji_name: String,
+        code: ZulipErrorCode,
         error: String,
     },
 
+    #[error("`{emoji_name}` isn't a valid emoji name. Emoji names may only contain lowercase letters, digits, `_`, `-`, and `+`.")]
+    InvalidEmojiName { emoji_name: String },
+
     #[error("Failed to upload the given file. (path: {path}, {error})")]
-    FileUploadFailed { path: String, error: String },
+    FileUploadFailed {
+        path: String,
+        code: ZulipErrorCode,
+        error: String,
+    },
+
+    #[error("Failed to get the list of attachments. {error}")]
+    GetAttachmentsFailed { code: ZulipErrorCode, error: String },
+
+    #[error("Failed to delete the attachment with ID `{id}`. {error}")]
+    DeleteAttachmentFailed {
+        id: u64,
+        code: ZulipErrorCode,
+        error: String,
+    },
 
     #[error("Failed to fetch the message with ID `{msg_id}`. {error}")]
-    SingleMessageFetchFailed { msg_id: u64, error: String },
+    SingleMessageFetchFailed {
+        msg_id: u64,
+        code: ZulipErrorCode,
+        error: String,
+    },
 
     #[error("The server failed to render the following message: `{content}`. {error}")]
-    RenderMessageFailed { content: String, error: String },
+    RenderMessageFailed {
+        content: String,
+        code: ZulipErrorCode,
+        error: String,
+    },
+
+    #[error("Failed to mark {target} as read. {error}")]
+    MarkAsReadFailed {
+        target: String,
+        code: ZulipErrorCode,
+        error: String,
+    },
+
+    #[error("Failed to update the `{flag}` flag on messages. {error}")]
+    UpdateFlagsFailed {
+        flag: String,
+        code: ZulipErrorCode,
+        error: String,
+    },
+
+    #[error("Failed to fetch messages. {error}")]
+    FetchMessagesFailed { code: ZulipErrorCode, error: String },
+
+    #[error("Couldn't find any messages in topic `{topic}` of channel `{stream_id}`.")]
+    TopicNotFound { stream_id: u64, topic: String },
+}
+
+/// Errors when making or parsing narrows.
+#[derive(Clone, Debug, Error)]
+pub enum NarrowError {
+    #[error("The narrow operator `{_0}` isn't known.")]
+    UnknownOperator(String),
+
+    #[error("The narrow operator `{operator}` can't take the operand `{operand}`.")]
+    InvalidOperand { operator: String, operand: String },
+
+    #[error("The topic `{topic}` was given without a channel to look in.")]
+    TopicWithoutChannel { topic: String },
+
+    #[error("The narrow has {count} channels, but a message can only be in one.")]
+    MultipleChannels { count: usize },
+}
+
+/// Errors when working with channels (streams).
+#[derive(Clone, Debug, Error)]
+pub enum StreamError {
+    #[error("No channel was found with the name `{stream}`.")]
+    StreamNotFound { stream: String },
+
+    #[error("Failed to get the channel `{stream}`. {error}")]
+    GetStreamFailed {
+        stream: String,
+        code: ZulipErrorCode,
+        error: String,
+    },
+
+    #[error("Failed to get the list of channels. {error}")]
+    GetStreamsFailed { code: ZulipErrorCode, error: String },
+
+    #[error("Failed to get the channels you're subscribed to. {error}")]
+    GetSubscriptionsFailed { code: ZulipErrorCode, error: String },
+
+    #[error("Failed to get the email address for channel `{stream_id}`. {error}")]
+    GetEmailAddressFailed {
+        stream_id: u64,
+        code: ZulipErrorCode,
+        error: String,
+    },
+
+    #[error("Failed to delete topic `{topic}` in channel `{stream_id}`. {error}")]
+    DeleteTopicFailed {
+        stream_id: u64,
+        topic: String,
+        code: ZulipErrorCode,
+        error: String,
+    },
+
+    #[error("You aren't allowed to delete topic `{topic}` in channel `{stream_id}`. Only administrators can delete topics.")]
+    DeleteTopicNotAllowed { stream_id: u64, topic: String },
+}
+
+/// Errors when working with users.
+#[derive(Clone, Debug, Error)]
+pub enum UserError {
+    #[error("Failed to get the list of users. {error}")]
+    GetUsersFailed { code: ZulipErrorCode, error: String },
+
+    #[error("No user was found with the ID or email `{user}`.")]
+    UserNotFound { user: String },
+
+    #[error("Failed to get the user with the ID or email `{user}`. {error}")]
+    GetUserFailed {
+        user: String,
+        code: ZulipErrorCode,
+        error: String,
+    },
+
+    #[error("Failed to get info about the current user. {error}")]
+    GetOwnUserFailed { code: ZulipErrorCode, error: String },
+
+    #[error("Failed to get the presence of the user with the ID or email `{user}`. {error}")]
+    GetPresenceFailed {
+        user: String,
+        code: ZulipErrorCode,
+        error: String,
+    },
+
+    #[error("Failed to update the current user's presence. {error}")]
+    UpdatePresenceFailed { code: ZulipErrorCode, error: String },
+
+    #[error("Failed to update the current user's status. {error}")]
+    UpdateStatusFailed { code: ZulipErrorCode, error: String },
+
+    #[error("Failed to upload the current user's avatar. {error}")]
+    UploadAvatarFailed { code: ZulipErrorCode, error: String },
+
+    #[error("Failed to create the bot `{name}`. {error}")]
+    CreateBotFailed {
+        name: String,
+        code: ZulipErrorCode,
+        error: String,
+    },
+
+    #[error("The current user doesn't own a bot with the ID `{bot_id}`.")]
+    BotNotFound { bot_id: u64 },
+
+    #[error("Failed to get the API key for the bot with ID `{bot_id}`. {error}")]
+    GetBotApiKeyFailed {
+        bot_id: u64,
+        code: ZulipErrorCode,
+        error: String,
+    },
+
+    #[error("Failed to regenerate the API key for the bot with ID `{bot_id}`. {error}")]
+    RegenerateBotApiKeyFailed {
+        bot_id: u64,
+        code: ZulipErrorCode,
+        error: String,
+    },
+}
+
+/// Errors when working with user groups.
+#[derive(Clone, Debug, Error)]
+pub enum UserGroupError {
+    #[error("Failed to get the list of user groups. {error}")]
+    GetUserGroupsFailed { code: ZulipErrorCode, error: String },
+
+    #[error("No user group was found with the name `{name}`.")]
+    UserGroupNotFound { name: String },
+
+    #[error("Failed to create the user group `{name}`. {error}")]
+    CreateUserGroupFailed {
+        name: String,
+        code: ZulipErrorCode,
+        error: String,
+    },
+
+    #[error("Failed to update the user group with ID `{group_id}`. {error}")]
+    UpdateUserGroupFailed {
+        group_id: u64,
+        code: ZulipErrorCode,
+        error: String,
+    },
+
+    #[error("Failed to update the members of the user group with ID `{group_id}`. {error}")]
+    UpdateMembersFailed {
+        group_id: u64,
+        code: ZulipErrorCode,
+        error: String,
+    },
+}
+
+/// Errors when working with organization (realm) settings.
+#[derive(Clone, Debug, Error)]
+pub enum OrganizationError {
+    #[error("Failed to get the organization's custom emoji. {error}")]
+    GetRealmEmojiFailed { code: ZulipErrorCode, error: String },
+
+    #[error("Failed to get the server's emoji names. {error}")]
+    GetServerEmojiDataFailed { code: ZulipErrorCode, error: String },
+
+    #[error("Failed to upload the custom emoji `{name}`. {error}")]
+    UploadRealmEmojiFailed {
+        name: String,
+        code: ZulipErrorCode,
+        error: String,
+    },
+
+    #[error("Failed to create a linkifier with the pattern `{pattern}`. {error}")]
+    CreateLinkifierFailed {
+        pattern: String,
+        code: ZulipErrorCode,
+        error: String,
+    },
+
+    #[error("Failed to update the linkifier with ID `{id}`. {error}")]
+    UpdateLinkifierFailed {
+        id: u64,
+        code: ZulipErrorCode,
+        error: String,
+    },
+
+    #[error("Failed to delete the linkifier with ID `{id}`. {error}")]
+    DeleteLinkifierFailed {
+        id: u64,
+        code: ZulipErrorCode,
+        error: String,
+    },
+
+    #[error("Failed to get the organization's custom profile fields. {error}")]
+    GetProfileFieldsFailed { code: ZulipErrorCode, error: String },
 }
@@ -0,0 +1,418 @@
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::{stream::BoxStream, Stream, StreamExt as _};
+
+use crate::{
+    error::{EventError, ZulipError, ZulipErrorCode},
+    Client,
+};
+
+use super::{
+    get_events::Event,
+    register::{RegisterQueue, RegisterResponse},
+};
+
+impl Client {
+    /// Registers an event queue, then yields its events as they come in.
+    ///
+    /// The stream long-polls with [`Client::get_events`], so it only ends
+    /// when something goes wrong. If registering or polling fails (including
+    /// when the queue expires), the stream yields the error and ends. Use
+    /// [`Client::resilient_event_stream`] to keep going instead.
+    ///
+    /// The queue isn't deleted when the stream is dropped. The server gets
+    /// rid of it after a while on its own.
+    pub fn event_stream(
+        &self,
+        register: RegisterQueue,
+    ) -> impl Stream<Item = Result<Event, ZulipError>> + Send + '_ {
+        futures::stream::unfold(Some(None), move |state| {
+            let register = register.clone();
+
+            async move {
+                // `None` means we've already failed
+                let queue = match state? {
+                    Some(queue) => queue,
+                    None => match EventQueue::register(self, &register).await {
+                        Ok((queue, _)) => queue,
+                        Err(e) => return Some((Err(e), None)),
+                    },
+                };
+
+                Some(match queue.next_event(self).await {
+                    Ok((event, queue)) => (Ok(event), Some(Some(queue))),
+                    Err(e) => (Err(e), None),
+                })
+            }
+        })
+    }
+
+    /// Like [`Client::event_stream`], but survives network trouble and
+    /// expired queues.
+    ///
+    /// See [`ResilientEventStream`] for how it recovers.
+    pub fn resilient_event_stream(&self, register: RegisterQueue) -> ResilientEventStream<'_> {
+        ResilientEventStream {
+            client: self,
+            register,
+            backoff: ReconnectBackoff::default(),
+            on_state_change: None,
+            inner: None,
+        }
+    }
+}
+
+/// An event stream that reconnects on its own.
+///
+/// - When the network fails (a timeout, a dropped connection, or a rate
+///   limit), it waits and polls the same queue again, so no events are
+///   missed. The wait doubles after each failure in a row.
+/// - When the queue has expired, it registers a new one and yields a
+///   [`ResilientEvent::Gap`]. Events may have been missed, so refresh any
+///   state you're keeping from the new snapshot.
+///
+/// Any other error is yielded, and then the stream ends.
+///
+/// Make one with [`Client::resilient_event_stream`], then set it up before
+/// polling it:
+///
+/// ```no_run
+/// # async fn run(client: libzulip::Client) {
+/// use std::time::Duration;
+///
+/// use futures::StreamExt as _;
+/// use libzulip::events::{
+///     event_stream::{ReconnectBackoff, ResilientEvent},
+///     register::RegisterQueue,
+/// };
+///
+/// let mut events = client
+///     .resilient_event_stream(RegisterQueue::default())
+///     .backoff(ReconnectBackoff {
+///         base_delay: Duration::from_millis(250),
+///         max_delay: Duration::from_secs(30),
+///     })
+///     .on_state_change(|state| println!("now {state:?}"));
+///
+/// while let Some(Ok(item)) = events.next().await {
+///     match item {
+///         ResilientEvent::Event(event) => println!("got {event:?}"),
+///         ResilientEvent::Gap(snapshot) => println!("missed some! {snapshot:?}"),
+///         _ => (),
+///     }
+/// }
+/// # }
+/// ```
+pub struct ResilientEventStream<'a> {
+    client: &'a Client,
+    register: RegisterQueue,
+    backoff: ReconnectBackoff,
+    on_state_change: Option<StateCallback>,
+    /// the actual stream, made on the first poll
+    inner: Option<BoxStream<'a, Result<ResilientEvent, ZulipError>>>,
+}
+
+impl<'a> ResilientEventStream<'a> {
+    /// Sets how long to wait between reconnects.
+    ///
+    /// This does nothing once the stream has been polled.
+    pub fn backoff(mut self, backoff: ReconnectBackoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Calls `callback` whenever the stream connects or loses its
+    /// connection.
+    ///
+    /// This does nothing once the stream has been polled.
+    pub fn on_state_change(mut self, callback: impl Fn(ConnectionState) + Send + 'static) -> Self {
+        self.on_state_change = Some(Box::new(callback));
+        self
+    }
+
+    /// Makes the stream that does the work.
+    fn start(&mut self) -> BoxStream<'a, Result<ResilientEvent, ZulipError>> {
+        let client = self.client;
+        let state = Resilience {
+            register: std::mem::take(&mut self.register),
+            backoff: self.backoff,
+            on_state_change: self.on_state_change.take(),
+            queue: None,
+            registered: false,
+            state: None,
+            failures: 0,
+            done: false,
+        };
+
+        futures::stream::unfold(state, move |mut state| async move {
+            if state.done {
+                return None;
+            }
+
+            let item = state.next_item(client).await;
+            state.done = item.is_err();
+            Some((item, state))
+        })
+        .boxed()
+    }
+}
+
+impl Stream for ResilientEventStream<'_> {
+    type Item = Result<ResilientEvent, ZulipError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.inner.is_none() {
+            let inner = self.start();
+            self.inner = Some(inner);
+        }
+
+        match self.inner {
+            Some(ref mut inner) => inner.poll_next_unpin(cx),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+impl std::fmt::Debug for ResilientEventStream<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResilientEventStream")
+            .field("register", &self.register)
+            .field("backoff", &self.backoff)
+            .field("started", &self.inner.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Something from a [`ResilientEventStream`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ResilientEvent {
+    /// An event from the queue.
+    Event(Event),
+    /// The old queue expired, so a new one was registered. Any events
+    /// between the two were missed.
+    ///
+    /// This holds the new queue's snapshot, which is up to date.
+    Gap(Box<RegisterResponse>),
+}
+
+/// Whether a [`ResilientEventStream`] is connected to its queue.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConnectionState {
+    /// Events are coming in.
+    Connected,
+    /// The connection failed, and we'll try again in `retry_in`.
+    Reconnecting {
+        /// How many times in a row it's failed.
+        attempt: u32,
+        /// How long until the next try.
+        retry_in: Duration,
+    },
+}
+
+/// How long a [`ResilientEventStream`] waits before reconnecting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReconnectBackoff {
+    /// How long to wait after the first failure. This doubles after each
+    /// failure in a row.
+    pub base_delay: Duration,
+    /// The longest we'll ever wait.
+    pub max_delay: Duration,
+}
+
+impl ReconnectBackoff {
+    /// How long to wait after the given failure in a row (starting at `0`).
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use libzulip::events::event_stream::ReconnectBackoff;
+    ///
+    /// let backoff = ReconnectBackoff {
+    ///     base_delay: Duration::from_secs(1),
+    ///     max_delay: Duration::from_secs(5),
+    /// };
+    /// assert_eq!(backoff.delay(0), Duration::from_secs(1));
+    /// assert_eq!(backoff.delay(2), Duration::from_secs(4));
+    /// assert_eq!(backoff.delay(3), Duration::from_secs(5));
+    /// ```
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2_u32.saturating_pow(attempt))
+            .min(self.max_delay)
+    }
+}
+
+impl Default for ReconnectBackoff {
+    fn default() -> Self {
+        Self {
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+type StateCallback = Box<dyn Fn(ConnectionState) + Send>;
+
+/// A registered queue, and where we're at in it.
+struct EventQueue {
+    queue_id: String,
+    last_event_id: i64,
+    /// events we've grabbed but haven't handed out yet
+    pending: VecDeque<Event>,
+}
+
+impl EventQueue {
+    /// Registers a new queue, handing back its snapshot, too.
+    async fn register(
+        client: &Client,
+        register: &RegisterQueue,
+    ) -> Result<(Self, RegisterResponse), ZulipError> {
+        let resp = client.register_queue(register.clone()).await?;
+
+        let Some(queue_id) = resp.queue_id.clone() else {
+            return Err(EventError::RegisterFailed {
+                code: ZulipErrorCode::BadRequest,
+                error: "The server didn't make a queue.".into(),
+            }
+            .into());
+        };
+
+        let queue = Self {
+            queue_id,
+            last_event_id: resp.last_event_id,
+            pending: VecDeque::new(),
+        };
+        Ok((queue, resp))
+    }
+
+    /// Grabs the next event, polling the server when we've run out.
+    ///
+    /// The queue is only handed back on success, as that's when it's safe
+    /// to keep going. Use `next_event_mut` to keep it around on failure.
+    async fn next_event(mut self, client: &Client) -> Result<(Event, Self), ZulipError> {
+        let event = self.next_event_mut(client).await?;
+        Ok((event, self))
+    }
+
+    async fn next_event_mut(&mut self, client: &Client) -> Result<Event, ZulipError> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                self.last_event_id = self.last_event_id.max(event.id);
+                return Ok(event);
+            }
+
+            let events = client
+                .get_events(&self.queue_id, self.last_event_id, false)
+                .await?;
+            self.pending.extend(events);
+        }
+    }
+}
+
+/// Where a [`ResilientEventStream`] is at.
+struct Resilience {
+    register: RegisterQueue,
+    backoff: ReconnectBackoff,
+    on_state_change: Option<StateCallback>,
+    /// `None` until we've registered, and again when the queue expires
+    queue: Option<EventQueue>,
+    /// whether we've ever had a queue
+    registered: bool,
+    /// the last state we told the callback about
+    state: Option<ConnectionState>,
+    /// how many times in a row we've failed to reach the server
+    failures: u32,
+    done: bool,
+}
+
+impl Resilience {
+    async fn next_item(&mut self, client: &Client) -> Result<ResilientEvent, ZulipError> {
+        loop {
+            let Some(ref mut queue) = self.queue else {
+                match EventQueue::register(client, &self.register).await {
+                    Ok((queue, snapshot)) => {
+                        self.queue = Some(queue);
+                        self.connected();
+
+                        // the first queue doesn't replace anything
+                        if std::mem::replace(&mut self.registered, true) {
+                            tracing::debug!("the event queue expired, so we made a new one.");
+                            return Ok(ResilientEvent::Gap(Box::new(snapshot)));
+                        }
+                    }
+                    Err(e) => self.wait_to_reconnect(e).await?,
+                }
+                continue;
+            };
+
+            match queue.next_event_mut(client).await {
+                Ok(event) => {
+                    self.connected();
+                    return Ok(ResilientEvent::Event(event));
+                }
+                Err(ZulipError::EventError(EventError::GetEventsFailed {
+                    code: ZulipErrorCode::BadEventQueueId,
+                    ..
+                })) => self.queue = None,
+                Err(e) => self.wait_to_reconnect(e).await?,
+            }
+        }
+    }
+
+    /// Notes that we've reached the server.
+    fn connected(&mut self) {
+        self.failures = 0;
+        self.set_state(ConnectionState::Connected);
+    }
+
+    /// Waits before trying again after a network error. Any other error is
+    /// handed back instead.
+    async fn wait_to_reconnect(&mut self, error: ZulipError) -> Result<(), ZulipError> {
+        let retry_in = match error {
+            ZulipError::RateLimited { retry_after } => retry_after,
+            ref e if is_network_error(e) => self.backoff.delay(self.failures),
+            e => return Err(e),
+        };
+
+        self.failures = self.failures.saturating_add(1);
+        tracing::debug!(
+            "lost the event queue's connection. retrying in {retry_in:?}... err: {error}"
+        );
+        self.set_state(ConnectionState::Reconnecting {
+            attempt: self.failures,
+            retry_in,
+        });
+
+        tokio::time::sleep(retry_in).await;
+        Ok(())
+    }
+
+    fn set_state(&mut self, state: ConnectionState) {
+        if self.state == Some(state) {
+            return;
+        }
+
+        self.state = Some(state);
+        if let Some(ref callback) = self.on_state_change {
+            callback(state);
+        }
+    }
+}
+
+/// Whether the error means we couldn't talk to the server, rather than the
+/// server telling us something.
+fn is_network_error(error: &ZulipError) -> bool {
+    match *error {
+        ZulipError::Timeout
+        | ZulipError::ConnectionFailed(_)
+        | ZulipError::ProxyConnectionFailed { .. } => true,
+        ZulipError::ReqwestError(ref e) => e.is_request() || e.is_body(),
+        _ => false,
+    }
+}
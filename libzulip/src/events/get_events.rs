@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    error::{EventError, ResponseError, ZulipError},
+    messages::{
+        emoji_reaction::ReactionType,
+        fetch_single_message::{Message, MessageType},
+    },
+    Client,
+};
+
+impl Client {
+    /// Grabs the events in a queue that came after `last_event_id`.
+    ///
+    /// Unless `dont_block` is set, the server holds the request open until
+    /// there's an event to send (or it sends a `heartbeat` after about a
+    /// minute), so make sure your timeout is long enough.
+    ///
+    /// If the queue has expired, the error's code is
+    /// `ZulipErrorCode::BadEventQueueId`. Register a new queue when that
+    /// happens.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_events(
+        &self,
+        queue_id: &str,
+        last_event_id: i64,
+        dont_block: bool,
+    ) -> Result<Vec<Event>, ZulipError> {
+        let url = self.api_url().join("events")?;
+        let parameters = HashMap::from([
+            ("queue_id", queue_id.to_string()),
+            ("last_event_id", last_event_id.to_string()),
+            ("dont_block", dont_block.to_string()),
+        ]);
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().get(url)).query(&parameters))
+            .await?;
+
+        let resp = match Self::read_json::<GetEventsResponse>(resp).await? {
+            Ok(GetEventsResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(EventError::GetEventsFailed {
+                    queue_id: queue_id.to_string(),
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        tracing::trace!("grabbed {} events!", resp.events.len());
+        Ok(resp.events)
+    }
+
+    /// Like [`Client::get_events`], but stops waiting when `cancel` is
+    /// cancelled, giving a `ZulipError::Cancelled`.
+    ///
+    /// This is handy for shutting down a task that's long-polling a queue
+    /// without waiting for the next heartbeat.
+    #[tracing::instrument(skip(self, cancel))]
+    pub async fn get_events_with_cancel(
+        &self,
+        queue_id: &str,
+        last_event_id: i64,
+        dont_block: bool,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<Event>, ZulipError> {
+        tokio::select! {
+            biased;
+            () = cancel.cancelled() => {
+                tracing::debug!("stopped waiting for events, as we were cancelled.");
+                Err(ZulipError::Cancelled)
+            }
+            result = self.get_events(queue_id, last_event_id, dont_block) => result,
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct GetEventsResponse {
+    #[serde(flatten)]
+    pub error: Option<ResponseError>,
+    /// The new events, oldest first.
+    #[serde(default)]
+    pub events: Vec<Event>,
+}
+
+/// Something that happened on the server.
+#[derive(Debug, serde::Deserialize)]
+#[non_exhaustive]
+pub struct Event {
+    /// The event's ID. Pass the newest one you've seen as `last_event_id`.
+    pub id: i64,
+    /// What happened.
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+/// The different kinds of events.
+///
+/// Kinds that this crate doesn't understand yet end up in `Other`.
+#[derive(Debug, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum EventKind {
+    /// A new message was sent.
+    Message {
+        message: Box<Message>,
+        /// The current user's flags on the message, like `read`.
+        #[serde(default)]
+        flags: Vec<String>,
+    },
+    /// Messages were deleted.
+    DeleteMessage(DeleteMessageEvent),
+    /// Someone added or removed an emoji reaction. See
+    /// [`Message::apply_reaction_event`] for keeping a message up to date.
+    Reaction(ReactionEvent),
+    /// Sent when nothing else has happened for a while, so you know the
+    /// queue is still alive.
+    Heartbeat,
+    /// An event we don't parse yet.
+    #[serde(other)]
+    Other,
+}
+
+/// An emoji reaction was added to (or removed from) a message.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[non_exhaustive]
+pub struct ReactionEvent {
+    /// Whether the reaction was added or removed.
+    pub op: ReactionOp,
+    /// The ID of the message that was reacted to.
+    pub message_id: u64,
+    /// The ID of the user who reacted.
+    pub user_id: u64,
+    /// The emoji's name.
+    pub emoji_name: String,
+    /// The emoji's code, within the namespace of `reaction_type`.
+    pub emoji_code: String,
+    /// The type of the emoji.
+    pub reaction_type: ReactionType,
+}
+
+/// Whether a reaction was added or removed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReactionOp {
+    Add,
+    Remove,
+}
+
+/// One or more messages were deleted.
+///
+/// Servers send a single `message_id` unless the queue was registered with
+/// the `bulk_message_deletion` capability. Both forms end up here.
+///
+/// ```
+/// use libzulip::events::get_events::{Event, EventKind};
+///
+/// let bulk = r#"{"id": 2, "type": "delete_message", "message_type": "stream", "message_ids": [7, 8], "stream_id": 3, "topic": "lunch"}"#;
+/// let legacy = r#"{"id": 3, "type": "delete_message", "message_type": "private", "message_id": 9}"#;
+///
+/// let EventKind::DeleteMessage(bulk) = serde_json::from_str::<Event>(bulk).unwrap().kind else {
+///     panic!("wrong kind");
+/// };
+/// assert_eq!(bulk.message_ids, vec![7, 8]);
+/// assert_eq!(bulk.stream_id, Some(3));
+///
+/// let EventKind::DeleteMessage(legacy) = serde_json::from_str::<Event>(legacy).unwrap().kind else {
+///     panic!("wrong kind");
+/// };
+/// assert_eq!(legacy.message_ids, vec![9]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(from = "RawDeleteMessageEvent")]
+pub struct DeleteMessageEvent {
+    /// The IDs of the deleted messages.
+    pub message_ids: Vec<u64>,
+    /// Whether they were channel messages or direct messages.
+    pub message_type: MessageType,
+    /// For channel messages, the channel they were in.
+    pub stream_id: Option<u64>,
+    /// For channel messages, the topic they were in.
+    pub topic: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct RawDeleteMessageEvent {
+    #[serde(default)]
+    message_ids: Vec<u64>,
+    message_id: Option<u64>,
+    message_type: MessageType,
+    stream_id: Option<u64>,
+    topic: Option<String>,
+}
+
+impl From<RawDeleteMessageEvent> for DeleteMessageEvent {
+    fn from(raw: RawDeleteMessageEvent) -> Self {
+        let mut message_ids = raw.message_ids;
+        message_ids.extend(raw.message_id);
+
+        Self {
+            message_ids,
+            message_type: raw.message_type,
+            stream_id: raw.stream_id,
+            topic: raw.topic,
+        }
+    }
+}
@@ -0,0 +1,7 @@
+// like `messages`, each module here represents one API call (or a small
+// family of them)
+
+pub mod event_stream;
+pub mod get_events;
+pub mod register;
+pub mod unread;
@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+
+use reqwest::Response;
+use serde_json::Value;
+
+use crate::{
+    error::{EventError, ResponseError, ZulipError},
+    messages::fetch_single_message::ContentFormat,
+    narrow::{NarrowList, NarrowListExt},
+    organizations::RealmEmoji,
+    streams::subscriptions::Subscription,
+    users::get_users::User,
+    Client,
+};
+
+use super::unread::UnreadMessages;
+
+impl Client {
+    /// Registers an event queue, grabbing a snapshot of the current state
+    /// along with it.
+    ///
+    /// Queues stick around on the server for a while, so call
+    /// [`Client::delete_queue`] when you're done with one.
+    #[tracing::instrument(skip(self))]
+    pub async fn register_queue(
+        &self,
+        register: RegisterQueue,
+    ) -> Result<RegisterResponse, ZulipError> {
+        let resp = self.send_register(&register).await?;
+
+        let resp = match Self::read_json::<RegisterResponse>(resp).await? {
+            Ok(RegisterResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(EventError::RegisterFailed {
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        tracing::trace!("registered event queue `{:?}`!", resp.queue_id);
+        Ok(resp)
+    }
+
+    /// Registers an event queue and grabs the organization's whole state
+    /// with it, in one request.
+    ///
+    /// This is the quickest way to start up a client: instead of asking for
+    /// the users, channels, and so on one at a time, the server sends them
+    /// all at once. Then, poll the queue to keep them up to date.
+    ///
+    /// `fetch_event_types` picks which parts of the state to include, like
+    /// `realm_user` or `subscription`. `None` means all of them, which can be
+    /// big in large organizations. The queue itself gets every event.
+    #[tracing::instrument(skip(self))]
+    pub async fn register_full_state(
+        &self,
+        fetch_event_types: Option<Vec<String>>,
+    ) -> Result<RealmState, ZulipError> {
+        let register = RegisterQueue {
+            fetch_event_types,
+            ..RegisterQueue::default()
+        };
+        let resp = self.send_register(&register).await?;
+
+        let resp = match Self::read_json::<RealmState>(resp).await? {
+            Ok(RealmState {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(EventError::RegisterFailed {
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        tracing::trace!(
+            "registered event queue `{:?}` with {} other state sections!",
+            resp.queue_id,
+            resp.other.len()
+        );
+        Ok(resp)
+    }
+
+    /// Deletes an event queue. Its ID won't work after this.
+    #[tracing::instrument(skip(self))]
+    pub async fn delete_queue(&self, queue_id: &str) -> Result<(), ZulipError> {
+        let url = self.api_url().join("events")?;
+        let parameters = HashMap::from([("queue_id", queue_id)]);
+
+        let resp = self
+            .send_request(
+                self.auth(self.reqwest_client().delete(url))
+                    .form(&parameters),
+            )
+            .await?;
+
+        if let Ok(DeleteQueueResponse { error: Some(error) }) | Err(error) =
+            Self::read_json::<DeleteQueueResponse>(resp).await?
+        {
+            error.warn_ignored();
+            return Err(EventError::DeleteQueueFailed {
+                queue_id: queue_id.to_string(),
+                code: error.code(),
+                error: error.to_string(),
+            }
+            .into());
+        }
+
+        tracing::trace!("deleted event queue `{queue_id}`!");
+        Ok(())
+    }
+}
+
+// private
+impl Client {
+    /// Sends `POST /register`, leaving the response for the caller to read.
+    async fn send_register(&self, register: &RegisterQueue) -> Result<Response, ZulipError> {
+        let url = self.api_url().join("register")?;
+        let feature_level = self.server_settings_cache.get().await?.zulip_feature_level;
+        let parameters = register.make_parameters(feature_level)?;
+
+        self.send_request(self.auth(self.reqwest_client().post(url)).form(&parameters))
+            .await
+    }
+}
+
+/// Options for [`Client::register_queue`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RegisterQueue {
+    /// Which events the queue should get, like `message`. `None` means all
+    /// of them.
+    pub event_types: Option<Vec<String>>,
+    /// Which parts of the state to include in the snapshot, like
+    /// `unread_msgs`. `None` means the same as `event_types`.
+    pub fetch_event_types: Option<Vec<String>>,
+    /// Only get message events matching these filters.
+    pub narrow: NarrowList,
+    /// Whether message content should be HTML or Markdown.
+    pub format: ContentFormat,
+    /// Which newer event formats we understand.
+    pub client_capabilities: ClientCapabilities,
+}
+
+impl RegisterQueue {
+    fn make_parameters(&self, feature_level: u64) -> Result<HashMap<&str, String>, ZulipError> {
+        let mut parameters = HashMap::from([
+            ("apply_markdown", self.format.apply_markdown().to_string()),
+            (
+                "narrow",
+                self.narrow.to_query_param_for_feature_level(feature_level),
+            ),
+        ]);
+
+        parameters.insert(
+            "client_capabilities",
+            serde_json::to_string(&self.client_capabilities)?,
+        );
+
+        if let Some(ref event_types) = self.event_types {
+            parameters.insert("event_types", serde_json::to_string(event_types)?);
+        }
+        if let Some(ref fetch_event_types) = self.fetch_event_types {
+            parameters.insert(
+                "fetch_event_types",
+                serde_json::to_string(fetch_event_types)?,
+            );
+        }
+
+        Ok(parameters)
+    }
+}
+
+/// Tells the server which newer event formats we understand. Each one
+/// changes what some events look like.
+///
+/// The defaults match the types in [`get_events`](super::get_events), so
+/// only change them if you're parsing events yourself.
+///
+/// ```
+/// use libzulip::events::register::ClientCapabilities;
+/// use serde_json::json;
+///
+/// let sent = serde_json::to_value(ClientCapabilities::default()).unwrap();
+/// assert_eq!(sent["bulk_message_deletion"], json!(true));
+/// assert_eq!(sent["notification_settings_null"], json!(false));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct ClientCapabilities {
+    /// Whether stream notification settings can be `null`, meaning "use the
+    /// user's default".
+    pub notification_settings_null: bool,
+    /// Whether `delete_message` events may hold many message IDs at once.
+    pub bulk_message_deletion: bool,
+    /// Whether users' `avatar_url` may be left out, so you can work it out
+    /// from their ID instead.
+    pub user_avatar_url_field_optional: bool,
+    /// Whether we want typing notifications for channel messages, too.
+    pub stream_typing_notifications: bool,
+}
+
+impl Default for ClientCapabilities {
+    fn default() -> Self {
+        Self {
+            notification_settings_null: false,
+            bulk_message_deletion: true,
+            user_avatar_url_field_optional: false,
+            stream_typing_notifications: false,
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[non_exhaustive]
+pub struct RegisterResponse {
+    #[serde(flatten)]
+    pub error: Option<ResponseError>,
+    /// The new queue's ID. `None` if the server didn't make one.
+    pub queue_id: Option<String>,
+    /// The ID of the last event the snapshot includes. Poll for events after
+    /// this one.
+    pub last_event_id: i64,
+    /// The server's feature level.
+    pub zulip_feature_level: Option<u64>,
+    /// Unread message info. Only included when asked for with
+    /// `unread_msgs`.
+    pub unread_msgs: Option<UnreadMessages>,
+    /// Where to grab the server's emoji names from. See
+    /// [`Client::get_server_emoji_reaction_data`].
+    ///
+    /// Only included when asked for with `realm`, from feature level 140.
+    pub server_emoji_data_url: Option<String>,
+}
+
+/// An event queue, along with the organization's state when it was made.
+/// See [`Client::register_full_state`].
+///
+/// The sections most clients need are typed. Everything else the server
+/// sent is kept in `other`, keyed by name. Sections that weren't asked for
+/// are `None`.
+///
+/// ```
+/// use libzulip::events::register::RealmState;
+/// use serde_json::json;
+///
+/// let snapshot = r##"{
+///     "result": "success", "msg": "",
+///     "queue_id": "1517975029:0", "last_event_id": -1, "zulip_feature_level": 300,
+///     "subscriptions": [{
+///         "stream_id": 1, "name": "general", "description": "", "rendered_description": "",
+///         "invite_only": false, "is_web_public": false, "history_public_to_subscribers": true,
+///         "stream_post_policy": 1, "message_retention_days": null, "stream_weekly_traffic": 12,
+///         "color": "#76ce90", "is_muted": false, "pin_to_top": true
+///     }],
+///     "realm_users": [{
+///         "user_id": 8, "email": "iago@zulip.com", "full_name": "Iago", "is_active": true,
+///         "is_bot": false, "role": 200, "avatar_url": null, "timezone": ""
+///     }],
+///     "realm_emoji": {
+///         "1": {"id": "1", "name": "green_tick", "source_url": "/user_avatars/1/emoji/images/1.png",
+///               "deactivated": false, "author_id": 8}
+///     },
+///     "unread_msgs": {"count": 2, "pms": [], "streams": [], "huddles": [], "mentions": [3], "old_unreads_missing": false},
+///     "realm_name": "Zulip Dev",
+///     "max_message_length": 10000
+/// }"##;
+///
+/// let state: RealmState = serde_json::from_str(snapshot).unwrap();
+/// assert_eq!(state.queue_id.as_deref(), Some("1517975029:0"));
+/// assert_eq!(state.subscriptions.unwrap()[0].stream.name, "general");
+/// assert_eq!(state.realm_users.unwrap()[0].full_name, "Iago");
+/// assert_eq!(state.realm_emoji.unwrap()["1"].name, "green_tick");
+/// assert_eq!(state.unread_msgs.unwrap().mentions, vec![3]);
+///
+/// // the rest is left as JSON
+/// assert_eq!(state.other["realm_name"], json!("Zulip Dev"));
+/// assert_eq!(state.other["max_message_length"], json!(10000));
+/// assert!(!state.other.contains_key("result") && !state.other.contains_key("msg"));
+///
+/// // sections we didn't ask for are just missing
+/// let bare: RealmState =
+///     serde_json::from_str(r#"{"result": "success", "msg": "", "queue_id": "1:0", "last_event_id": 4}"#)
+///         .unwrap();
+/// assert!(bare.subscriptions.is_none() && bare.realm_users.is_none());
+/// ```
+#[derive(Debug, serde::Deserialize)]
+#[non_exhaustive]
+pub struct RealmState {
+    #[serde(flatten)]
+    pub error: Option<ResponseError>,
+    /// The new queue's ID. `None` if the server didn't make one.
+    pub queue_id: Option<String>,
+    /// The ID of the last event the snapshot includes. Poll for events after
+    /// this one.
+    pub last_event_id: i64,
+    /// The server's feature level.
+    pub zulip_feature_level: Option<u64>,
+    /// The channels the current user is subscribed to. Included with
+    /// `subscription`.
+    pub subscriptions: Option<Vec<Subscription>>,
+    /// Everyone in the organization, bots included. Included with
+    /// `realm_user`.
+    pub realm_users: Option<Vec<User>>,
+    /// The organization's custom emoji, keyed by ID. Included with
+    /// `realm_emoji`.
+    pub realm_emoji: Option<HashMap<String, RealmEmoji>>,
+    /// Unread message info. Included with `unread_msgs`.
+    pub unread_msgs: Option<UnreadMessages>,
+    /// Every other section, as the server sent it.
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+    /// Zulip's `result`, taken out here so it doesn't end up in `other`.
+    #[serde(default, rename = "result")]
+    _result: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct DeleteQueueResponse {
+    #[serde(flatten)]
+    error: Option<ResponseError>,
+}
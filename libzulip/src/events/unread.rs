@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use crate::{error::ZulipError, Client};
+
+use super::register::RegisterQueue;
+
+impl Client {
+    /// Grabs a summary of the current user's unread messages.
+    ///
+    /// This registers a short-lived event queue to get the info, then
+    /// deletes it right away.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_unread_counts(&self) -> Result<UnreadCounts, ZulipError> {
+        let resp = self
+            .register_queue(RegisterQueue {
+                // we don't want any events - just the snapshot
+                event_types: Some(Vec::new()),
+                fetch_event_types: Some(vec!["unread_msgs".into()]),
+                ..RegisterQueue::default()
+            })
+            .await?;
+
+        if let Some(ref queue_id) = resp.queue_id {
+            self.delete_queue(queue_id).await?;
+        }
+
+        // we asked for it, so this should always be here
+        let unread = resp.unread_msgs.unwrap_or_default();
+
+        tracing::trace!("grabbed {} unread messages!", unread.count);
+        Ok(UnreadCounts::from(unread))
+    }
+}
+
+/// The current user's unread messages, grouped by where they were sent.
+///
+/// ```
+/// use libzulip::events::unread::{UnreadCounts, UnreadMessages};
+///
+/// let snapshot = r#"{
+///     "count": 4,
+///     "pms": [{"other_user_id": 8, "unread_message_ids": [20]}],
+///     "streams": [
+///         {"stream_id": 3, "topic": "lunch", "unread_message_ids": [11, 12]},
+///         {"stream_id": 3, "topic": "dinner", "unread_message_ids": [10]}
+///     ],
+///     "huddles": [],
+///     "mentions": [12],
+///     "old_unreads_missing": false
+/// }"#;
+///
+/// let unread = serde_json::from_str::<UnreadMessages>(snapshot).unwrap();
+/// let counts = UnreadCounts::from(unread);
+///
+/// assert_eq!(counts.streams[&3], vec![10, 11, 12]);
+/// assert_eq!(counts.in_stream(3), 3);
+/// assert_eq!(counts.pms[&8], vec![20]);
+/// assert_eq!(counts.mentions, vec![12]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UnreadCounts {
+    /// The total number of unread messages.
+    pub count: u64,
+    /// Unread channel message IDs, keyed by channel ID.
+    pub streams: HashMap<u64, Vec<u64>>,
+    /// Unread one-on-one direct message IDs, keyed by the other user's ID.
+    pub pms: HashMap<u64, Vec<u64>>,
+    /// Unread group direct message IDs, keyed by a comma-separated list of
+    /// the user IDs in the group.
+    pub huddles: HashMap<String, Vec<u64>>,
+    /// Unread messages that mention the current user.
+    pub mentions: Vec<u64>,
+    /// If `true`, the server only sent the most recent unreads, so these
+    /// counts are too low.
+    pub old_unreads_missing: bool,
+}
+
+impl UnreadCounts {
+    /// How many unread messages are in the given channel.
+    pub fn in_stream(&self, stream_id: u64) -> usize {
+        self.streams.get(&stream_id).map_or(0, Vec::len)
+    }
+}
+
+impl From<UnreadMessages> for UnreadCounts {
+    fn from(unread: UnreadMessages) -> Self {
+        let mut streams = HashMap::<u64, Vec<u64>>::new();
+        for topic in unread.streams {
+            streams
+                .entry(topic.stream_id)
+                .or_default()
+                .extend(topic.unread_message_ids);
+        }
+        for ids in streams.values_mut() {
+            ids.sort_unstable();
+        }
+
+        let pms = unread
+            .pms
+            .into_iter()
+            .filter_map(|pm| {
+                // older servers call this `sender_id`
+                let user_id = pm.other_user_id.or(pm.sender_id)?;
+                Some((user_id, pm.unread_message_ids))
+            })
+            .collect();
+
+        let huddles = unread
+            .huddles
+            .into_iter()
+            .map(|huddle| (huddle.user_ids_string, huddle.unread_message_ids))
+            .collect();
+
+        Self {
+            count: unread.count,
+            streams,
+            pms,
+            huddles,
+            mentions: unread.mentions,
+            old_unreads_missing: unread.old_unreads_missing,
+        }
+    }
+}
+
+/// The `unread_msgs` part of the register snapshot, as Zulip sends it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(default)]
+pub struct UnreadMessages {
+    /// The total number of unread messages.
+    pub count: u64,
+    /// Unread one-on-one direct messages.
+    pub pms: Vec<UnreadDirectMessages>,
+    /// Unread channel messages, grouped by topic.
+    pub streams: Vec<UnreadTopic>,
+    /// Unread group direct messages.
+    #[serde(alias = "direct_message_groups")]
+    pub huddles: Vec<UnreadGroupMessages>,
+    /// Unread messages that mention the current user.
+    pub mentions: Vec<u64>,
+    /// Whether some older unread messages were left out.
+    pub old_unreads_missing: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+pub struct UnreadDirectMessages {
+    /// The other user in the conversation.
+    pub other_user_id: Option<u64>,
+    /// The old name for `other_user_id`.
+    pub sender_id: Option<u64>,
+    pub unread_message_ids: Vec<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+pub struct UnreadTopic {
+    pub stream_id: u64,
+    pub topic: String,
+    pub unread_message_ids: Vec<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+pub struct UnreadGroupMessages {
+    /// The IDs of everyone in the group, separated by commas.
+    pub user_ids_string: String,
+    pub unread_message_ids: Vec<u64>,
+}
@@ -1,13 +1,28 @@
-use organizations::ServerSettingsCache;
-use reqwest::{Client as ReqwestClient, RequestBuilder, Url};
+use std::time::Duration;
 
-use crate::{config::ClientConfig, error::ZulipError};
+use organizations::{CachedLinkifiers, ServerSettings, ServerSettingsCache};
+use reqwest::{
+    header::RETRY_AFTER, Client as ReqwestClient, Method, RequestBuilder, Response, StatusCode, Url,
+};
+use serde::de::DeserializeOwned;
+use tokio::sync::RwLock;
+
+use crate::{
+    config::ClientConfig,
+    error::{ResponseError, ZulipError},
+    streams::{channel_directory::ChannelDirectory, subscriptions::Subscription},
+    users::get_own_user::OwnUser,
+};
 
 pub mod config;
 pub mod error;
+pub mod events;
 pub mod messages;
 pub mod narrow;
 pub mod organizations;
+pub mod streams;
+pub mod user_groups;
+pub mod users;
 
 pub mod build_info {
     include!(concat!(env!("OUT_DIR"), "/built.rs"));
@@ -30,6 +45,14 @@ pub struct Client {
     /// which allows modification. Instead, go for `self.api_url()`!
     __api_url: Url,
     client: ReqwestClient,
+
+    /// the current user, grabbed on the first call to `get_own_user`
+    own_user_cache: RwLock<Option<OwnUser>>,
+    /// the last linkifiers we grabbed, kept while the server's `ETag` says
+    /// they haven't changed
+    linkifiers_cache: RwLock<Option<CachedLinkifiers>>,
+    /// channel names and IDs, filled in when a lookup misses
+    channel_directory: RwLock<ChannelDirectory>,
 }
 
 impl Client {
@@ -38,23 +61,29 @@ impl Client {
         let server_address = conf.server_address.clone();
 
         let (reqwest_client, api_url) = futures::join! {
-            Self::make_reqwest_client(),
+            Self::make_reqwest_client(&conf),
             Self::make_api_url(&server_address),
         };
+        let reqwest_client = reqwest_client?;
 
         let server_settings_cache = ServerSettingsCache::new(
-            reqwest_client,
+            reqwest_client.clone(),
             &api_url,
             conf.server_settings_cache_interval.clone(),
         )
-        .await?;
+        .await
+        .map_err(|e| conf.proxy.blame(e))?;
 
         let client = Client {
             conf,
             server_settings_cache,
 
             __api_url: api_url,
-            client: ReqwestClient::new(),
+            client: reqwest_client,
+
+            own_user_cache: RwLock::new(None),
+            linkifiers_cache: RwLock::new(None),
+            channel_directory: RwLock::new(ChannelDirectory::default()),
         };
 
         Ok(client)
@@ -63,6 +92,73 @@ impl Client {
     pub fn reqwest_client(&self) -> ReqwestClient {
         self.client.clone()
     }
+
+    /// Checks that the server is at least at the given feature level,
+    /// returning a `ZulipError::FeatureUnsupported` if it isn't.
+    ///
+    /// This uses the cached server settings, so it usually won't make a
+    /// request. Endpoints that were added in newer Zulip versions call this
+    /// before doing anything else.
+    pub async fn require_feature_level(&self, min: u64) -> Result<(), ZulipError> {
+        let available = self.server_settings_cache.get().await?.zulip_feature_level;
+
+        if available < min {
+            tracing::warn!("the server is too old! (needs feature level {min}, has {available})");
+            return Err(ZulipError::FeatureUnsupported {
+                required: min,
+                available,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the server is up and talking to us.
+    ///
+    /// This grabs the server settings, which doesn't need authentication, so
+    /// it won't catch a bad API key. If we can't reach the server at all,
+    /// you'll get a `ZulipError::ConnectionFailed` (or a
+    /// `ZulipError::ProxyConnectionFailed`, when using a custom proxy).
+    #[tracing::instrument(skip(self))]
+    pub async fn check_connection(&self) -> Result<(), ZulipError> {
+        self.server_settings_cache
+            .fetch_uncached()
+            .await
+            .map_err(|e| self.conf.proxy.blame(e))?;
+
+        tracing::trace!("the server is up!");
+        Ok(())
+    }
+
+    /// Grabs what most clients need at startup, all at once: the current
+    /// user, the server settings, and the user's subscriptions.
+    #[tracing::instrument(skip(self))]
+    pub async fn bootstrap(&self) -> Result<Bootstrap, ZulipError> {
+        let (own_user, server_settings, subscriptions) = futures::join! {
+            self.get_own_user(),
+            self.server_settings_cache.get(),
+            self.get_subscribed_streams(false),
+        };
+
+        tracing::trace!("bootstrapped!");
+        Ok(Bootstrap {
+            own_user: own_user?,
+            server_settings: server_settings?,
+            subscriptions: subscriptions?,
+        })
+    }
+}
+
+/// Everything [`Client::bootstrap`] grabs.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct Bootstrap {
+    /// The current user.
+    pub own_user: OwnUser,
+    /// The server's settings.
+    pub server_settings: ServerSettings,
+    /// The channels the current user is subscribed to.
+    pub subscriptions: Vec<Subscription>,
 }
 
 impl Client {
@@ -73,12 +169,37 @@ impl Client {
     async fn make_api_url(server_address: &Url) -> Url {
         let addr = server_address.clone();
 
-        tokio::task::spawn_blocking(move || {
-            addr.join("/api/v1/")
-                .expect("the api part of the addr should always be correct")
-        })
-        .await
-        .expect("the tokio task for modifying a url should never panic")
+        tokio::task::spawn_blocking(move || Self::api_url_for(&addr))
+            .await
+            .expect("the tokio task for modifying a url should never panic")
+    }
+
+    /// Makes the API URL for a server address.
+    ///
+    /// The API lives under the server address, so this works for servers
+    /// installed in a subdirectory, too.
+    ///
+    /// ```
+    /// use libzulip::Client;
+    /// use reqwest::Url;
+    ///
+    /// let api_url = |addr| Client::api_url_for(&Url::parse(addr).unwrap()).to_string();
+    ///
+    /// assert_eq!(api_url("https://host"), "https://host/api/v1/");
+    /// assert_eq!(api_url("https://host/"), "https://host/api/v1/");
+    /// assert_eq!(api_url("https://host/zulip/"), "https://host/zulip/api/v1/");
+    /// assert_eq!(api_url("https://host/zulip"), "https://host/zulip/api/v1/");
+    /// ```
+    pub fn api_url_for(server_address: &Url) -> Url {
+        // without a trailing slash, `join` would replace the last segment
+        let mut addr = server_address.clone();
+        if !addr.path().ends_with('/') {
+            let path = format!("{}/", addr.path());
+            addr.set_path(&path);
+        }
+
+        addr.join("api/v1/")
+            .expect("the api part of the addr should always be correct")
     }
 
     /// Makes the API URL (for example, `https://my.url/api/v1/`) from the
@@ -87,10 +208,210 @@ impl Client {
         self.__api_url.clone()
     }
 
-    async fn make_reqwest_client() -> ReqwestClient {
-        tokio::task::spawn_blocking(ReqwestClient::new)
+    /// Sends all future requests to a different server.
+    ///
+    /// This is mostly for tests: build the client as usual, then point it at
+    /// a mock server that answers however the test needs. Everything cached
+    /// belonged to the old server, so it's all dropped: the server settings
+    /// are grabbed again on the next request that needs them, along with the
+    /// user, linkifiers, and channels.
+    ///
+    /// Note that `allow_http` still applies, so a plain `http://` mock needs
+    /// it set in the config.
+    pub fn set_server_address(&mut self, server_address: Url) {
+        let api_url = Self::api_url_for(&server_address);
+        tracing::debug!("pointing the client at `{api_url}`.");
+
+        self.server_settings_cache.set_api_url(api_url.clone());
+        self.server_settings_cache.invalidate();
+        self.__api_url = api_url;
+        self.conf.server_address = server_address;
+
+        *self.own_user_cache.get_mut() = None;
+        *self.linkifiers_cache.get_mut() = None;
+        *self.channel_directory.get_mut() = ChannelDirectory::default();
+    }
+
+    /// Makes the `reqwest` client that all requests go through, applying any
+    /// relevant settings from the config.
+    ///
+    /// The config's fields are public, so this checks the server address
+    /// again in case it was made without [`config::ClientConfigBuilder::build`].
+    async fn make_reqwest_client(conf: &ClientConfig) -> Result<ReqwestClient, ZulipError> {
+        config::check_scheme(&conf.server_address, conf.allow_http)?;
+
+        let mut builder = ReqwestClient::builder();
+
+        if let Some(timeout) = conf.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(connect_timeout) = conf.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        builder = conf.proxy.apply(builder)?;
+        if !conf.default_headers.is_empty() {
+            builder = builder.default_headers(config::header_map(&conf.default_headers)?);
+        }
+        for certificate in &conf.root_certificates {
+            for cert in certificate.load().await? {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+        if conf.http1_only {
+            builder = builder.http1_only();
+        }
+        if conf.server_address.scheme() == "http" {
+            tracing::warn!(
+                "talking to the server over plaintext http! don't do this outside of development."
+            );
+        } else if !conf.allow_http {
+            builder = builder.https_only(true);
+        }
+        if conf.danger_accept_invalid_certs {
+            tracing::warn!(
+                "accepting invalid TLS certificates! don't do this outside of development."
+            );
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
+        Ok(tokio::task::spawn_blocking(move || builder.build())
         .await
-        .expect("there was something wrong with your system configuration. `reqwest` was unable to find the required TLS library, or no system configuration was available.")
+        .expect("there was something wrong with your system configuration. `reqwest` was unable to find the required TLS library, or no system configuration was available.")?)
+    }
+
+    /// How long we'll ask callers to wait when the server rate limits us
+    /// without saying for how long.
+    const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+    /// Sends the given request to Zulip.
+    ///
+    /// Use this instead of `RequestBuilder::send`! It handles stuff that all
+    /// requests need to care about, like rate limiting.
+    pub(crate) async fn send_request(
+        &self,
+        request_builder: RequestBuilder,
+    ) -> Result<Response, ZulipError> {
+        self.send_request_inner(request_builder)
+            .await
+            .map_err(|e| self.conf.proxy.blame(e))
+    }
+
+    /// Does the actual work for `send_request`.
+    async fn send_request_inner(
+        &self,
+        request_builder: RequestBuilder,
+    ) -> Result<Response, ZulipError> {
+        let Some(ref retry) = self.conf.retry else {
+            let resp = request_builder.send().await?;
+            return Self::check_rate_limit(resp).await;
+        };
+
+        let request = request_builder.build()?;
+
+        // resending a `POST` could do the thing twice (like sending a message),
+        // so only do it if the user really wants us to
+        let idempotent = !matches!(*request.method(), Method::POST | Method::PATCH);
+        let may_retry = idempotent || retry.retry_non_idempotent;
+
+        let mut attempt = 0;
+        loop {
+            // when we're out of retries (or the body can't be cloned, like a
+            // streamed file), just send it one last time
+            let Some(this_try) = request
+                .try_clone()
+                .filter(|_| may_retry && attempt < retry.max_retries)
+            else {
+                let resp = self.client.execute(request).await?;
+                return Self::check_rate_limit(resp).await;
+            };
+
+            let delay = match self.client.execute(this_try).await {
+                Ok(resp) if resp.status().is_server_error() => retry.backoff(attempt),
+                Ok(resp) => match Self::check_rate_limit(resp).await {
+                    // waiting any less than asked would just get us limited
+                    // again, so a long wait is the caller's call to make
+                    Err(ZulipError::RateLimited { retry_after })
+                        if retry.respect_retry_after && retry_after > retry.max_delay =>
+                    {
+                        tracing::debug!("the server wants us to wait {retry_after:?}. giving up.");
+                        return Err(ZulipError::RateLimited { retry_after });
+                    }
+                    Err(ZulipError::RateLimited { retry_after }) if retry.respect_retry_after => {
+                        retry_after
+                    }
+                    Err(ZulipError::RateLimited { .. }) => retry.backoff(attempt),
+                    other => return other,
+                },
+                Err(e) if e.is_connect() || e.is_timeout() => retry.backoff(attempt),
+                Err(e) => return Err(e.into()),
+            };
+
+            attempt += 1;
+            tracing::debug!(
+                "request failed. retrying in {delay:?}... (attempt {attempt}/{})",
+                retry.max_retries
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Turns an HTTP 429 response into a `ZulipError::RateLimited`, passing
+    /// any other response through.
+    ///
+    /// Zulip says how long to wait in both the `Retry-After` header and the
+    /// `retry-after` field of the body. We check them in that order.
+    pub(crate) async fn check_rate_limit(resp: Response) -> Result<Response, ZulipError> {
+        if resp.status() != StatusCode::TOO_MANY_REQUESTS {
+            return Ok(resp);
+        }
+
+        let from_header = resp
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<f64>().ok());
+
+        let secs = match from_header {
+            Some(secs) => Some(secs),
+            None => serde_json::from_str::<RateLimitResponse>(&resp.text().await?)
+                .ok()
+                .and_then(|body| body.retry_after),
+        };
+
+        // absurdly long waits (like `1e300`) don't fit in a `Duration`, so
+        // they're cut down to the longest one that does
+        let retry_after = secs
+            .filter(|secs| secs.is_finite() && *secs >= 0.0)
+            .map_or(Self::DEFAULT_RETRY_AFTER, |secs| {
+                Duration::try_from_secs_f64(secs).unwrap_or(Duration::MAX)
+            });
+
+        tracing::warn!("hit the rate limit! retry after {retry_after:?}.");
+        Err(ZulipError::RateLimited { retry_after })
+    }
+
+    /// Reads a response's JSON body as `T`, handing back Zulip's error
+    /// instead if it sent one.
+    ///
+    /// Zulip reports most failures with an HTTP 4xx and a body holding the
+    /// error's `code` and `msg`. `Response::error_for_status` throws that body
+    /// away, so use this when callers might want to know what went wrong.
+    pub(crate) async fn read_json<T: DeserializeOwned>(
+        resp: Response,
+    ) -> Result<Result<T, ResponseError>, ZulipError> {
+        let Err(status_error) = resp.error_for_status_ref() else {
+            return Ok(Ok(resp.json::<T>().await?));
+        };
+
+        if !resp.status().is_client_error() {
+            return Err(status_error.into());
+        }
+
+        // if the body isn't one of zulip's errors, we can't say much more
+        match serde_json::from_str::<ResponseError>(&resp.text().await?) {
+            Ok(error) => Ok(Err(error)),
+            Err(_) => Err(status_error.into()),
+        }
     }
 
     /// Apply authentication to the created `RequestBuilder` using internal
@@ -101,3 +422,10 @@ impl Client {
         request_builder.basic_auth(self.conf.email.clone(), Some(self.conf.api_key.get()))
     }
 }
+
+/// The body Zulip sends along with an HTTP 429.
+#[derive(Debug, serde::Deserialize)]
+struct RateLimitResponse {
+    #[serde(rename = "retry-after")]
+    retry_after: Option<f64>,
+}
@@ -0,0 +1,117 @@
+use crate::{
+    error::{MessageError, ResponseError, ZulipError},
+    Client,
+};
+
+use super::edit_message::BasicMessageRepresentation;
+
+impl Client {
+    /// Grabs every file the current user has uploaded.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_attachments(&self) -> Result<Vec<Attachment>, ZulipError> {
+        let url = self.api_url().join("attachments")?;
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().get(url)))
+            .await?;
+
+        let resp = match Self::read_json::<GetAttachmentsResponse>(resp).await? {
+            Ok(GetAttachmentsResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(MessageError::GetAttachmentsFailed {
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        tracing::trace!("grabbed {} attachments!", resp.attachments.len());
+        Ok(resp.attachments)
+    }
+
+    /// Deletes one of the current user's uploaded files.
+    ///
+    /// Messages linking to the file will have a broken link afterward, so
+    /// this is best for files with no `messages`.
+    #[tracing::instrument(skip(self))]
+    pub async fn delete_attachment(&self, id: u64) -> Result<(), ZulipError> {
+        let url = self.api_url().join(&format!("attachments/{id}"))?;
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().delete(url)))
+            .await?;
+
+        if let Ok(DeleteAttachmentResponse { error: Some(error) }) | Err(error) =
+            Self::read_json::<DeleteAttachmentResponse>(resp).await?
+        {
+            error.warn_ignored();
+            return Err(MessageError::DeleteAttachmentFailed {
+                id,
+                code: error.code(),
+                error: error.to_string(),
+            }
+            .into());
+        }
+
+        tracing::trace!("deleted attachment `{id}`!");
+        Ok(())
+    }
+}
+
+/// A file uploaded to Zulip.
+///
+/// ```
+/// use libzulip::messages::attachments::Attachment;
+///
+/// let attachment = r#"{
+///     "id": 1,
+///     "name": "notes.txt",
+///     "path_id": "2/ce/2Xpnnwgh8JWKxBXtTfD6BHKV/notes.txt",
+///     "size": 6,
+///     "create_time": 1588145417000,
+///     "messages": [{"id": 102, "date_sent": 1588145424000}]
+/// }"#;
+///
+/// let attachment = serde_json::from_str::<Attachment>(attachment).unwrap();
+/// assert_eq!(attachment.name, "notes.txt");
+/// assert_eq!(attachment.messages[0].id, 102);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+pub struct Attachment {
+    /// The unique ID for the attachment.
+    pub id: u64,
+    /// Name of the uploaded file.
+    pub name: String,
+    /// A representation of the path of the file within the repository of
+    /// user-uploaded files. If the path_id of a file is `{realm_id}/ab/cdef/temp_file.py`,
+    /// its URL will be: `{server_url}/user_uploads/{realm_id}/ab/cdef/temp_file.py`.
+    pub path_id: String,
+    /// Size of the file in bytes.
+    pub size: u64,
+    /// Time when the attachment was uploaded as a UNIX timestamp multiplied by
+    /// 1000 (matching the format of getTime() in JavaScript).
+    pub create_time: u64,
+    /// Contains basic details on any Zulip messages that have been sent
+    /// referencing this uploaded file. This includes messages sent by any user
+    /// in the Zulip organization who sent a message containing a link to the
+    /// uploaded file.
+    pub messages: Vec<BasicMessageRepresentation>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GetAttachmentsResponse {
+    #[serde(flatten)]
+    error: Option<ResponseError>,
+    attachments: Vec<Attachment>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct DeleteAttachmentResponse {
+    #[serde(flatten)]
+    error: Option<ResponseError>,
+}
@@ -1,29 +1,51 @@
+use std::collections::HashMap;
+
+use futures::StreamExt as _;
+
 use crate::{
-    error::{MessageError, ResponseError, ZulipError},
+    error::{MessageError, ResponseError, ZulipError, ZulipErrorCode},
     Client,
 };
 
+/// How many deletes [`Client::delete_messages`] sends at once.
+pub const MAX_CONCURRENT_DELETES: usize = 8;
+
 impl Client {
     /// Permanently delete a message.
     ///
-    /// This endpoint is only available to organization administrators.
+    /// Administrators can delete any message. Depending on the organization's
+    /// settings, other users may be able to delete their own messages for a
+    /// while after sending them. Zulip's API has no "soft" delete - to hide
+    /// a message without deleting it, move it to another channel or topic
+    /// instead.
+    ///
+    /// If you aren't allowed to delete the message, this returns a
+    /// `MessageError::DeletionNotAllowed`.
     ///
     /// For more, see: https://zulip.com/help/delete-a-message#delete-a-message-completely
     pub async fn delete_message(&self, msg_id: u64) -> Result<(), ZulipError> {
         let url = self.api_url().join(&format!("messages/{msg_id}"))?;
 
         let resp = self
-            .auth(self.reqwest_client().delete(url))
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<DeletedMessageResponse>()
+            .send_request(self.auth(self.reqwest_client().delete(url)))
             .await?;
 
-        if let Some(error) = resp.error {
+        if let Ok(DeletedMessageResponse { error: Some(error) }) | Err(error) =
+            Self::read_json::<DeletedMessageResponse>(resp).await?
+        {
             error.warn_ignored();
+
+            if Self::is_deletion_denied(&error) {
+                return Err(MessageError::DeletionNotAllowed {
+                    id: msg_id,
+                    error: error.to_string(),
+                }
+                .into());
+            }
+
             return Err(MessageError::DeletionFailed {
                 id: msg_id,
+                code: error.code(),
                 error: error.to_string(),
             }
             .into());
@@ -31,6 +53,57 @@ impl Client {
 
         Ok(())
     }
+
+    /// Permanently deletes several messages, a few at a time.
+    ///
+    /// Each message is deleted on its own, so one failing (say, it's already
+    /// gone, or you can't delete it) doesn't stop the rest. The result for
+    /// each ID is in the returned map.
+    ///
+    /// At most [`MAX_CONCURRENT_DELETES`] requests are in flight at once.
+    #[tracing::instrument(skip(self))]
+    pub async fn delete_messages(&self, ids: &[u64]) -> HashMap<u64, Result<(), ZulipError>> {
+        let results = futures::stream::iter(ids.iter().copied())
+            .map(|id| async move { (id, self.delete_message(id).await) })
+            .buffer_unordered(MAX_CONCURRENT_DELETES)
+            .collect::<HashMap<_, _>>()
+            .await;
+
+        tracing::trace!(
+            "deleted {} of {} messages!",
+            results.values().filter(|res| res.is_ok()).count(),
+            ids.len()
+        );
+        results
+    }
+
+    /// Checks whether we can delete any message, without trying it.
+    ///
+    /// Only administrators (and owners) can. When this is `false`, we might
+    /// still be able to delete our own messages, but only the server knows
+    /// for sure.
+    pub async fn can_delete_any_message(&self) -> Result<bool, ZulipError> {
+        Ok(self.get_own_user().await?.is_admin)
+    }
+}
+
+// private
+impl Client {
+    /// Whether the server refused to delete a message because we aren't
+    /// allowed to.
+    ///
+    /// Zulip usually says so with a plain `BAD_REQUEST`, so we have to check
+    /// the message, too.
+    fn is_deletion_denied(error: &ResponseError) -> bool {
+        match error.code() {
+            ZulipErrorCode::Unauthorized | ZulipErrorCode::UnauthorizedPrincipal => true,
+            ZulipErrorCode::BadRequest => {
+                let msg = error.msg().to_lowercase();
+                msg.contains("permission") || msg.contains("time limit")
+            }
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -1,4 +1,6 @@
 use tempfile::NamedTempFile;
+use tokio::io::AsyncWriteExt as _;
+use tokio_util::sync::CancellationToken;
 
 use crate::{
     error::{FileError, ZulipError},
@@ -12,24 +14,66 @@ impl Client {
     where
         S: AsRef<str> + std::fmt::Debug + Send,
     {
-        let url = self.api_url().join(url.as_ref())?;
+        self.download_file_inner(url.as_ref()).await
+    }
+
+    /// Like [`Client::download_file`], but stops early when `cancel` is
+    /// cancelled, giving a `ZulipError::Cancelled`.
+    ///
+    /// Anything written to the temporary file so far is removed.
+    #[tracing::instrument(skip(self, cancel))]
+    pub async fn download_file_with_cancel<S>(
+        &self,
+        url: S,
+        cancel: &CancellationToken,
+    ) -> Result<NamedTempFile, ZulipError>
+    where
+        S: AsRef<str> + std::fmt::Debug + Send,
+    {
+        tokio::select! {
+            biased;
+            () = cancel.cancelled() => {
+                // the download is dropped here, and the temp file with it
+                tracing::debug!("the download was cancelled.");
+                Err(ZulipError::Cancelled)
+            }
+            result = self.download_file_inner(url.as_ref()) => result,
+        }
+    }
+}
+
+// private
+impl Client {
+    /// Streams the file into a temporary file as it arrives.
+    ///
+    /// The temp file deletes itself when dropped, so stopping partway (on an
+    /// error or a cancellation) doesn't leave anything behind.
+    async fn download_file_inner(&self, url: &str) -> Result<NamedTempFile, ZulipError> {
+        let url = self.api_url().join(url)?;
         tracing::info!("downloading file... (url: {url}");
 
-        let resp = self
-            .auth(self.reqwest_client().get(url))
-            .send()
+        let mut resp = self
+            .send_request(self.auth(self.reqwest_client().get(url)))
             .await?
             .error_for_status()?;
 
-        tracing::trace!("downloaded file successfully!");
-
         let temp_file = NamedTempFile::new()
             .map_err(|_| ZulipError::FileError(FileError::DownloadFailTempFile))?;
         let temp_file_path = temp_file.path();
 
         tracing::trace!("writing to disk at path `{}`...", temp_file_path.display());
 
-        tokio::fs::write(temp_file_path, resp.bytes().await?)
+        let mut file = tokio::fs::OpenOptions::new()
+            .write(true)
+            .open(temp_file_path)
+            .await
+            .map_err(|_| FileError::DownloadFailTempFile)?;
+        while let Some(chunk) = resp.chunk().await? {
+            file.write_all(&chunk)
+                .await
+                .map_err(|_| FileError::DownloadFailTempFile)?;
+        }
+        file.flush()
             .await
             .map_err(|_| FileError::DownloadFailTempFile)?;
 
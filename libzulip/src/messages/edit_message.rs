@@ -2,6 +2,8 @@ use std::collections::HashMap;
 
 use crate::{error::ZulipError, Client};
 
+use super::attachments::Attachment;
+
 impl Client {
     #[tracing::instrument(skip(self))]
     pub async fn edit_message(
@@ -18,8 +20,13 @@ impl Client {
             parameters.insert("topic", topic);
         }
 
-        // FIXME: propogate_mode should be given with editedmessage as an enum
-        parameters.insert("propagate_mode", "change_one".into());
+        parameters.insert(
+            "propagate_mode",
+            edited_message
+                .propagate_mode
+                .unwrap_or(PropagateMode::ChangeOne)
+                .to_string(),
+        );
 
         if let Some(noti_old) = edited_message.send_notification_to_old_thread {
             parameters.insert("send_notification_to_old_thread", noti_old.to_string());
@@ -35,9 +42,10 @@ impl Client {
         }
 
         let resp = self
-            .auth(self.reqwest_client().patch(url))
-            .form(&parameters)
-            .send()
+            .send_request(
+                self.auth(self.reqwest_client().patch(url))
+                    .form(&parameters),
+            )
             .await?
             .error_for_status()?;
 
@@ -68,12 +76,17 @@ pub struct EditedMessage {
     /// The channel ID to move the message(s) to, to request moving messages to
     /// another channel.
     pub stream_id: Option<u64>,
+    /// Which messages to change when editing the topic or channel. Defaults
+    /// to `PropagateMode::ChangeOne`.
+    pub propagate_mode: Option<PropagateMode>,
 }
 
 /// The edit mode for a channel, topic, or message: Which message(s) should be
 /// edited.
 ///
 /// This is always `message` (`Message`) when editing those.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PropagateMode {
     /// The target message and all following messages.
     ChangeLater,
@@ -83,6 +96,16 @@ pub enum PropagateMode {
     ChangeAll,
 }
 
+impl std::fmt::Display for PropagateMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            PropagateMode::ChangeLater => f.write_str("change_later"),
+            PropagateMode::ChangeOne => f.write_str("change_one"),
+            PropagateMode::ChangeAll => f.write_str("change_all"),
+        }
+    }
+}
+
 #[derive(Debug, serde::Deserialize)]
 pub struct EditedMessageResponse {
     /// Details on all files uploaded by the acting user whose only references
@@ -90,29 +113,10 @@ pub struct EditedMessageResponse {
     pub detached_uploads: Vec<DetachedUpload>,
 }
 
-#[derive(Debug, serde::Deserialize)]
-pub struct DetachedUpload {
-    /// The unique ID for the attachment.
-    pub id: u64,
-    /// Name of the uploaded file.
-    pub name: String,
-    /// A representation of the path of the file within the repository of
-    /// user-uploaded files. If the path_id of a file is `{realm_id}/ab/cdef/temp_file.py`,
-    /// its URL will be: `{server_url}/user_uploads/{realm_id}/ab/cdef/temp_file.py`.
-    pub path_id: String,
-    /// Size of the file in bytes.
-    pub size: u64,
-    /// Time when the attachment was uploaded as a UNIX timestamp multiplied by
-    /// 1000 (matching the format of getTime() in JavaScript).
-    pub create_time: u64,
-    /// Contains basic details on any Zulip messages that have been sent
-    /// referencing this uploaded file. This includes messages sent by any user
-    /// in the Zulip organization who sent a message containing a link to the
-    /// uploaded file.
-    pub messages: Vec<BasicMessageRepresentation>,
-}
+/// A file that's no longer used by any message after an edit.
+pub type DetachedUpload = Attachment;
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
 pub struct BasicMessageRepresentation {
     /// Time when the message was sent as a UNIX timestamp multiplied by 1000
     /// (matching the format of getTime() in JavaScript).
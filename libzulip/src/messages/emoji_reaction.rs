@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use crate::{
-    error::{MessageError, ResponseError, ZulipError},
+    error::{MessageError, ResponseError, ZulipError, ZulipErrorCode},
     Client,
 };
 
@@ -15,68 +15,83 @@ impl Client {
             .api_url()
             .join(format!("messages/{msg_id}/reactions").as_str())?;
 
+        // catch bad names before bothering the server
+        let selector = selector.validate()?;
+
         // create parameters
         let parameters = selector.make_parameters();
 
         // send the request
         let resp = self
-            .auth(self.reqwest_client().post(url))
-            .form(&parameters)
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<EmojiReactionResponse>()
+            .send_request(self.auth(self.reqwest_client().post(url)).form(&parameters))
             .await?;
 
-        tracing::trace!("added emoji reaction successfully!");
-
-        if let Some(error) = resp.error {
+        if let Ok(EmojiReactionResponse { error: Some(error) }) | Err(error) =
+            Self::read_json::<EmojiReactionResponse>(resp).await?
+        {
+            error.warn_ignored();
             return Err(MessageError::AddEmojiFailed {
                 msg_id,
                 emoji_name: selector.emoji_name,
+                code: error.code(),
                 error: error.to_string(),
             }
             .into());
         }
 
+        tracing::trace!("added emoji reaction successfully!");
+
         Ok(())
     }
 
+    /// Removes your emoji reaction from a message.
+    ///
+    /// Returns `false` if you hadn't reacted with that emoji, so there was
+    /// nothing to remove.
     pub async fn remove_emoji_reaction(
         &self,
         msg_id: u64,
         selector: EmojiSelector,
-    ) -> Result<(), ZulipError> {
+    ) -> Result<bool, ZulipError> {
         let url = self
             .api_url()
             .join(format!("messages/{msg_id}/reactions").as_str())?;
 
+        // catch bad names before bothering the server
+        let selector = selector.validate()?;
+
         // create parameters
         let parameters = selector.make_parameters();
 
         // send the request
         let resp = self
-            .auth(self.reqwest_client().delete(url))
-            .form(&parameters)
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<EmojiReactionResponse>()
+            .send_request(
+                self.auth(self.reqwest_client().delete(url))
+                    .form(&parameters),
+            )
             .await?;
 
-        tracing::trace!("removed emoji reaction successfully!");
+        if let Ok(EmojiReactionResponse { error: Some(error) }) | Err(error) =
+            Self::read_json::<EmojiReactionResponse>(resp).await?
+        {
+            if error.code() == ZulipErrorCode::ReactionDoesNotExist {
+                tracing::trace!("there was no emoji reaction to remove.");
+                return Ok(false);
+            }
 
-        if let Some(error) = resp.error {
             error.warn_ignored();
             return Err(MessageError::RemoveEmojiFailed {
                 msg_id,
                 emoji_name: selector.emoji_name,
+                code: error.code(),
                 error: error.to_string(),
             }
             .into());
         }
 
-        Ok(())
+        tracing::trace!("removed emoji reaction successfully!");
+
+        Ok(true)
     }
 }
 
@@ -117,12 +132,110 @@ impl EmojiSelector {
         }
     }
 
-    fn make_parameters(&self) -> HashMap<&str, String> {
-        // urlencode the emoji name
-        let emoji_name = urlencoding::encode(&self.emoji_name).to_string();
+    /// Creates a new `EmojiSelector` from a Unicode emoji, like the ones an
+    /// emoji picker gives you.
+    ///
+    /// Zulip wants the emoji's name along with its code, so this only knows
+    /// about the commonly used emoji in a small bundled table. Others give
+    /// `None` - grab their names with
+    /// [`Client::get_server_emoji_reaction_data`] instead.
+    ///
+    /// See [`EmojiSelector::from_unicode_str`] for emoji made of more than
+    /// one character.
+    ///
+    /// ```
+    /// use libzulip::messages::emoji_reaction::{EmojiSelector, ReactionType};
+    ///
+    /// let heart = EmojiSelector::from_unicode('❤').unwrap();
+    /// assert_eq!(heart.emoji_name, "heart");
+    /// assert_eq!(heart.emoji_code.as_deref(), Some("2764"));
+    /// assert_eq!(heart.reaction_type, Some(ReactionType::UnicodeEmoji));
+    ///
+    /// assert_eq!(EmojiSelector::from_unicode('a'), None);
+    /// ```
+    pub fn from_unicode(emoji: char) -> Option<Self> {
+        Self::from_unicode_str(emoji.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Like [`EmojiSelector::from_unicode`], but takes the whole emoji as a
+    /// string. Use this for emoji made of many codepoints, like flags.
+    ///
+    /// Variation selectors (`U+FE0F`) are left out of the code, as Zulip
+    /// doesn't use them.
+    ///
+    /// ```
+    /// use libzulip::messages::emoji_reaction::EmojiSelector;
+    ///
+    /// // a flag is two "regional indicator" letters
+    /// let usa = EmojiSelector::from_unicode_str("🇺🇸").unwrap();
+    /// assert_eq!(usa.emoji_name, "usa");
+    /// assert_eq!(usa.emoji_code.as_deref(), Some("1f1fa-1f1f8"));
+    ///
+    /// // pickers often add a variation selector
+    /// assert_eq!(
+    ///     EmojiSelector::from_unicode_str("❤\u{fe0f}"),
+    ///     EmojiSelector::from_unicode('❤')
+    /// );
+    /// ```
+    pub fn from_unicode_str(emoji: &str) -> Option<Self> {
+        let emoji_code = emoji
+            .chars()
+            .filter(|&c| c != '\u{fe0f}')
+            .map(|c| format!("{:x}", u32::from(c)))
+            .collect::<Vec<_>>()
+            .join("-");
+
+        let (_, name) = UNICODE_EMOJI_NAMES
+            .iter()
+            .find(|&&(code, _)| code == emoji_code)?;
+
+        Some(Self {
+            emoji_name: (*name).into(),
+            emoji_code: Some(emoji_code),
+            reaction_type: Some(ReactionType::UnicodeEmoji),
+        })
+    }
+
+    /// Checks that the emoji name looks like one Zulip would accept, giving
+    /// back the selector with its name cleaned up.
+    ///
+    /// Emoji names are made of lowercase letters, digits, `_`, `-`, and `+`.
+    /// Spaces become underscores, since that's how Zulip spells them.
+    ///
+    /// The reaction methods call this for you, but it's handy for checking
+    /// user input early.
+    ///
+    /// ```
+    /// use libzulip::messages::emoji_reaction::EmojiSelector;
+    ///
+    /// let heart = EmojiSelector::new_from_name("heart").validate().unwrap();
+    /// assert_eq!(heart.emoji_name, "heart");
+    ///
+    /// let smile = EmojiSelector::new_from_name("big smile").validate().unwrap();
+    /// assert_eq!(smile.emoji_name, "big_smile");
+    ///
+    /// assert!(EmojiSelector::new_from_name("<3").validate().is_err());
+    /// ```
+    pub fn validate(mut self) -> Result<Self, MessageError> {
+        let name = self.emoji_name.trim().replace(' ', "_");
 
+        let allowed =
+            |c: char| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '-' | '+');
+        if name.is_empty() || !name.chars().all(allowed) {
+            return Err(MessageError::InvalidEmojiName {
+                emoji_name: self.emoji_name,
+            });
+        }
+
+        self.emoji_name = name;
+        Ok(self)
+    }
+
+    fn make_parameters(&self) -> HashMap<&str, String> {
+        // these go in a form, which encodes them for us. encoding the name
+        // here too would send `+1` as `%2B1`
         let mut parameters = HashMap::new();
-        parameters.insert("emoji_name", emoji_name);
+        parameters.insert("emoji_name", self.emoji_name.clone());
 
         if let Some(emoji_code) = self.emoji_code.clone() {
             parameters.insert("emoji_code", emoji_code);
@@ -163,6 +276,64 @@ impl std::fmt::Display for ReactionType {
     }
 }
 
+/// Zulip's names for commonly used Unicode emoji, keyed by `emoji_code`.
+///
+/// Used by [`EmojiSelector::from_unicode`]. The full list is huge, and
+/// servers have it anyway.
+const UNICODE_EMOJI_NAMES: &[(&str, &str)] = &[
+    ("1f600", "grinning"),
+    ("1f603", "smiley"),
+    ("1f604", "smile"),
+    ("1f605", "sweat_smile"),
+    ("1f606", "laughing"),
+    ("1f609", "wink"),
+    ("1f60d", "heart_eyes"),
+    ("1f602", "joy"),
+    ("1f622", "cry"),
+    ("1f62d", "sob"),
+    ("1f914", "thinking"),
+    ("1f937", "shrug"),
+    ("1f44d", "+1"),
+    ("1f44e", "-1"),
+    ("1f44f", "clap"),
+    ("1f44b", "wave"),
+    ("1f64f", "pray"),
+    ("1f440", "eyes"),
+    ("2764", "heart"),
+    ("1f389", "tada"),
+    ("1f680", "rocket"),
+    ("1f525", "fire"),
+    ("1f4af", "100"),
+    ("2705", "check"),
+    ("1f6e0", "working_on_it"),
+    ("1f419", "octopus"),
+    ("1f1fa-1f1f8", "usa"),
+];
+
+/// Turns a Unicode emoji's `emoji_code` into the emoji itself.
+///
+/// The code is the emoji's codepoints in hex, separated by dashes. Returns
+/// `None` if any part isn't a valid codepoint.
+///
+/// ```
+/// use libzulip::messages::emoji_reaction::unicode_emoji;
+///
+/// assert_eq!(unicode_emoji("1f600").as_deref(), Some("😀"));
+/// // the man technologist is a man, a zero-width joiner, and a laptop
+/// assert_eq!(unicode_emoji("1f468-200d-1f4bb").as_deref(), Some("👨‍💻"));
+/// assert_eq!(unicode_emoji("zulip"), None);
+/// ```
+pub fn unicode_emoji(emoji_code: &str) -> Option<String> {
+    emoji_code
+        .split('-')
+        .map(|codepoint| {
+            u32::from_str_radix(codepoint, 16)
+                .ok()
+                .and_then(char::from_u32)
+        })
+        .collect()
+}
+
 #[derive(Clone, Debug, serde::Deserialize)]
 pub struct EmojiReactionResponse {
     #[serde(flatten)]
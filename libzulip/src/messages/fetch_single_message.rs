@@ -1,11 +1,11 @@
-use std::collections::HashMap;
-
 use crate::{
     error::{MessageError, ResponseError, ZulipError},
+    events::get_events::{ReactionEvent, ReactionOp},
+    narrow::{NameOrId, Narrow, NarrowKind, NarrowList, NarrowNegation, OneOrMany},
     Client,
 };
 
-use super::emoji_reaction::ReactionType;
+use super::emoji_reaction::{unicode_emoji, ReactionType};
 
 impl Client {
     /// Given a message ID, return the message object.
@@ -15,45 +15,143 @@ impl Client {
     /// need to occasionally fetch the message's raw Markdown (e.g. for view
     /// source or prefilling a message edit textarea).
     ///
-    /// Note: you probably want `apply_markdown` to be `false`, as this decides
-    /// if the returned message will be in rendered (HTML) form or if it'll
-    /// keep the user's original `markdown` (`false`).
+    /// Note: you probably want `ContentFormat::RawMarkdown`, which keeps the
+    /// user's original Markdown. `ContentFormat::RenderedHtml` gives you the
+    /// message rendered as HTML instead.
+    ///
+    /// Set `allow_empty_topic_name` if you can handle messages with an empty
+    /// topic. Otherwise, the server swaps it out for its own placeholder name.
+    /// This needs feature level 334, but older servers just ignore it.
     ///
     /// TODO: fix when not broken: https://github.com/zulip/zulip/issues/31832
     pub async fn fetch_single_message(
         &self,
         msg_id: u64,
-        apply_markdown: bool,
+        format: ContentFormat,
+        allow_empty_topic_name: bool,
     ) -> Result<SingleMessageResponse, ZulipError> {
-        let url = self
-            .api_url()
-            .join(format!("messages/{msg_id}").as_str())?
-            .query_pairs_mut()
-            .append_pair(
-                "apply_markdown",
-                &serde_json::Value::Bool(apply_markdown).to_string(),
-            )
-            .finish()
-            .to_owned();
+        let mut url = self.api_url().join(format!("messages/{msg_id}").as_str())?;
+
+        url.query_pairs_mut()
+            .append_pair("apply_markdown", &format.apply_markdown().to_string());
+        if allow_empty_topic_name {
+            url.query_pairs_mut()
+                .append_pair("allow_empty_topic_name", "true");
+        }
 
         let resp = self
-            .auth(self.reqwest_client().get(url))
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<SingleMessageResponse>()
+            .send_request(self.auth(self.reqwest_client().get(url)))
             .await?;
 
-        if let Some(error) = resp.error {
-            error.warn_ignored();
-            return Err(MessageError::SingleMessageFetchFailed {
-                msg_id,
-                error: error.to_string(),
+        match Self::read_json::<SingleMessageResponse>(resp).await? {
+            Ok(SingleMessageResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                Err(MessageError::SingleMessageFetchFailed {
+                    msg_id,
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into())
+            }
+            Ok(mut resp) => {
+                // the server sends this next to the message, not in it
+                if resp.message.raw_content.is_none() {
+                    resp.message.raw_content = resp.raw_content.take();
+                }
+                Ok(resp)
             }
-            .into());
         }
+    }
+
+    /// Grabs just the reactions on a message.
+    ///
+    /// This is handy for refreshing a message's reactions without caring
+    /// about the rest of it. Messages without reactions give an empty list.
+    pub async fn get_message_reactions(&self, msg_id: u64) -> Result<Vec<Emoji>, ZulipError> {
+        let resp = self
+            .fetch_single_message(msg_id, ContentFormat::RawMarkdown, false)
+            .await?;
+
+        Ok(resp.message.reactions.unwrap_or_default())
+    }
+
+    /// Grabs just the original Markdown of a message.
+    ///
+    /// This is what you'd want to prefill a box for editing the message.
+    pub async fn get_message_markdown(&self, msg_id: u64) -> Result<String, ZulipError> {
+        let message = self
+            .fetch_single_message(msg_id, ContentFormat::RawMarkdown, true)
+            .await?
+            .message;
 
-        Ok(resp)
+        // without rendering, `content` is already the markdown
+        Ok(message.raw_content.unwrap_or(message.content))
+    }
+
+    /// Grabs a message's rendered HTML and its original Markdown together.
+    ///
+    /// This is usually one request, as servers send the Markdown along with
+    /// a rendered message. Newer servers may stop doing that, so we ask for
+    /// the Markdown separately when it's missing.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_message_contents(&self, msg_id: u64) -> Result<MessageContents, ZulipError> {
+        let message = self
+            .fetch_single_message(msg_id, ContentFormat::RenderedHtml, true)
+            .await?
+            .message;
+
+        let raw_content = if let Some(raw_content) = message.raw_content {
+            raw_content
+        } else {
+            tracing::debug!("the server didn't send the markdown. grabbing it...");
+            self.get_message_markdown(msg_id).await?
+        };
+
+        Ok(MessageContents {
+            content: message.content,
+            raw_content,
+        })
+    }
+}
+
+/// Both versions of a message's content, from
+/// [`Client::get_message_contents`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MessageContents {
+    /// The content, rendered as HTML.
+    pub content: String,
+    /// The original Markdown.
+    pub raw_content: String,
+}
+
+/// Which form message content should come in.
+///
+/// Zulip calls this `apply_markdown`.
+///
+/// ```
+/// use libzulip::messages::fetch_single_message::ContentFormat;
+///
+/// assert!(ContentFormat::RenderedHtml.apply_markdown());
+/// assert!(!ContentFormat::RawMarkdown.apply_markdown());
+/// assert_eq!(ContentFormat::default(), ContentFormat::RawMarkdown);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ContentFormat {
+    /// The content, rendered as HTML. This is what Zulip's web app shows.
+    RenderedHtml,
+    /// The original Markdown the sender wrote. Handy for editing, or for
+    /// clients that render it themselves.
+    #[default]
+    RawMarkdown,
+}
+
+impl ContentFormat {
+    /// The value of Zulip's `apply_markdown` parameter for this format.
+    pub fn apply_markdown(self) -> bool {
+        self == ContentFormat::RenderedHtml
     }
 }
 
@@ -64,6 +162,11 @@ pub struct SingleMessageResponse {
     pub error: Option<ResponseError>,
     /// An object containing details of the message.
     pub message: Message,
+    /// The message's original Markdown. This is also moved into
+    /// `Message::raw_content`.
+    ///
+    /// Deprecated by Zulip - only older servers send it.
+    pub raw_content: Option<String>,
 }
 
 /// A representation of a message. Contains most important details.
@@ -76,10 +179,18 @@ pub struct Message {
     pub client: String,
     /// The content/body of the message.
     pub content: String,
+    /// The message's original Markdown, even when `content` was rendered.
+    ///
+    /// Only present when fetching a single message from a server that sends
+    /// it.
+    #[serde(default)]
+    pub raw_content: Option<String>,
     /// The HTTP content_type for the message content. This will be `text/html`
-    /// or `text/x-markdown`, depending on whether `apply_markdown` was set.
+    /// or `text/x-markdown`, depending on the [`ContentFormat`] asked for.
     pub content_type: String,
-    // `display_recipient: DisplayRecipient`, // TODO: not sure what the Users variant should contain...
+    /// Who the message was sent to: a channel's name, or the people in a
+    /// direct message conversation.
+    pub display_recipient: DisplayRecipient,
     /// An array of changes made to the message.
     pub edit_history: Option<Vec<MessageEdit>>,
     /// The unique message ID. Messages should always be displayed sorted by ID.
@@ -114,27 +225,173 @@ pub struct Message {
     pub topic_links: Vec<Link>,
     #[serde(rename = "type")]
     pub typ: MessageType,
+    // message events send these next to the message instead
+    #[serde(default)]
     pub flags: Vec<String>, // FIXME: this should use a `MessageFlags` type later on
+    /// The message's content, with any search keyword matches highlighted.
+    ///
+    /// Only present when grabbing messages with a keyword narrow.
+    pub match_content: Option<String>,
+    /// The message's topic, with any search keyword matches highlighted.
+    ///
+    /// Only present when grabbing messages with a keyword narrow.
+    #[serde(rename = "match_subject")]
+    pub match_topic: Option<String>,
 }
 
-#[derive(Debug, serde::Deserialize)]
+impl Message {
+    /// Makes a narrow for the conversation this message is in: its channel
+    /// and topic, or everyone in its direct message conversation.
+    pub fn conversation(&self) -> NarrowList {
+        let kind = match (self.stream_id, &self.display_recipient) {
+            (Some(stream_id), _) => NarrowKind::ChannelWithTopic {
+                channel: NameOrId::Id(stream_id),
+                topic: NameOrId::Name(self.subject.clone()),
+            },
+            (None, DisplayRecipient::ChannelName(name)) => NarrowKind::ChannelWithTopic {
+                channel: NameOrId::Name(name.clone()),
+                topic: NameOrId::Name(self.subject.clone()),
+            },
+            (None, DisplayRecipient::Users(users)) => NarrowKind::DirectMessage(OneOrMany::Many(
+                users.iter().map(|user| NameOrId::Id(user.id)).collect(),
+            )),
+        };
+
+        vec![Narrow::new(kind, NarrowNegation::Normal)]
+    }
+
+    /// Updates this message's reactions with a reaction event, returning
+    /// whether anything changed.
+    ///
+    /// A reaction is the same one if its emoji code, type, and user match.
+    /// Adding a reaction that's already there (or removing one that isn't)
+    /// does nothing, and so do events for other messages.
+    ///
+    /// ```
+    /// use libzulip::{
+    ///     events::get_events::{Event, EventKind},
+    ///     messages::fetch_single_message::Message,
+    /// };
+    ///
+    /// let mut msg: Message = serde_json::from_str(r#"{
+    ///     "id": 32, "client": "test", "content": "hi", "content_type": "text/x-markdown",
+    ///     "display_recipient": "general", "is_me_message": false, "recipient_id": 1,
+    ///     "sender_email": "iago@zulip.com", "sender_full_name": "Iago", "sender_id": 10,
+    ///     "sender_realm_str": "zulip", "stream_id": 1, "subject": "lunch", "timestamp": 0,
+    ///     "topic_links": [], "type": "stream", "reactions": []
+    /// }"#).unwrap();
+    ///
+    /// let reaction = |op| {
+    ///     let event = format!(r#"{{
+    ///         "id": 1, "type": "reaction", "op": "{op}", "message_id": 32, "user_id": 10,
+    ///         "emoji_name": "octopus", "emoji_code": "1f419", "reaction_type": "unicode_emoji"
+    ///     }}"#);
+    ///     match serde_json::from_str::<Event>(&event).unwrap().kind {
+    ///         EventKind::Reaction(reaction) => reaction,
+    ///         _ => panic!("wrong kind"),
+    ///     }
+    /// };
+    ///
+    /// assert!(msg.apply_reaction_event(&reaction("add")));
+    /// assert!(!msg.apply_reaction_event(&reaction("add"))); // already there
+    /// assert_eq!(msg.reactions.as_ref().unwrap().len(), 1);
+    ///
+    /// assert!(msg.apply_reaction_event(&reaction("remove")));
+    /// assert!(msg.reactions.as_ref().unwrap().is_empty());
+    /// ```
+    pub fn apply_reaction_event(&mut self, event: &ReactionEvent) -> bool {
+        if event.message_id != self.id {
+            return false;
+        }
+
+        let reactions = self.reactions.get_or_insert_with(Vec::new);
+        let existing = reactions.iter().position(|emoji| {
+            emoji.user_id == event.user_id
+                && emoji.emoji_code.as_deref() == Some(event.emoji_code.as_str())
+                // reactions without a type are unicode ones
+                && emoji
+                    .reaction_type
+                    .as_ref()
+                    .unwrap_or(&ReactionType::UnicodeEmoji)
+                    == &event.reaction_type
+        });
+
+        match (event.op, existing) {
+            (ReactionOp::Add, None) => {
+                reactions.push(Emoji {
+                    emoji_name: event.emoji_name.clone(),
+                    emoji_code: Some(event.emoji_code.clone()),
+                    reaction_type: Some(event.reaction_type.clone()),
+                    user_id: event.user_id,
+                });
+                true
+            }
+            (ReactionOp::Remove, Some(index)) => {
+                reactions.remove(index);
+                true
+            }
+            (ReactionOp::Add, Some(_)) | (ReactionOp::Remove, None) => false,
+        }
+    }
+}
+
+/// Who a message was sent to.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(untagged)]
 pub enum DisplayRecipient {
+    /// For channel messages, the channel's name.
     ChannelName(String),
-    Users {
-        // TODO
-    },
+    /// For direct messages, everyone in the conversation, including the
+    /// sender.
+    Users(Vec<RecipientUser>),
+}
+
+/// Someone in a direct message conversation.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[non_exhaustive]
+pub struct RecipientUser {
+    /// The user's ID.
+    pub id: u64,
+    /// The user's Zulip API email address.
+    pub email: String,
+    /// The user's full name.
+    pub full_name: String,
 }
 
 /// Documents the changes in a previous edit made to the message.
+///
+/// ```
+/// use libzulip::messages::fetch_single_message::MessageEdit;
+///
+/// // a topic was renamed, and the message moved to another channel
+/// let edit: MessageEdit = serde_json::from_str(
+///     r#"{"prev_topic": "lunch", "topic": "dinner", "prev_stream": 3, "stream": 5, "timestamp": 1594825451, "user_id": 10}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(edit.prev_topic.as_deref(), Some("lunch"));
+/// assert_eq!(edit.topic.as_deref(), Some("dinner"));
+/// assert_eq!((edit.prev_stream, edit.stream), (Some(3), Some(5)));
+/// ```
 #[derive(Debug, serde::Deserialize)]
 pub struct MessageEdit {
+    /// The content before this edit, if the content changed.
     pub prev_content: Option<String>,
+    /// The rendered content before this edit, if the content changed.
     pub prev_rendered_content: Option<String>,
+    /// The ID of the channel the message was in before this edit, if it was
+    /// moved.
     pub prev_stream: Option<u64>,
-    pub prev_topic: Option<u64>,
+    /// The topic before this edit, if it changed. Older servers call this
+    /// `prev_subject`.
+    #[serde(alias = "prev_subject")]
+    pub prev_topic: Option<String>,
+    /// The ID of the channel the message was moved to, if it was moved.
     pub stream: Option<u64>,
+    /// When the edit happened, in UNIX seconds.
     pub timestamp: u64,
+    /// The topic after this edit, if it changed.
     pub topic: Option<String>,
+    /// Who made the edit. `None` for edits made before Zulip kept track.
     pub user_id: Option<u64>,
 }
 
@@ -161,15 +418,47 @@ pub struct Emoji {
     pub user_id: u64,
 }
 
+impl Emoji {
+    /// Returns the emoji itself, if it's a Unicode emoji.
+    ///
+    /// Reactions without a type are treated as Unicode ones, since that's
+    /// Zulip's default. For custom emoji, look up the `emoji_code` in
+    /// [`Client::get_realm_emoji`] instead.
+    pub fn unicode(&self) -> Option<String> {
+        match self.reaction_type {
+            Some(ReactionType::UnicodeEmoji) | None => unicode_emoji(self.emoji_code.as_deref()?),
+            Some(_) => None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, serde::Deserialize)]
 pub struct Link {
     pub text: String,
     pub url: String,
 }
 
-#[derive(Clone, Debug, serde::Deserialize)]
+/// Whether a message was sent to a channel or directly to users.
+///
+/// Zulip has renamed these over time, so every spelling is accepted:
+///
+/// ```
+/// use libzulip::messages::fetch_single_message::MessageType;
+///
+/// let parse = |s| serde_json::from_str::<MessageType>(s).unwrap();
+///
+/// assert_eq!(parse(r#""stream""#), MessageType::Stream);
+/// assert_eq!(parse(r#""channel""#), MessageType::Stream);
+/// assert_eq!(parse(r#""private""#), MessageType::Private);
+/// assert_eq!(parse(r#""direct""#), MessageType::Private);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum MessageType {
+    /// A message in a channel. (also called `channel`)
+    #[serde(alias = "channel")]
     Stream,
+    /// A direct message. (also called `direct`)
+    #[serde(alias = "direct")]
     Private,
 }
@@ -0,0 +1,378 @@
+use std::collections::HashMap;
+
+use futures::Stream;
+
+use crate::{
+    error::{MessageError, ResponseError, ZulipError, ZulipErrorCode},
+    narrow::{NarrowList, NarrowListExt},
+    Client,
+};
+
+use super::fetch_single_message::{ContentFormat, Message};
+
+impl Client {
+    /// Grabs a batch of messages around an anchor, filtered by a narrow.
+    ///
+    /// When the narrow has a keyword (`search`) in it, each message comes
+    /// with `match_content` and `match_topic`, which highlight the matching
+    /// text. Use them to show search results.
+    ///
+    /// The narrow is checked with [`NarrowListExt::validate`] first, so
+    /// contradictory filters fail without a request.
+    ///
+    /// Archived channels keep their messages, so narrow by the channel's ID
+    /// to grab them. If the organization's plan hides older messages, the
+    /// response's `history_limited` is set.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_messages(
+        &self,
+        query: GetMessages,
+    ) -> Result<GetMessagesResponse, ZulipError> {
+        query.narrow.validate()?;
+
+        let url = self.api_url().join("messages")?;
+        let feature_level = self.server_settings_cache.get().await?.zulip_feature_level;
+        let parameters = query.make_parameters(feature_level);
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().get(url)).query(&parameters))
+            .await?;
+
+        let resp = match Self::read_json::<GetMessagesResponse>(resp).await? {
+            Ok(GetMessagesResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(MessageError::FetchMessagesFailed {
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        if resp.history_limited {
+            tracing::debug!("some older messages were hidden by the organization's plan.");
+        }
+
+        tracing::trace!("grabbed {} messages!", resp.messages.len());
+        Ok(resp)
+    }
+
+    /// Grabs the messages with the given IDs, in the same order.
+    ///
+    /// Messages that don't exist (or that you can't see) are `None`, so the
+    /// result always lines up with `ids`. Content is left as Markdown.
+    ///
+    /// From feature level 300, this is a single request. Older servers get
+    /// one request per message.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_messages_by_ids(
+        &self,
+        ids: &[u64],
+    ) -> Result<Vec<Option<Message>>, ZulipError> {
+        let feature_level = self.server_settings_cache.get().await?.zulip_feature_level;
+
+        let mut found = if feature_level >= 300 {
+            self.request_messages_by_ids(ids).await?
+        } else {
+            let mut found = HashMap::new();
+            for &id in ids {
+                match self
+                    .fetch_single_message(id, ContentFormat::RawMarkdown, true)
+                    .await
+                {
+                    Ok(resp) => {
+                        found.insert(id, resp.message);
+                    }
+                    // zulip says "invalid message(s)" for both missing and
+                    // hidden messages
+                    Err(ZulipError::MessageError(MessageError::SingleMessageFetchFailed {
+                        code: ZulipErrorCode::BadRequest,
+                        ..
+                    })) => (),
+                    Err(e) => return Err(e),
+                }
+            }
+            found
+        };
+
+        let messages = ids.iter().map(|id| found.remove(id)).collect::<Vec<_>>();
+        tracing::trace!(
+            "grabbed {} of {} messages!",
+            messages.iter().flatten().count(),
+            ids.len()
+        );
+        Ok(messages)
+    }
+
+    /// Grabs every message matching the narrow, from newest to oldest.
+    ///
+    /// This pages backward through the messages with `get_messages` until
+    /// the server says it found the oldest one. Only one page is held at a
+    /// time, so it's fine for long exports or scans.
+    ///
+    /// If grabbing a page fails, the stream yields the error and ends.
+    pub fn get_all_messages(
+        &self,
+        narrow: NarrowList,
+        options: GetAllMessages,
+    ) -> impl Stream<Item = Result<Message, ZulipError>> + '_ {
+        let state = Paging {
+            next_anchor: Some(Anchor::Newest),
+            page: Vec::new(),
+        };
+
+        futures::stream::unfold(state, move |mut state| {
+            let narrow = narrow.clone();
+
+            async move {
+                // pages come oldest-first, so the newest is at the back
+                if let Some(msg) = state.page.pop() {
+                    return Some((Ok(msg), state));
+                }
+
+                let anchor = state.next_anchor.take()?;
+                let query = GetMessages {
+                    anchor,
+                    num_before: options.page_size,
+                    num_after: 0,
+                    include_anchor: anchor == Anchor::Newest,
+                    narrow,
+                    format: options.format,
+                };
+
+                let resp = match self.get_messages(query).await {
+                    Ok(resp) => resp,
+                    Err(e) => return Some((Err(e), state)),
+                };
+
+                // older servers always send the anchor, even when we say not to
+                let mut page = resp.messages;
+                if let Anchor::MessageId(before) = anchor {
+                    page.retain(|msg| msg.id < before);
+                }
+
+                if !resp.found_oldest {
+                    state.next_anchor = page.first().map(|msg| Anchor::MessageId(msg.id));
+                }
+
+                state.page = page;
+                let msg = state.page.pop()?;
+                Some((Ok(msg), state))
+            }
+        })
+    }
+}
+
+// private
+impl Client {
+    /// Grabs the given messages with the `message_ids` parameter, keyed by
+    /// their IDs. Ones we can't see are left out.
+    async fn request_messages_by_ids(
+        &self,
+        ids: &[u64],
+    ) -> Result<HashMap<u64, Message>, ZulipError> {
+        let url = self.api_url().join("messages")?;
+        let parameters = HashMap::from([
+            ("message_ids", serde_json::to_string(ids)?),
+            (
+                "apply_markdown",
+                ContentFormat::RawMarkdown.apply_markdown().to_string(),
+            ),
+            ("allow_empty_topic_name", true.to_string()),
+        ]);
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().get(url)).query(&parameters))
+            .await?;
+
+        let resp = match Self::read_json::<MessagesByIdsResponse>(resp).await? {
+            Ok(MessagesByIdsResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(MessageError::FetchMessagesFailed {
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        Ok(resp.messages.into_iter().map(|msg| (msg.id, msg)).collect())
+    }
+}
+
+/// Where [`Client::get_all_messages`] is at.
+struct Paging {
+    /// The anchor for the next page. `None` when there are no more pages.
+    next_anchor: Option<Anchor>,
+    /// The rest of the current page.
+    page: Vec<Message>,
+}
+
+/// Options for [`Client::get_all_messages`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GetAllMessages {
+    /// How many messages to grab per request.
+    ///
+    /// Zulip caps this at 5000.
+    pub page_size: u32,
+    /// Whether the messages' content should be HTML or Markdown.
+    pub format: ContentFormat,
+}
+
+impl Default for GetAllMessages {
+    /// Pages of 100 messages, as Markdown.
+    fn default() -> Self {
+        Self {
+            page_size: 100,
+            format: ContentFormat::RawMarkdown,
+        }
+    }
+}
+
+/// Which messages to grab with [`Client::get_messages`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GetMessages {
+    /// The message to start from.
+    pub anchor: Anchor,
+    /// How many messages before the anchor to grab.
+    pub num_before: u32,
+    /// How many messages after the anchor to grab.
+    pub num_after: u32,
+    /// Whether the anchor message itself should be included.
+    ///
+    /// Needs feature level 155. Older servers always include it.
+    pub include_anchor: bool,
+    /// Filters on which messages to grab. Empty means all of them.
+    pub narrow: NarrowList,
+    /// Whether the messages' content should be HTML or Markdown.
+    pub format: ContentFormat,
+}
+
+impl GetMessages {
+    /// Makes the query parameters for `GET /messages`.
+    fn make_parameters(&self, feature_level: u64) -> HashMap<&str, String> {
+        let mut parameters = HashMap::from([
+            ("num_before", self.num_before.to_string()),
+            ("num_after", self.num_after.to_string()),
+            ("include_anchor", self.include_anchor.to_string()),
+            ("apply_markdown", self.format.apply_markdown().to_string()),
+            (
+                "narrow",
+                self.narrow.to_query_param_for_feature_level(feature_level),
+            ),
+        ]);
+
+        // every server understands the old way of asking for the first unread
+        // message, but only newer ones take `first_unread` as an anchor
+        match self.anchor {
+            Anchor::FirstUnread => parameters.insert("use_first_unread_anchor", "true".into()),
+            anchor => parameters.insert("anchor", anchor.to_string()),
+        };
+
+        parameters
+    }
+}
+
+impl Default for GetMessages {
+    /// The newest 100 messages (including the anchor), as Markdown.
+    fn default() -> Self {
+        Self {
+            anchor: Anchor::Newest,
+            num_before: 100,
+            num_after: 0,
+            include_anchor: true,
+            narrow: NarrowList::new(),
+            format: ContentFormat::RawMarkdown,
+        }
+    }
+}
+
+/// The message to start from when grabbing messages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Anchor {
+    /// The most recent message.
+    Newest,
+    /// The oldest message.
+    Oldest,
+    /// The oldest unread message, or the newest message if there aren't any.
+    ///
+    /// The server says which message it picked in the response's `anchor`,
+    /// so you can scroll to it.
+    FirstUnread,
+    /// The message with this ID.
+    MessageId(u64),
+}
+
+impl std::fmt::Display for Anchor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Anchor::Newest => f.write_str("newest"),
+            Anchor::Oldest => f.write_str("oldest"),
+            Anchor::FirstUnread => f.write_str("first_unread"),
+            Anchor::MessageId(id) => write!(f, "{id}"),
+        }
+    }
+}
+
+/// A batch of messages from [`Client::get_messages`].
+///
+/// ```
+/// use libzulip::messages::get_messages::GetMessagesResponse;
+///
+/// let resp: GetMessagesResponse = serde_json::from_str(
+///     r#"{"result": "success", "msg": "", "messages": [], "anchor": 0, "found_oldest": true, "found_newest": true, "history_limited": true}"#,
+/// )
+/// .unwrap();
+/// assert!(resp.history_limited);
+///
+/// // older servers don't send it
+/// let resp: GetMessagesResponse = serde_json::from_str(
+///     r#"{"result": "success", "msg": "", "messages": [], "anchor": 0, "found_oldest": true, "found_newest": true}"#,
+/// )
+/// .unwrap();
+/// assert!(!resp.history_limited);
+/// ```
+#[derive(Debug, serde::Deserialize)]
+pub struct GetMessagesResponse {
+    #[serde(flatten)]
+    pub error: Option<ResponseError>,
+    /// The messages, sorted by ID.
+    pub messages: Vec<Message>,
+    /// The ID of the message the server used as the anchor.
+    ///
+    /// For `Anchor::MessageId`, this is the given ID. Otherwise, it's where
+    /// the server decided to start.
+    pub anchor: u64,
+    /// Whether the anchor message was found. Only sent when the anchor is a
+    /// `MessageId`.
+    pub found_anchor: Option<bool>,
+    /// Whether the oldest message matching the narrow is in `messages`.
+    pub found_oldest: bool,
+    /// Whether the newest message matching the narrow is in `messages`.
+    pub found_newest: bool,
+    /// Whether the organization's plan hides older messages, and some of
+    /// them were left out. If so, `found_oldest` is `true` even though there
+    /// were older messages.
+    ///
+    /// Only sent by servers at feature level 114 and up. `false` otherwise.
+    #[serde(default)]
+    pub history_limited: bool,
+}
+
+/// What `GET /messages` sends back when given `message_ids`. There's no
+/// anchor stuff, as there's no range.
+#[derive(Debug, serde::Deserialize)]
+struct MessagesByIdsResponse {
+    #[serde(flatten)]
+    error: Option<ResponseError>,
+    #[serde(default)]
+    messages: Vec<Message>,
+}
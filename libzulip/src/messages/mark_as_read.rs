@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use reqwest::Response;
+
+use crate::{
+    error::{MessageError, ResponseError, ZulipError},
+    Client,
+};
+
+impl Client {
+    /// Marks every message as read.
+    ///
+    /// For big accounts, the server may give up partway through, returning
+    /// `false`. Call this again until it returns `true` to finish the job.
+    #[tracing::instrument(skip(self))]
+    pub async fn mark_all_as_read(&self) -> Result<bool, ZulipError> {
+        let url = self.api_url().join("mark_all_as_read")?;
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().post(url)))
+            .await?;
+
+        let resp = Self::check_mark_as_read(resp, "all messages".into()).await?;
+
+        if resp.complete {
+            tracing::trace!("marked all messages as read!");
+        } else {
+            tracing::debug!("only some messages were marked as read. call again to finish!");
+        }
+
+        Ok(resp.complete)
+    }
+
+    /// Marks every message in the given channel as read.
+    #[tracing::instrument(skip(self))]
+    pub async fn mark_stream_as_read(&self, stream_id: u64) -> Result<(), ZulipError> {
+        let url = self.api_url().join("mark_stream_as_read")?;
+        let parameters = HashMap::from([("stream_id", stream_id.to_string())]);
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().post(url)).form(&parameters))
+            .await?;
+
+        Self::check_mark_as_read(resp, format!("channel `{stream_id}`")).await?;
+
+        tracing::trace!("marked channel as read!");
+        Ok(())
+    }
+
+    /// Marks every message in the given topic as read.
+    #[tracing::instrument(skip(self))]
+    pub async fn mark_topic_as_read<S: AsRef<str> + std::fmt::Debug + Send>(
+        &self,
+        stream_id: u64,
+        topic: S,
+    ) -> Result<(), ZulipError> {
+        let url = self.api_url().join("mark_topic_as_read")?;
+        let topic = topic.as_ref();
+        let parameters = HashMap::from([
+            ("stream_id", stream_id.to_string()),
+            ("topic_name", topic.to_string()),
+        ]);
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().post(url)).form(&parameters))
+            .await?;
+
+        Self::check_mark_as_read(resp, format!("topic `{topic}` in channel `{stream_id}`")).await?;
+
+        tracing::trace!("marked topic as read!");
+        Ok(())
+    }
+}
+
+// private
+impl Client {
+    /// Turns any error in a `mark_*_as_read` response into a
+    /// `MessageError::MarkAsReadFailed`.
+    async fn check_mark_as_read(
+        resp: Response,
+        target: String,
+    ) -> Result<MarkAsReadResponse, ZulipError> {
+        match Self::read_json::<MarkAsReadResponse>(resp).await? {
+            Ok(MarkAsReadResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                Err(MessageError::MarkAsReadFailed {
+                    target,
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into())
+            }
+            Ok(resp) => Ok(resp),
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct MarkAsReadResponse {
+    #[serde(flatten)]
+    error: Option<ResponseError>,
+    /// Whether everything was marked as read. Only `mark_all_as_read` sends
+    /// this, and only on newer servers, so assume it worked otherwise.
+    #[serde(default = "MarkAsReadResponse::default_complete")]
+    complete: bool,
+}
+
+impl MarkAsReadResponse {
+    fn default_complete() -> bool {
+        true
+    }
+}
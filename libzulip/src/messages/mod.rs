@@ -5,10 +5,16 @@
 pub mod delete_message;
 pub mod edit_message;
 pub mod fetch_single_message;
+pub mod get_messages;
+pub mod mark_as_read;
+pub mod move_topic;
 pub mod render_message;
+pub mod resolve_topic;
 pub mod send_message;
+pub mod update_message_flags;
 
 // media modules
+pub mod attachments;
 pub mod download_file;
 pub mod emoji_reaction; // contains both add and remove calls
 pub mod upload_file; // note: this isn't an api call. it's here for sanity
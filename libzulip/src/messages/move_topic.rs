@@ -0,0 +1,147 @@
+use futures::TryStreamExt as _;
+
+use crate::{
+    error::{MessageError, ZulipError},
+    narrow::{NameOrId, Narrow, NarrowKind, NarrowNegation},
+    Client,
+};
+
+use super::{
+    edit_message::{DetachedUpload, EditedMessage, EditedMessageResponse, PropagateMode},
+    fetch_single_message::ContentFormat,
+    get_messages::{Anchor, GetAllMessages, GetMessages},
+};
+
+impl Client {
+    /// Moves a whole topic to another channel, optionally renaming it.
+    ///
+    /// This edits the topic's first message with `PropagateMode::ChangeAll`,
+    /// so every message in the topic comes along. To only rename the topic,
+    /// pass the same channel for `from_stream` and `to_stream`.
+    ///
+    /// When `notify` is set, Zulip posts a note in both the old and new
+    /// topics saying where the messages went.
+    #[tracing::instrument(skip(self))]
+    pub async fn move_topic<S: AsRef<str> + std::fmt::Debug + Send>(
+        &self,
+        from_stream: u64,
+        topic: S,
+        to_stream: u64,
+        new_topic: Option<String>,
+        notify: bool,
+    ) -> Result<EditedMessageResponse, ZulipError> {
+        let message_id = self
+            .first_message_in_topic(from_stream, topic.as_ref())
+            .await?;
+
+        self.edit_message(EditedMessage {
+            message_id,
+            topic: new_topic,
+            send_notification_to_old_thread: Some(notify),
+            send_notification_to_new_thread: Some(notify),
+            content: None,
+            // zulip complains if we "move" it to where it already is
+            stream_id: (from_stream != to_stream).then_some(to_stream),
+            propagate_mode: Some(PropagateMode::ChangeAll),
+        })
+        .await
+    }
+
+    /// Moves a message (and maybe the ones around it) to another channel
+    /// and/or topic, without touching its content.
+    ///
+    /// `propagate_mode` picks which messages in the topic come along: just
+    /// this one, this one and the ones after it, or all of them. Leave
+    /// `to_stream` or `to_topic` as `None` to keep the current one.
+    ///
+    /// To report how many messages moved, the other modes count the topic's
+    /// messages first. That's an extra request (or a few, for big topics).
+    #[tracing::instrument(skip(self))]
+    pub async fn move_message(
+        &self,
+        msg_id: u64,
+        to_stream: Option<u64>,
+        to_topic: Option<String>,
+        propagate_mode: PropagateMode,
+    ) -> Result<MovedMessages, ZulipError> {
+        let moved = match propagate_mode {
+            PropagateMode::ChangeOne => 1,
+            PropagateMode::ChangeLater | PropagateMode::ChangeAll => {
+                let conversation = self
+                    .fetch_single_message(msg_id, ContentFormat::RawMarkdown, true)
+                    .await?
+                    .message
+                    .conversation();
+
+                // newest first, so we can stop once we're before the message
+                let later_only = propagate_mode == PropagateMode::ChangeLater;
+                self.get_all_messages(conversation, GetAllMessages::default())
+                    .try_take_while(|msg| futures::future::ok(!later_only || msg.id >= msg_id))
+                    .try_fold(0, |count, _| futures::future::ok(count + 1))
+                    .await?
+            }
+        };
+
+        // leaving `content` out is what keeps the message's body as-is
+        let resp = self
+            .edit_message(EditedMessage {
+                message_id: msg_id,
+                topic: to_topic,
+                send_notification_to_old_thread: None,
+                send_notification_to_new_thread: None,
+                content: None,
+                stream_id: to_stream,
+                propagate_mode: Some(propagate_mode),
+            })
+            .await?;
+
+        tracing::trace!("moved {moved} messages!");
+        Ok(MovedMessages {
+            moved,
+            detached_uploads: resp.detached_uploads,
+        })
+    }
+}
+
+/// What happened after [`Client::move_message`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MovedMessages {
+    /// How many messages were moved.
+    pub moved: u64,
+    /// Files that no messages use anymore. Moving doesn't change content,
+    /// so this is usually empty.
+    pub detached_uploads: Vec<DetachedUpload>,
+}
+
+// private
+impl Client {
+    /// Grabs the ID of the oldest message in the given topic.
+    async fn first_message_in_topic(&self, stream_id: u64, topic: &str) -> Result<u64, ZulipError> {
+        let resp = self
+            .get_messages(GetMessages {
+                anchor: Anchor::Oldest,
+                num_before: 0,
+                num_after: 1,
+                narrow: vec![
+                    Narrow::new(
+                        NarrowKind::Channel(NameOrId::Id(stream_id)),
+                        NarrowNegation::Normal,
+                    ),
+                    Narrow::new(NarrowKind::Topic(topic.into()), NarrowNegation::Normal),
+                ],
+                ..GetMessages::default()
+            })
+            .await?;
+
+        let Some(first) = resp.messages.first() else {
+            return Err(MessageError::TopicNotFound {
+                stream_id,
+                topic: topic.into(),
+            }
+            .into());
+        };
+
+        tracing::trace!("found the topic's first message!");
+        Ok(first.id)
+    }
+}
@@ -26,25 +26,25 @@ impl Client {
 
         // render it
         let resp = self
-            .auth(self.reqwest_client().post(url))
-            .form(&parameters)
-            .send()
-            .await?
-            .error_for_status()?;
+            .send_request(self.auth(self.reqwest_client().post(url)).form(&parameters))
+            .await?;
 
         // parse it
-        let parsed_resp = serde_json::from_str::<RenderResponse>(&resp.text().await?)?;
+        let parsed_resp = Self::read_json::<RenderResponse>(resp).await?;
 
         // twist it
-        if let Some(error) = parsed_resp.error {
-            return Err(MessageError::RenderMessageFailed {
+        match parsed_resp {
+            Ok(RenderResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => Err(MessageError::RenderMessageFailed {
                 content: String::from(content),
+                code: error.code(),
                 error: error.to_string(),
             }
-            .into());
+            .into()),
+            Ok(parsed_resp) => Ok(parsed_resp.rendered),
         }
-
-        Ok(parsed_resp.rendered)
     }
 }
 
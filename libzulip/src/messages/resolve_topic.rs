@@ -0,0 +1,55 @@
+use crate::{error::ZulipError, Client};
+
+use super::edit_message::EditedMessageResponse;
+
+/// What Zulip puts in front of a topic's name to mark it as resolved.
+pub const RESOLVED_TOPIC_PREFIX: &str = "✔ ";
+
+impl Client {
+    /// Marks a topic as resolved, adding the `✔ ` prefix to its name.
+    ///
+    /// `topic` is the topic's current name. If it's already resolved, nothing
+    /// happens, and you'll get `None` back.
+    #[tracing::instrument(skip(self))]
+    pub async fn resolve_topic<S: AsRef<str> + std::fmt::Debug + Send>(
+        &self,
+        stream_id: u64,
+        topic: S,
+    ) -> Result<Option<EditedMessageResponse>, ZulipError> {
+        let topic = topic.as_ref();
+
+        if topic.starts_with(RESOLVED_TOPIC_PREFIX) {
+            tracing::debug!("the topic is already resolved. skipping...");
+            return Ok(None);
+        }
+
+        let resolved = format!("{RESOLVED_TOPIC_PREFIX}{topic}");
+        self.move_topic(stream_id, topic, stream_id, Some(resolved), true)
+            .await
+            .map(Some)
+    }
+
+    /// Marks a topic as unresolved, removing the `✔ ` prefix from its name.
+    ///
+    /// `topic` is the topic's current name. If it isn't resolved, nothing
+    /// happens, and you'll get `None` back.
+    #[tracing::instrument(skip(self))]
+    pub async fn unresolve_topic<S: AsRef<str> + std::fmt::Debug + Send>(
+        &self,
+        stream_id: u64,
+        topic: S,
+    ) -> Result<Option<EditedMessageResponse>, ZulipError> {
+        let topic = topic.as_ref();
+
+        // strip every prefix, in case someone added a few by hand
+        let unresolved = topic.trim_start_matches(RESOLVED_TOPIC_PREFIX);
+        if unresolved.len() == topic.len() {
+            tracing::debug!("the topic isn't resolved. skipping...");
+            return Ok(None);
+        }
+
+        self.move_topic(stream_id, topic, stream_id, Some(unresolved.into()), true)
+            .await
+            .map(Some)
+    }
+}
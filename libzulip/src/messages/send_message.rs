@@ -1,41 +1,126 @@
 use std::collections::HashMap;
 
+use reqwest::Response;
+
 use crate::{
+    config::Credentials,
     error::{MessageError, ResponseError, ZulipError},
     Client,
 };
 
+use super::upload_file::UploadFileResponse;
+
 impl Client {
+    /// Sends a message.
+    ///
+    /// Messages that are empty (or only whitespace) or longer than the
+    /// server's [`max_message_length`](crate::organizations::ServerSettings::max_message_length)
+    /// are turned away before we send anything.
     #[tracing::instrument(skip(self))]
     pub async fn send_message(&self, msg: &Message) -> Result<MessageResponse, ZulipError> {
+        self.check_content(msg).await?;
+
         let url = self.api_url().join("messages").unwrap();
 
         // make the parameters
-        let parameters = msg.make_parameters();
+        let mut parameters = msg.make_parameters();
+
+        // older servers just ignore this, so there's no need to check first
+        if self.conf.messages.read_by_sender {
+            parameters.insert("read_by_sender", "true".into());
+        }
 
         // post the request and grab its response
         let resp = self
-            .auth(self.reqwest_client().post(url))
-            .form(&parameters)
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<MessageResponse>()
+            .send_request(self.auth(self.reqwest_client().post(url)).form(&parameters))
             .await?;
 
-        if let Some(error) = resp.error {
-            return Err(MessageError::SendFailed {
-                content: msg.content(),
-                error: error.to_string(),
-            }
-            .into());
-        }
+        let resp = Self::check_sent(resp, msg).await?;
 
         tracing::trace!("sent msg successfully!");
 
         // try to parse the reply out
         Ok(resp)
     }
+
+    /// Sends a channel message as a bot, using the bot's credentials instead
+    /// of this client's.
+    ///
+    /// This is handy for automation that posts as several bots (like
+    /// incoming webhook bots), since you won't need a `Client` for each one.
+    #[tracing::instrument(skip(self))]
+    pub async fn send_incoming_webhook<S: AsRef<str> + std::fmt::Debug + Send>(
+        &self,
+        bot: &Credentials,
+        content: S,
+        stream: ChannelMessageTarget,
+        topic: S,
+    ) -> Result<MessageResponse, ZulipError> {
+        let url = self.api_url().join("messages")?;
+
+        let msg = Message::Channel {
+            to: stream,
+            content: content.as_ref().into(),
+            topic: topic.as_ref().into(),
+            queue_id: "".into(),
+            local_id: "".into(),
+        };
+        self.check_content(&msg).await?;
+        let parameters = msg.make_parameters();
+
+        let resp = self
+            .send_request(
+                self.reqwest_client()
+                    .post(url)
+                    .basic_auth(&bot.email, Some(bot.api_key.get()))
+                    .form(&parameters),
+            )
+            .await?;
+
+        let resp = Self::check_sent(resp, &msg).await?;
+
+        tracing::trace!("sent msg as `{}` successfully!", bot.email);
+        Ok(resp)
+    }
+}
+
+// private
+impl Client {
+    /// Makes sure the server would take the message's content, so we don't
+    /// waste a request on it.
+    async fn check_content(&self, msg: &Message) -> Result<(), ZulipError> {
+        let content = msg.content();
+        if content.trim().is_empty() {
+            return Err(MessageError::EmptyContent.into());
+        }
+
+        let max = self.server_settings_cache.get().await?.max_message_length();
+        let length = content.chars().count() as u64;
+        if length > max {
+            return Err(MessageError::ContentTooLong { length, max }.into());
+        }
+
+        Ok(())
+    }
+
+    /// Turns any error in a send response into a `MessageError::SendFailed`.
+    async fn check_sent(resp: Response, msg: &Message) -> Result<MessageResponse, ZulipError> {
+        match Self::read_json::<MessageResponse>(resp).await? {
+            Ok(MessageResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                Err(MessageError::SendFailed {
+                    content: msg.content(),
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into())
+            }
+            Ok(resp) => Ok(resp),
+        }
+    }
 }
 
 /// The message being sent.
@@ -48,7 +133,10 @@ pub enum Message {
         queue_id: String, // TODO
         local_id: String, // TODO
     },
+    /// The same as `Channel`, but sent with the old `stream` type. Only use
+    /// this for servers that don't understand `channel` yet.
     Stream {
+        to: ChannelMessageTarget,
         content: String,
         topic: String,
         queue_id: String, // TODO
@@ -97,16 +185,36 @@ impl Message {
 
     fn to(&self) -> Option<String> {
         match *self {
-            Message::Channel { ref to, .. } => match to {
+            Message::Channel { ref to, .. } | Message::Stream { ref to, .. } => match to {
                 ChannelMessageTarget::Name(s) => Some(s.clone()),
                 ChannelMessageTarget::Id(number) => Some(number.to_string()),
             },
-            Message::Direct { ref to, .. } => match to {
-                DirectMessageTarget::Ids(vec) => serde_json::to_string(vec).ok(),
-                DirectMessageTarget::Emails(vec) => serde_json::to_string(vec).ok(),
-            },
-            Message::Stream { .. } => None,
+            Message::Direct { ref to, .. } => Some(to.to_param()),
+        }
+    }
+
+    /// Makes a copy of this message with a link to an uploaded file at the
+    /// end.
+    pub(crate) fn with_attachment(&self, upload: &UploadFileResponse) -> Message {
+        let link = format!("[{}]({})", upload.filename, upload.url);
+        let mut msg = self.clone();
+        match msg {
+            Message::Direct {
+                ref mut content, ..
+            }
+            | Message::Stream {
+                ref mut content, ..
+            }
+            | Message::Channel {
+                ref mut content, ..
+            } => {
+                if !content.is_empty() {
+                    content.push('\n');
+                }
+                content.push_str(&link);
+            }
         }
+        msg
     }
 
     fn content(&self) -> String {
@@ -157,6 +265,49 @@ pub enum DirectMessageTarget {
     Emails(Vec<String>),
 }
 
+impl DirectMessageTarget {
+    /// Makes the `to` parameter, which Zulip wants as a JSON array of user
+    /// IDs or emails. A DM to one person is still an array.
+    ///
+    /// ```
+    /// use libzulip::messages::send_message::DirectMessageTarget;
+    ///
+    /// assert_eq!(DirectMessageTarget::Ids(vec![9]).to_param(), "[9]");
+    /// assert_eq!(DirectMessageTarget::Ids(vec![9, 10]).to_param(), "[9,10]");
+    /// assert_eq!(
+    ///     DirectMessageTarget::Emails(vec!["iago@zulip.com".into(), "hamlet@zulip.com".into()])
+    ///         .to_param(),
+    ///     r#"["iago@zulip.com","hamlet@zulip.com"]"#
+    /// );
+    /// ```
+    pub fn to_param(&self) -> String {
+        // numbers and strings always serialize, so this can't fail
+        match *self {
+            DirectMessageTarget::Ids(ref ids) => {
+                serde_json::to_string(ids).expect("a list of ids is valid json")
+            }
+            DirectMessageTarget::Emails(ref emails) => {
+                serde_json::to_string(emails).expect("a list of emails is valid json")
+            }
+        }
+    }
+}
+
+/// What the server says after sending a message.
+///
+/// ```
+/// use libzulip::messages::send_message::{AutoFollowPolicy, MessageResponse};
+///
+/// let resp: MessageResponse = serde_json::from_str(
+///     r#"{"result": "success", "msg": "", "id": 42, "automatic_new_visibility_policy": 3}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(resp.auto_follow_policy(), Some(AutoFollowPolicy::Followed));
+///
+/// let resp: MessageResponse =
+///     serde_json::from_str(r#"{"result": "success", "msg": "", "id": 43}"#).unwrap();
+/// assert_eq!(resp.auto_follow_policy(), None);
+/// ```
 #[derive(Clone, Debug, serde::Deserialize)]
 #[non_exhaustive]
 pub struct MessageResponse {
@@ -167,3 +318,38 @@ pub struct MessageResponse {
     pub error: Option<ResponseError>,
     pub stream: Option<String>,
 }
+
+impl MessageResponse {
+    /// How sending this message changed the sender's settings for its topic,
+    /// if it did.
+    ///
+    /// Depending on their settings, users automatically follow (or unmute)
+    /// topics they send messages to. When this is `Some`, it's worth letting
+    /// them know.
+    pub fn auto_follow_policy(&self) -> Option<AutoFollowPolicy> {
+        self.automatic_new_visibility_policy
+            .map(AutoFollowPolicy::from)
+    }
+}
+
+/// A topic's new visibility policy, after it was changed automatically.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum AutoFollowPolicy {
+    /// The topic was unmuted, in a channel the user had muted.
+    Unmuted,
+    /// The user now follows the topic.
+    Followed,
+    /// A policy we don't know about yet.
+    Other(u8),
+}
+
+impl From<u8> for AutoFollowPolicy {
+    fn from(policy: u8) -> Self {
+        match policy {
+            2 => AutoFollowPolicy::Unmuted,
+            3 => AutoFollowPolicy::Followed,
+            other => AutoFollowPolicy::Other(other),
+        }
+    }
+}
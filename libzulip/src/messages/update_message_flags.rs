@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::{MessageError, ResponseError, ZulipError},
+    narrow::{NarrowList, NarrowListExt},
+    Client,
+};
+
+use super::{fetch_single_message::ContentFormat, get_messages::Anchor};
+
+impl Client {
+    /// Adds or removes a flag on every message in a range of a narrow.
+    ///
+    /// The range works like [`Client::get_messages`]: up to `num_before`
+    /// messages before the anchor, the anchor itself, and up to `num_after`
+    /// messages after it. This saves grabbing all the message IDs first.
+    ///
+    /// The server may stop early on big ranges. If so, keep going from the
+    /// returned `last_processed_id` until `found_newest` (or `found_oldest`,
+    /// when going backward) is `true`.
+    ///
+    /// Needs feature level 155.
+    #[tracing::instrument(skip(self))]
+    pub async fn update_message_flags_for_narrow(
+        &self,
+        narrow: NarrowList,
+        op: FlagOp,
+        flag: MessageFlag,
+        anchor: Anchor,
+        num_before: u32,
+        num_after: u32,
+    ) -> Result<UpdateFlagsForNarrowResponse, ZulipError> {
+        self.require_feature_level(155).await?;
+
+        let url = self.api_url().join("messages/flags/narrow")?;
+        let feature_level = self.server_settings_cache.get().await?.zulip_feature_level;
+
+        let parameters = HashMap::from([
+            ("anchor", anchor.to_string()),
+            ("num_before", num_before.to_string()),
+            ("num_after", num_after.to_string()),
+            (
+                "narrow",
+                narrow.to_query_param_for_feature_level(feature_level),
+            ),
+            ("op", op.to_string()),
+            ("flag", flag.to_string()),
+        ]);
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().post(url)).form(&parameters))
+            .await?;
+
+        let resp = match Self::read_json::<UpdateFlagsForNarrowResponse>(resp).await? {
+            Ok(UpdateFlagsForNarrowResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(MessageError::UpdateFlagsFailed {
+                    flag: flag.to_string(),
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        tracing::trace!(
+            "updated the `{flag}` flag on {} of {} messages!",
+            resp.updated_count,
+            resp.processed_count
+        );
+        Ok(resp)
+    }
+
+    /// Marks a message, and every message after it in the same conversation,
+    /// as unread. Returns how many messages were changed.
+    ///
+    /// The conversation is the message's topic, or its direct message
+    /// conversation. Messages that were already unread aren't counted.
+    ///
+    /// Needs feature level 155.
+    #[tracing::instrument(skip(self))]
+    pub async fn mark_unread_from(&self, msg_id: u64) -> Result<u64, ZulipError> {
+        let narrow = self
+            .fetch_single_message(msg_id, ContentFormat::RawMarkdown, true)
+            .await?
+            .message
+            .conversation();
+
+        let mut anchor = Anchor::MessageId(msg_id);
+        let mut updated = 0;
+        loop {
+            let resp = self
+                .update_message_flags_for_narrow(
+                    narrow.clone(),
+                    FlagOp::Remove,
+                    MessageFlag::Read,
+                    anchor,
+                    0,
+                    Self::MARK_UNREAD_BATCH_SIZE,
+                )
+                .await?;
+            updated += resp.updated_count;
+
+            // the server stops after each batch, so keep going from the last one
+            match resp.last_processed_id {
+                Some(last) if !resp.found_newest => anchor = Anchor::MessageId(last),
+                _ => break,
+            }
+        }
+
+        tracing::trace!("marked {updated} messages as unread from `{msg_id}`!");
+        Ok(updated)
+    }
+}
+
+// private
+impl Client {
+    /// How many messages `mark_unread_from` asks the server to change at once.
+    const MARK_UNREAD_BATCH_SIZE: u32 = 1000;
+}
+
+/// Whether to add or remove a flag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FlagOp {
+    Add,
+    Remove,
+}
+
+impl std::fmt::Display for FlagOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            FlagOp::Add => f.write_str("add"),
+            FlagOp::Remove => f.write_str("remove"),
+        }
+    }
+}
+
+/// A message flag that users can change themselves.
+///
+/// The server manages the rest (like `mentioned`), so they can't be set here.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MessageFlag {
+    /// Whether the user has read the message.
+    Read,
+    /// Whether the user has starred the message.
+    Starred,
+    /// Whether the user has collapsed the message.
+    Collapsed,
+}
+
+impl std::fmt::Display for MessageFlag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            MessageFlag::Read => f.write_str("read"),
+            MessageFlag::Starred => f.write_str("starred"),
+            MessageFlag::Collapsed => f.write_str("collapsed"),
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct UpdateFlagsForNarrowResponse {
+    #[serde(flatten)]
+    pub error: Option<ResponseError>,
+    /// How many messages were in the range.
+    pub processed_count: u64,
+    /// How many of those messages actually changed. Messages that already
+    /// had (or didn't have) the flag aren't counted.
+    pub updated_count: u64,
+    /// The ID of the oldest message in the range, if there were any.
+    pub first_processed_id: Option<u64>,
+    /// The ID of the newest message in the range, if there were any.
+    pub last_processed_id: Option<u64>,
+    /// Whether the range reached the oldest message in the narrow.
+    pub found_oldest: bool,
+    /// Whether the range reached the newest message in the narrow.
+    pub found_newest: bool,
+}
@@ -1,10 +1,14 @@
 use std::path::Path;
 
+use reqwest::multipart::Form;
+
 use crate::{
-    error::{FileError, MessageError, ResponseError, ZulipError},
+    error::{FileError, MessageError, ResponseError, ZulipError, ZulipErrorCode},
     Client,
 };
 
+use super::send_message::{Message, MessageResponse};
+
 impl Client {
     /// Attempts to upload a file to Zulip.
     #[tracing::instrument(skip(self))]
@@ -13,28 +17,9 @@ impl Client {
         P: AsRef<Path> + std::fmt::Debug + Send,
     {
         let path = path.as_ref().to_path_buf();
-        let path_str = path.display().to_string();
-
-        let file_name = {
-            let p = path.clone();
-
-            p.file_name()
-                .ok_or(ZulipError::FileError(FileError::FileNameNotFound(
-                    path_str.clone(),
-                )))?
-                .to_string_lossy()
-                .to_string()
-                .clone()
-        };
 
-        tracing::trace!("checking if file exists...");
-        // make sure we have a file at all
-        if tokio::fs::try_exists(&path).await.is_err() {
-            return Err(ZulipError::FileError(FileError::FileNotFound(
-                path_str.clone(),
-            )));
-        }
-        tracing::trace!("file exists. making url...");
+        // don't bother uploading if the server will just reject it
+        self.check_upload_size(&path).await?;
 
         // make the url
         tracing::info!("creating url...");
@@ -43,38 +28,251 @@ impl Client {
 
         // upload that mf
         let resp = self
-            .auth(self.reqwest_client().post(url))
-            .multipart(
-                reqwest::multipart::Form::new()
-                    .file(file_name, path.clone())
-                    .await
-                    .map_err(move |_| FileError::AttachSerializeFailed(path_str))?,
+            .send_request(
+                self.auth(self.reqwest_client().post(url))
+                    .multipart(file_form(&path).await?),
             )
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<UploadFileResponse>()
             .await?;
 
-        if let Some(error) = resp.error {
-            error.warn_ignored();
-            return Err(MessageError::FileUploadFailed {
-                path: path.to_string_lossy().to_string(),
-                error: error.to_string(),
+        let resp = match Self::read_json::<UploadFileResponse>(resp).await? {
+            Ok(UploadFileResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(MessageError::FileUploadFailed {
+                    path: path.to_string_lossy().to_string(),
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
             }
-            .into());
-        }
+            Ok(resp) => resp,
+        };
 
         tracing::trace!("uploaded file successfully!");
         Ok(resp)
     }
+
+    /// Uploads a file, then sends `msg` with a link to it at the end.
+    ///
+    /// Use this to retry a send that failed after its upload went through.
+    /// If the upload works but the send doesn't, the file is left without a
+    /// message (an "orphan"). Set `delete_orphan` to delete it right away.
+    /// Otherwise, [`UploadAndSend::orphaned_path_id`] tells you which file to
+    /// clean up.
+    ///
+    /// Only a failed upload gives an `Err`. Check `sent` for the message.
+    #[tracing::instrument(skip(self))]
+    pub async fn reupload_and_resend<P>(
+        &self,
+        path: P,
+        msg: &Message,
+        delete_orphan: bool,
+    ) -> Result<UploadAndSend, ZulipError>
+    where
+        P: AsRef<Path> + std::fmt::Debug + Send,
+    {
+        let upload = self.upload_file(path).await?;
+
+        let sent = self.send_message(&msg.with_attachment(&upload)).await;
+        let mut orphan_deleted = false;
+        if let Err(ref e) = sent {
+            tracing::warn!("sent the file, but not the message. (error: {e})");
+
+            if delete_orphan {
+                orphan_deleted = self.delete_orphan(&upload).await;
+            }
+        }
+
+        Ok(UploadAndSend {
+            upload,
+            sent,
+            orphan_deleted,
+        })
+    }
+}
+
+// private
+impl Client {
+    /// Tries to delete an upload that no message uses, returning whether it
+    /// worked.
+    async fn delete_orphan(&self, upload: &UploadFileResponse) -> bool {
+        let attachment = match self.get_attachments().await {
+            Ok(attachments) => attachments
+                .into_iter()
+                .find(|a| a.path_id == upload.path_id()),
+            Err(e) => {
+                tracing::warn!("couldn't look for the orphaned file. (error: {e})");
+                return false;
+            }
+        };
+
+        let Some(attachment) = attachment else {
+            tracing::warn!(
+                "the orphaned file `{}` wasn't in our uploads.",
+                upload.path_id()
+            );
+            return false;
+        };
+
+        match self.delete_attachment(attachment.id).await {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!("couldn't delete the orphaned file. (error: {e})");
+                false
+            }
+        }
+    }
+
+    /// Whether the server turned down an upload because of the file itself,
+    /// like it not being an image or being too big.
+    ///
+    /// Zulip uses `BAD_IMAGE` for images it can't read, but a plain
+    /// `BAD_REQUEST` for ones that are too large, so we check the message
+    /// for those.
+    pub(crate) fn is_upload_rejected(error: &ResponseError) -> bool {
+        match error.code() {
+            ZulipErrorCode::BadImage => true,
+            ZulipErrorCode::BadRequest => {
+                let msg = error.msg().to_lowercase();
+                msg.contains("larger than") || msg.contains("image")
+            }
+            _ => false,
+        }
+    }
+
+    /// Checks the file at `path` against the server's max upload size, if the
+    /// server told us what that is.
+    pub(crate) async fn check_upload_size(&self, path: &Path) -> Result<(), ZulipError> {
+        let Some(max_mib) = self
+            .server_settings_cache
+            .get()
+            .await?
+            .max_file_upload_size_mib
+        else {
+            return Ok(());
+        };
+
+        // if we can't read the metadata, `file_form` will complain about it
+        let Ok(metadata) = tokio::fs::metadata(path).await else {
+            return Ok(());
+        };
+
+        let (max, given) = (max_mib.saturating_mul(1024 * 1024), metadata.len());
+        if given > max {
+            return Err(FileError::FileTooLarge { max, given }.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Makes a multipart form holding the file at `path`, checking that the file
+/// actually exists first.
+///
+/// Anything that uploads files should use this.
+pub(crate) async fn file_form(path: &Path) -> Result<Form, ZulipError> {
+    let path_str = path.display().to_string();
+
+    let file_name = path
+        .file_name()
+        .ok_or(ZulipError::FileError(FileError::FileNameNotFound(
+            path_str.clone(),
+        )))?
+        .to_string_lossy()
+        .to_string();
+
+    tracing::trace!("checking if file exists...");
+    // make sure we have a file at all. note that `try_exists` gives `Ok(false)`
+    // for missing files - it only errors when it can't check
+    if !matches!(tokio::fs::try_exists(path).await, Ok(true)) {
+        return Err(ZulipError::FileError(FileError::FileNotFound(path_str)));
+    }
+    tracing::trace!("file exists. attaching...");
+
+    Ok(Form::new()
+        .file(file_name, path)
+        .await
+        .map_err(move |_| FileError::AttachSerializeFailed(path_str))?)
 }
 
 /// A representation of an uploaded file.
+///
+/// Newer servers also say where the file ended up and how big it is. Older
+/// ones don't, so those are `None`.
+///
+/// ```
+/// use libzulip::messages::upload_file::UploadFileResponse;
+///
+/// let rich: UploadFileResponse = serde_json::from_str(
+///     r#"{"result": "success", "msg": "", "url": "/user_uploads/2/ce/2Xpnnwgh8JWKxBXtTfD6BHKV/notes.txt", "filename": "notes.txt", "path_id": "2/ce/2Xpnnwgh8JWKxBXtTfD6BHKV/notes.txt", "size": 1024}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(rich.path_id.as_deref(), Some("2/ce/2Xpnnwgh8JWKxBXtTfD6BHKV/notes.txt"));
+/// assert_eq!(rich.size, Some(1024));
+///
+/// let plain: UploadFileResponse = serde_json::from_str(
+///     r#"{"result": "success", "msg": "", "url": "/user_uploads/2/ce/2Xpnnwgh8JWKxBXtTfD6BHKV/notes.txt", "filename": "notes.txt"}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(plain.path_id, None);
+/// assert_eq!(plain.size, None);
+/// ```
 #[derive(Debug, serde::Deserialize)]
 pub struct UploadFileResponse {
     pub url: String,
     pub filename: String,
+    /// The file's path on the server, if it said. [`UploadFileResponse::path_id`]
+    /// works it out from `url` when it didn't.
+    #[serde(default)]
+    pub path_id: Option<String>,
+    /// The file's size in bytes, if the server said.
+    #[serde(default)]
+    pub size: Option<u64>,
     #[serde(flatten)]
     pub error: Option<ResponseError>,
 }
+
+impl UploadFileResponse {
+    /// Returns the file's path on the server, which matches
+    /// [`Attachment::path_id`](super::attachments::Attachment::path_id).
+    ///
+    /// This is the server's `path_id` if it sent one, or else the end of `url`.
+    ///
+    /// ```
+    /// use libzulip::messages::upload_file::UploadFileResponse;
+    ///
+    /// let resp: UploadFileResponse = serde_json::from_str(
+    ///     r#"{"result": "success", "msg": "", "url": "/user_uploads/2/ce/2Xpnnwgh8JWKxBXtTfD6BHKV/notes.txt", "filename": "notes.txt"}"#,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(resp.path_id(), "2/ce/2Xpnnwgh8JWKxBXtTfD6BHKV/notes.txt");
+    /// ```
+    pub fn path_id(&self) -> &str {
+        match self.path_id {
+            Some(ref path_id) => path_id,
+            None => self.url.strip_prefix("/user_uploads/").unwrap_or(&self.url),
+        }
+    }
+}
+
+/// What happened in [`Client::reupload_and_resend`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct UploadAndSend {
+    /// The uploaded file.
+    pub upload: UploadFileResponse,
+    /// Whether the message was sent.
+    pub sent: Result<MessageResponse, ZulipError>,
+    /// Whether the file was deleted after the message failed to send.
+    pub orphan_deleted: bool,
+}
+
+impl UploadAndSend {
+    /// Returns the `path_id` of the uploaded file if the message didn't send
+    /// and the file is still there, so you can clean it up.
+    pub fn orphaned_path_id(&self) -> Option<&str> {
+        (self.sent.is_err() && !self.orphan_deleted).then(|| self.upload.path_id())
+    }
+}
@@ -1,16 +1,180 @@
 //! Contains an implementation of Zulip's `Narrow` type, useful for creating a
 //! set of filters on various Zulip constructs.
 
+use serde_json::Value;
+
+use crate::error::NarrowError;
+
 /// A list of [`Narrow`]s.
 ///
 /// Or, in slightly cooler words, a query that hasn't been run yet.
 pub type NarrowList = Vec<Narrow>;
 
+/// Extra methods for lists of narrows.
+pub trait NarrowListExt {
+    /// Checks for combinations of narrows that can't be right, so you get a
+    /// clear error instead of a confusing (or empty) response.
+    ///
+    /// Right now, that's:
+    ///
+    /// - a `Topic` without a `Channel` to look in, and
+    /// - more than one channel, since a message is only ever in one.
+    ///
+    /// Negated narrows are left alone, as they can be combined freely. (except
+    /// a negated [`NarrowKind::ExcludedChannels`], which asks for messages
+    /// *in* those channels)
+    ///
+    /// ```
+    /// use libzulip::narrow::{NameOrId, NarrowBuilder, NarrowListExt};
+    ///
+    /// let fine = NarrowBuilder::new()
+    ///     .channel(NameOrId::Name("general".into()))
+    ///     .topic("lunch")
+    ///     .build();
+    /// assert!(fine.validate().is_ok());
+    ///
+    /// let no_channel = NarrowBuilder::new().topic("lunch").build();
+    /// assert!(no_channel.validate().is_err());
+    ///
+    /// let two_channels = NarrowBuilder::new()
+    ///     .channel(NameOrId::Id(1))
+    ///     .channel(NameOrId::Id(2))
+    ///     .build();
+    /// assert!(two_channels.validate().is_err());
+    /// ```
+    fn validate(&self) -> Result<(), NarrowError>;
+
+    /// Makes the `narrow` parameter: a JSON array of [`NarrowTerm`]s, using
+    /// the newest operator names.
+    ///
+    /// Zulip takes this as a plain string, so it's URL-encoded like any other
+    /// query parameter.
+    ///
+    /// ```
+    /// use libzulip::narrow::{MessageSender, NameOrId, NarrowBuilder, NarrowListExt};
+    /// use reqwest::Url;
+    ///
+    /// let narrows = NarrowBuilder::new()
+    ///     .channel(NameOrId::Id(9))
+    ///     .topic_not("lunch & stuff")
+    ///     .sender(MessageSender::Other(NameOrId::Name("iago@zulip.com".into())))
+    ///     .build();
+    ///
+    /// let param = narrows.to_query_param();
+    /// assert_eq!(
+    ///     param,
+    ///     r#"[{"operator":"channel","operand":9,"negated":false},{"operator":"topic","operand":"lunch & stuff","negated":true},{"operator":"sender","operand":"iago@zulip.com","negated":false}]"#
+    /// );
+    ///
+    /// let mut url = Url::parse("https://chat.zulip.org/api/v1/messages").unwrap();
+    /// url.query_pairs_mut().append_pair("narrow", &param);
+    /// assert_eq!(
+    ///     url.query(),
+    ///     Some("narrow=%5B%7B%22operator%22%3A%22channel%22%2C%22operand%22%3A9%2C%22negated%22%3Afalse%7D%2C%7B%22operator%22%3A%22topic%22%2C%22operand%22%3A%22lunch+%26+stuff%22%2C%22negated%22%3Atrue%7D%2C%7B%22operator%22%3A%22sender%22%2C%22operand%22%3A%22iago%40zulip.com%22%2C%22negated%22%3Afalse%7D%5D")
+    /// );
+    /// ```
+    fn to_query_param(&self) -> String;
+
+    /// Like [`NarrowListExt::to_query_param`], but uses the operator names
+    /// that a server at the given feature level understands. See
+    /// [`to_terms_for_feature_level`].
+    fn to_query_param_for_feature_level(&self, feature_level: u64) -> String;
+
+    /// Swaps every `MessageSender::Me` for the given user ID, leaving the
+    /// rest alone.
+    ///
+    /// Zulip understands `me` as a `sender` operand, so you don't need this
+    /// to send a narrow. It's handy when you want to match the narrow against
+    /// messages yourself, or compare it with one that uses your ID. Grab your
+    /// ID with `Client::get_own_user`.
+    ///
+    /// ```
+    /// use libzulip::narrow::{MessageSender, NameOrId, NarrowBuilder, NarrowListExt};
+    ///
+    /// let narrows = NarrowBuilder::new()
+    ///     .sender(MessageSender::Me)
+    ///     .sender_not(MessageSender::Other(NameOrId::Id(5)))
+    ///     .build();
+    /// assert_eq!(
+    ///     narrows.with_sender_id(8).to_query_param(),
+    ///     r#"[{"operator":"sender","operand":8,"negated":false},{"operator":"sender","operand":5,"negated":true}]"#
+    /// );
+    /// ```
+    fn with_sender_id(&self, own_user_id: u64) -> NarrowList;
+}
+
+impl NarrowListExt for [Narrow] {
+    fn validate(&self) -> Result<(), NarrowError> {
+        let mut channels = 0;
+        let mut topic = None;
+        for narrow in self {
+            let negated = narrow.negation == NarrowNegation::Negated;
+            match narrow.kind {
+                NarrowKind::Channel(_) | NarrowKind::ChannelWithTopic { .. } if !negated => {
+                    channels += 1;
+                }
+                // leaving out channels is fine, but *not* leaving them out
+                // means being in all of them
+                NarrowKind::ExcludedChannels(ref list) if negated => {
+                    channels += list.as_slice().len();
+                }
+                NarrowKind::Topic(ref name) if !negated => topic = Some(name),
+                _ => (),
+            }
+        }
+
+        if channels > 1 {
+            return Err(NarrowError::MultipleChannels { count: channels });
+        }
+        if let (Some(topic), 0) = (topic, channels) {
+            return Err(NarrowError::TopicWithoutChannel {
+                topic: topic.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn to_query_param(&self) -> String {
+        terms_to_json(&to_terms(self))
+    }
+
+    fn to_query_param_for_feature_level(&self, feature_level: u64) -> String {
+        terms_to_json(&to_terms_for_feature_level(self, feature_level))
+    }
+
+    fn with_sender_id(&self, own_user_id: u64) -> NarrowList {
+        self.iter()
+            .map(|narrow| match narrow.kind {
+                NarrowKind::Sender(MessageSender::Me) => Narrow::new(
+                    NarrowKind::Sender(MessageSender::Other(NameOrId::Id(own_user_id))),
+                    narrow.negation(),
+                ),
+                _ => narrow.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Serializes terms as JSON. Their operators are strings and their operands
+/// are already JSON, so this can't fail.
+fn terms_to_json(terms: &[NarrowTerm]) -> String {
+    serde_json::to_string(terms).expect("narrow terms are always valid json")
+}
+
 /// A Narrow is a set of filters for Zulip messages that can be based on many
 /// different factors, such as the sender, channel, topic, search keywords, etc...
 ///
 /// Narrows are used in various places in the Zulip API - most importantly, in
 /// the API for fetching messages.
+///
+/// To make a bunch of these at once, try a [`NarrowBuilder`].
+///
+/// On the wire, Zulip represents these as `{operator, operand, negated}`
+/// objects, which you can deserialize straight into a `Narrow`. To go the
+/// other way, see [`to_terms`].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize)]
+#[serde(try_from = "NarrowTerm")]
 pub struct Narrow {
     kind: NarrowKind,
     negation: NarrowNegation,
@@ -39,6 +203,436 @@ impl Narrow {
     pub fn negation(&self) -> NarrowNegation {
         self.negation.clone()
     }
+
+    /// Converts this `Narrow` into the terms Zulip understands.
+    ///
+    /// Most kinds become one term, but some need more. For example,
+    /// `ChannelWithTopic` becomes a `channel` term and a `topic` term. Note
+    /// that negating a kind like that negates each of its terms.
+    ///
+    /// Zulip is picky about operand types: IDs have to be JSON numbers, and
+    /// names have to be strings.
+    ///
+    /// ```
+    /// use libzulip::narrow::{NameOrId, Narrow, NarrowKind, NarrowNegation};
+    /// use serde_json::json;
+    ///
+    /// let by_id = Narrow::new(NarrowKind::Channel(NameOrId::Id(9)), NarrowNegation::Normal);
+    /// assert_eq!(by_id.terms()[0].operand, json!(9));
+    ///
+    /// let by_name = Narrow::new(
+    ///     NarrowKind::ChannelWithTopic {
+    ///         channel: NameOrId::Name("general".into()),
+    ///         topic: NameOrId::Name("lunch".into()),
+    ///     },
+    ///     NarrowNegation::Normal,
+    /// );
+    /// let terms = by_name.terms();
+    /// assert_eq!(terms[0].operand, json!("general"));
+    /// assert_eq!(terms[1].operand, json!("lunch"));
+    /// ```
+    pub fn terms(&self) -> Vec<NarrowTerm> {
+        let negated = self.negation == NarrowNegation::Negated;
+        let term = |operator: &str, operand: Value| NarrowTerm {
+            operator: operator.into(),
+            operand,
+            negated,
+        };
+
+        match self.kind {
+            NarrowKind::Keyword(ref keyword) => vec![term("search", keyword.as_str().into())],
+            NarrowKind::Channel(ref channel) => vec![term("channel", channel.operand())],
+            NarrowKind::Topic(ref topic) => vec![term("topic", topic.as_str().into())],
+            NarrowKind::ChannelWithTopic {
+                ref channel,
+                ref topic,
+            } => vec![
+                term("channel", channel.operand()),
+                term("topic", topic.operand()),
+            ],
+            NarrowKind::DirectMessage(ref users) => vec![term("dm", users.dm_operand())],
+            // zulip only takes one user per `dm-including` term
+            NarrowKind::DirectMessageIncluding(ref users) => users
+                .as_slice()
+                .iter()
+                .map(|user| term("dm-including", user.operand()))
+                .collect(),
+            // like `dm-including`, each channel gets its own term. these are
+            // exclusions already, so negating the narrow flips them back
+            NarrowKind::ExcludedChannels(ref channels) => channels
+                .as_slice()
+                .iter()
+                .map(|channel| NarrowTerm {
+                    operator: "channel".into(),
+                    operand: channel.operand(),
+                    negated: !negated,
+                })
+                .collect(),
+            NarrowKind::Channels(ref attribute) => {
+                vec![term("channels", attribute.as_operand().into())]
+            }
+            NarrowKind::Sender(ref sender) => vec![term("sender", sender.operand())],
+            NarrowKind::Has(ref media) => vec![term("has", media.as_operand().into())],
+            NarrowKind::Is(ref status) => vec![term("is", status.as_operand().into())],
+            NarrowKind::Id(id) => vec![term("id", id.into())],
+            NarrowKind::With(id) => vec![term("with", id.into())],
+        }
+    }
+}
+
+/// Converts a list of narrows into the terms Zulip understands.
+///
+/// Serialize the result with `serde_json` to send it in a request.
+///
+/// ```
+/// use libzulip::narrow::{self, NameOrId, Narrow, NarrowBuilder};
+///
+/// let narrows = NarrowBuilder::new()
+///     .channel(NameOrId::Id(9))
+///     .topic_not("lunch")
+///     .build();
+///
+/// let json = serde_json::to_string(&narrow::to_terms(&narrows)).unwrap();
+/// let parsed: Vec<Narrow> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(parsed, narrows);
+///
+/// // unknown operators are an error
+/// let unknown = r#"{"operator": "vibes", "operand": "good"}"#;
+/// assert!(serde_json::from_str::<Narrow>(unknown).is_err());
+/// ```
+pub fn to_terms(narrows: &[Narrow]) -> Vec<NarrowTerm> {
+    narrows.iter().flat_map(Narrow::terms).collect()
+}
+
+/// The feature level where `dm` and `dm-including` replaced `pm-with` and
+/// `group-pm-with`.
+pub const DM_OPERATORS_FEATURE_LEVEL: u64 = 177;
+
+/// The feature level where `channel` and `channels` replaced `stream` and
+/// `streams`.
+pub const CHANNEL_OPERATORS_FEATURE_LEVEL: u64 = 250;
+
+/// Like [`to_terms`], but uses the operator names that a server at the
+/// given feature level understands.
+///
+/// ```
+/// use libzulip::narrow::{
+///     self, ChannelAttribute, NameOrId, Narrow, NarrowKind, NarrowNegation, OneOrMany,
+/// };
+///
+/// let narrows = vec![
+///     Narrow::new(
+///         NarrowKind::DirectMessage(OneOrMany::One(NameOrId::Id(8))),
+///         NarrowNegation::Normal,
+///     ),
+///     Narrow::new(NarrowKind::Channel(NameOrId::Id(3)), NarrowNegation::Negated),
+///     Narrow::new(
+///         NarrowKind::Channels(ChannelAttribute::Public),
+///         NarrowNegation::Normal,
+///     ),
+/// ];
+/// let operators = |level| {
+///     narrow::to_terms_for_feature_level(&narrows, level)
+///         .into_iter()
+///         .map(|term| term.operator)
+///         .collect::<Vec<_>>()
+/// };
+///
+/// assert_eq!(operators(250), ["dm", "channel", "channels"]);
+/// assert_eq!(operators(249), ["dm", "stream", "streams"]);
+/// assert_eq!(operators(176), ["pm-with", "stream", "streams"]);
+/// ```
+pub fn to_terms_for_feature_level(narrows: &[Narrow], feature_level: u64) -> Vec<NarrowTerm> {
+    let mut terms = to_terms(narrows);
+
+    if feature_level < DM_OPERATORS_FEATURE_LEVEL {
+        for term in &mut terms {
+            match term.operator.as_str() {
+                "dm" => term.operator = "pm-with".into(),
+                "dm-including" => term.operator = "group-pm-with".into(),
+                _ => (),
+            }
+        }
+    }
+    if feature_level < CHANNEL_OPERATORS_FEATURE_LEVEL {
+        for term in &mut terms {
+            match term.operator.as_str() {
+                "channel" => term.operator = "stream".into(),
+                "channels" => term.operator = "streams".into(),
+                _ => (),
+            }
+        }
+    }
+
+    terms
+}
+
+/// One filter, in the form Zulip sends and receives them.
+///
+/// You probably want a [`Narrow`] instead - this is the raw version.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct NarrowTerm {
+    /// What to filter on, like `channel` or `sender`.
+    pub operator: String,
+    /// The value to filter with, like a channel name or a user ID.
+    pub operand: Value,
+    /// Whether to include everything that *doesn't* match.
+    #[serde(default)]
+    pub negated: bool,
+}
+
+impl TryFrom<NarrowTerm> for Narrow {
+    type Error = NarrowError;
+
+    fn try_from(term: NarrowTerm) -> Result<Self, Self::Error> {
+        let invalid = || NarrowError::InvalidOperand {
+            operator: term.operator.clone(),
+            operand: term.operand.to_string(),
+        };
+        let string = || term.operand.as_str().map(String::from).ok_or_else(invalid);
+
+        let kind = match term.operator.as_str() {
+            "search" => NarrowKind::Keyword(string()?),
+            // older servers use the legacy names, so we take those too
+            "channel" | "stream" => {
+                NarrowKind::Channel(NameOrId::from_operand(&term.operand).ok_or_else(invalid)?)
+            }
+            "topic" | "subject" => NarrowKind::Topic(string()?),
+            "dm" | "pm-with" => NarrowKind::DirectMessage(
+                OneOrMany::from_dm_operand(&term.operand).ok_or_else(invalid)?,
+            ),
+            "dm-including" | "group-pm-with" => NarrowKind::DirectMessageIncluding(OneOrMany::One(
+                NameOrId::from_operand(&term.operand).ok_or_else(invalid)?,
+            )),
+            "channels" | "streams" => NarrowKind::Channels(
+                ChannelAttribute::from_operand(&string()?).ok_or_else(invalid)?,
+            ),
+            "sender" => NarrowKind::Sender(match term.operand.as_str() {
+                Some("me") => MessageSender::Me,
+                _ => {
+                    MessageSender::Other(NameOrId::from_operand(&term.operand).ok_or_else(invalid)?)
+                }
+            }),
+            "has" => {
+                NarrowKind::Has(MessageMediaKind::from_operand(&string()?).ok_or_else(invalid)?)
+            }
+            "is" => {
+                NarrowKind::Is(MessageStatusKind::from_operand(&string()?).ok_or_else(invalid)?)
+            }
+            "id" => NarrowKind::Id(Self::id_operand(&term.operand).ok_or_else(invalid)?),
+            "with" => NarrowKind::With(Self::id_operand(&term.operand).ok_or_else(invalid)?),
+            other => return Err(NarrowError::UnknownOperator(other.to_string())),
+        };
+
+        let negation = if term.negated {
+            NarrowNegation::Negated
+        } else {
+            NarrowNegation::Normal
+        };
+
+        Ok(Narrow::new(kind, negation))
+    }
+}
+
+impl Narrow {
+    /// Message IDs should be numbers, but older servers sometimes send them
+    /// as strings.
+    fn id_operand(operand: &Value) -> Option<u64> {
+        match *operand {
+            Value::Number(ref id) => id.as_u64(),
+            Value::String(ref id) => id.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Makes a [`NarrowList`] one filter at a time.
+///
+/// Each method adds a filter, and has a `_not` twin that adds the negated
+/// version instead. For example, here's a query for unread messages in the
+/// `general` channel that you didn't send:
+///
+/// ```
+/// use libzulip::narrow::{MessageSender, MessageStatusKind, NameOrId, NarrowBuilder};
+///
+/// let narrows = NarrowBuilder::new()
+///     .channel(NameOrId::Name("general".into()))
+///     .is(MessageStatusKind::Unread)
+///     .sender_not(MessageSender::Me)
+///     .build();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct NarrowBuilder {
+    narrows: NarrowList,
+}
+
+impl NarrowBuilder {
+    /// Creates a builder with no filters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only include messages in the given channel.
+    pub fn channel(self, channel: NameOrId) -> Self {
+        self.push(NarrowKind::Channel(channel), NarrowNegation::Normal)
+    }
+
+    /// Exclude messages in the given channel.
+    pub fn channel_not(self, channel: NameOrId) -> Self {
+        self.push(NarrowKind::Channel(channel), NarrowNegation::Negated)
+    }
+
+    /// Exclude messages in any of the given channels.
+    pub fn channels_not(self, channels: OneOrMany<NameOrId>) -> Self {
+        self.push(
+            NarrowKind::ExcludedChannels(channels),
+            NarrowNegation::Normal,
+        )
+    }
+
+    /// Only include messages in topics with the given name.
+    pub fn topic<S: AsRef<str>>(self, topic: S) -> Self {
+        self.push(
+            NarrowKind::Topic(topic.as_ref().into()),
+            NarrowNegation::Normal,
+        )
+    }
+
+    /// Exclude messages in topics with the given name.
+    pub fn topic_not<S: AsRef<str>>(self, topic: S) -> Self {
+        self.push(
+            NarrowKind::Topic(topic.as_ref().into()),
+            NarrowNegation::Negated,
+        )
+    }
+
+    /// Only include messages sent by the given sender.
+    pub fn sender(self, sender: MessageSender) -> Self {
+        self.push(NarrowKind::Sender(sender), NarrowNegation::Normal)
+    }
+
+    /// Exclude messages sent by the given sender.
+    pub fn sender_not(self, sender: MessageSender) -> Self {
+        self.push(NarrowKind::Sender(sender), NarrowNegation::Negated)
+    }
+
+    /// Only include messages matching the given search keywords.
+    pub fn keyword<S: AsRef<str>>(self, keyword: S) -> Self {
+        self.push(
+            NarrowKind::Keyword(keyword.as_ref().into()),
+            NarrowNegation::Normal,
+        )
+    }
+
+    /// Exclude messages matching the given search keywords.
+    pub fn keyword_not<S: AsRef<str>>(self, keyword: S) -> Self {
+        self.push(
+            NarrowKind::Keyword(keyword.as_ref().into()),
+            NarrowNegation::Negated,
+        )
+    }
+
+    /// Only include messages that have the given kind of media.
+    pub fn has(self, media: MessageMediaKind) -> Self {
+        self.push(NarrowKind::Has(media), NarrowNegation::Normal)
+    }
+
+    /// Exclude messages that have the given kind of media.
+    pub fn has_not(self, media: MessageMediaKind) -> Self {
+        self.push(NarrowKind::Has(media), NarrowNegation::Negated)
+    }
+
+    /// Only include messages with the given status.
+    pub fn is(self, status: MessageStatusKind) -> Self {
+        self.push(NarrowKind::Is(status), NarrowNegation::Normal)
+    }
+
+    /// Exclude messages with the given status.
+    pub fn is_not(self, status: MessageStatusKind) -> Self {
+        self.push(NarrowKind::Is(status), NarrowNegation::Negated)
+    }
+
+    /// Only include messages in resolved topics.
+    ///
+    /// ```
+    /// use libzulip::narrow::{self, NarrowBuilder};
+    /// use serde_json::json;
+    ///
+    /// let resolved = NarrowBuilder::new().is_resolved().build();
+    /// assert_eq!(
+    ///     serde_json::to_value(narrow::to_terms(&resolved)).unwrap(),
+    ///     json!([{"operator": "is", "operand": "resolved", "negated": false}])
+    /// );
+    ///
+    /// let unresolved = NarrowBuilder::new().is_not_resolved().build();
+    /// assert_eq!(
+    ///     serde_json::to_value(narrow::to_terms(&unresolved)).unwrap(),
+    ///     json!([{"operator": "is", "operand": "resolved", "negated": true}])
+    /// );
+    /// ```
+    pub fn is_resolved(self) -> Self {
+        self.is(MessageStatusKind::Resolved)
+    }
+
+    /// Only include messages in topics that aren't resolved.
+    pub fn is_not_resolved(self) -> Self {
+        self.is_not(MessageStatusKind::Resolved)
+    }
+
+    /// Only include messages in topics the user follows.
+    ///
+    /// ```
+    /// use libzulip::narrow::{self, NarrowBuilder};
+    /// use serde_json::json;
+    ///
+    /// let followed = NarrowBuilder::new().is_followed().is_not_muted().build();
+    /// assert_eq!(
+    ///     serde_json::to_value(narrow::to_terms(&followed)).unwrap(),
+    ///     json!([
+    ///         {"operator": "is", "operand": "followed", "negated": false},
+    ///         {"operator": "is", "operand": "muted", "negated": true},
+    ///     ])
+    /// );
+    /// ```
+    pub fn is_followed(self) -> Self {
+        self.is(MessageStatusKind::Followed)
+    }
+
+    /// Only include messages in muted channels or topics.
+    pub fn is_muted(self) -> Self {
+        self.is(MessageStatusKind::Muted)
+    }
+
+    /// Hide messages in muted channels or topics.
+    pub fn is_not_muted(self) -> Self {
+        self.is_not(MessageStatusKind::Muted)
+    }
+
+    /// Only include the message with the given ID.
+    ///
+    /// Use this with [`Client::get_messages`](crate::Client::get_messages)
+    /// to jump to a message.
+    pub fn id(self, msg_id: u64) -> Self {
+        self.push(NarrowKind::Id(msg_id), NarrowNegation::Normal)
+    }
+
+    /// Follow the conversation holding the message with the given ID, even
+    /// if it's moved to another channel or topic.
+    ///
+    /// Needs feature level 271.
+    pub fn with(self, msg_id: u64) -> Self {
+        self.push(NarrowKind::With(msg_id), NarrowNegation::Normal)
+    }
+
+    /// Finishes building, returning the list of narrows.
+    pub fn build(self) -> NarrowList {
+        self.narrows
+    }
+
+    fn push(mut self, kind: NarrowKind, negation: NarrowNegation) -> Self {
+        self.narrows.push(Narrow::new(kind, negation));
+        self
+    }
 }
 
 /// Whether or not a `Narrow`'s kind will be negated in the query.
@@ -48,9 +642,12 @@ impl Narrow {
 ///
 /// Ex: `{NarrowKind::Keyword("hi"), Negated}` will find messages that do NOT
 /// contain "hi".
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
 pub enum NarrowNegation {
     /// The narrow is left as-is. It is unchanged. This is the default value.
+    #[default]
     Normal,
     /// Used to negate (perform the opposite of) the paired `NarrowKind`.
     Negated,
@@ -82,6 +679,11 @@ pub enum NarrowKind {
     Keyword(String),
     /// The channel a message appears in.
     Channel(NameOrId),
+    /// Search for messages in topics with the given name.
+    ///
+    /// Pair this with a `Channel` narrow - topics only mean something inside
+    /// a channel, so [`NarrowListExt::validate`] rejects one on its own.
+    Topic(String),
     /// Search within a channel, only including results from a specific topic.
     ChannelWithTopic { channel: NameOrId, topic: NameOrId },
     /// Search direct messages with a given person (or group of people).
@@ -93,6 +695,50 @@ pub enum NarrowKind {
     /// Search direct message chats that include the given person (or people),
     /// alongside any number of other people.
     DirectMessageIncluding(OneOrMany<NameOrId>),
+    /// Leaves out messages in any of the given channels, as one negated
+    /// `channel` term per channel.
+    ///
+    /// This only excludes. Zulip keeps messages matching *every* term, and
+    /// has no way to ask for messages in "any of these channels", so there's
+    /// nothing to include several channels with. Negating this narrow gives
+    /// plain `channel` terms, which only makes sense for one channel (a
+    /// message can't be in two at once!) - [`NarrowListExt::validate`]
+    /// rejects more.
+    ///
+    /// ```
+    /// use libzulip::narrow::{NameOrId, Narrow, NarrowKind, NarrowListExt, NarrowNegation, OneOrMany};
+    /// use serde_json::json;
+    ///
+    /// let channels = || {
+    ///     NarrowKind::ExcludedChannels(OneOrMany::Many(vec![
+    ///         NameOrId::Id(3),
+    ///         NameOrId::Name("random".into()),
+    ///     ]))
+    /// };
+    ///
+    /// let many = vec![Narrow::new(channels(), NarrowNegation::Normal)];
+    /// let terms = many[0].terms();
+    /// assert_eq!(terms.len(), 2);
+    /// assert_eq!(terms[1].operand, json!("random"));
+    /// assert!(terms.iter().all(|t| t.operator == "channel" && t.negated));
+    /// assert!(many.validate().is_ok());
+    ///
+    /// // negated, a single channel is just a `channel` narrow...
+    /// let one = vec![Narrow::new(
+    ///     NarrowKind::ExcludedChannels(OneOrMany::One(NameOrId::Id(3))),
+    ///     NarrowNegation::Negated,
+    /// )];
+    /// let terms = one[0].terms();
+    /// assert_eq!(terms.len(), 1);
+    /// assert_eq!((terms[0].operator.as_str(), &terms[0].operand), ("channel", &json!(3)));
+    /// assert!(!terms[0].negated);
+    /// assert!(one.validate().is_ok());
+    ///
+    /// // ...but several can never match anything
+    /// let several = vec![Narrow::new(channels(), NarrowNegation::Negated)];
+    /// assert!(several.validate().is_err());
+    /// ```
+    ExcludedChannels(OneOrMany<NameOrId>),
     /// Search in channels with the given channel attribute.
     ///
     /// ## Attributes
@@ -109,16 +755,104 @@ pub enum NarrowKind {
     Has(MessageMediaKind),
     /// Finds messages that have the given status.
     Is(MessageStatusKind),
+    /// Finds the message with the given ID.
+    ///
+    /// ```
+    /// use libzulip::narrow::{Narrow, NarrowBuilder};
+    /// use serde_json::json;
+    ///
+    /// let narrows = NarrowBuilder::new().id(42).build();
+    /// let terms = narrows[0].terms();
+    /// assert_eq!(terms[0].operator, "id");
+    /// assert_eq!(terms[0].operand, json!(42));
+    ///
+    /// let parsed: Narrow = serde_json::from_str(r#"{"operator": "id", "operand": 42}"#).unwrap();
+    /// assert_eq!(parsed, narrows[0]);
+    /// ```
+    Id(u64),
+    /// Follows the conversation holding the message with the given ID.
+    ///
+    /// Zulip's permalinks use this to keep working when a topic is moved or
+    /// renamed. Combine it with the `Channel` and `Topic` (or
+    /// `ChannelWithTopic`) the message was in - if the message moves, the
+    /// server swaps those out for where it is now.
+    ///
+    /// Needs feature level 271.
+    ///
+    /// ```
+    /// use libzulip::narrow::{NameOrId, Narrow, NarrowBuilder};
+    /// use serde_json::json;
+    ///
+    /// let narrows = NarrowBuilder::new()
+    ///     .channel(NameOrId::Name("general".into()))
+    ///     .topic("lunch")
+    ///     .with(42)
+    ///     .build();
+    /// let terms = narrows[2].terms();
+    /// assert_eq!(terms[0].operator, "with");
+    /// assert_eq!(terms[0].operand, json!(42));
+    ///
+    /// let parsed: Narrow = serde_json::from_str(r#"{"operator": "with", "operand": 42}"#).unwrap();
+    /// assert_eq!(parsed, narrows[2]);
+    /// ```
+    With(u64),
+}
+
+impl NarrowKind {
+    /// Makes a `Keyword` narrow that searches for this exact phrase.
+    ///
+    /// The phrase is wrapped in double quotes for you. Zulip has no way to
+    /// escape a quote inside a phrase, and its search skips punctuation
+    /// anyway, so any quotes in `phrase` become spaces. That way, they can't
+    /// end the phrase early.
+    ///
+    /// Use `Keyword` directly to search for each word on its own.
+    ///
+    /// ```
+    /// use libzulip::narrow::NarrowKind;
+    ///
+    /// assert_eq!(
+    ///     NarrowKind::keyword_phrase("new logo"),
+    ///     NarrowKind::Keyword(r#""new logo""#.into())
+    /// );
+    /// assert_eq!(
+    ///     NarrowKind::keyword_phrase(r#"she said "hi" to me"#),
+    ///     NarrowKind::Keyword(r#""she said  hi  to me""#.into())
+    /// );
+    /// ```
+    pub fn keyword_phrase(phrase: &str) -> Self {
+        NarrowKind::Keyword(format!("\"{}\"", phrase.replace('"', " ")))
+    }
 }
 
 /// An enumeration representing the fact that many NarrowKinds take in both
 /// object names (e.g. a named stream) or object IDs (e.g. msg_id = `65`).
+///
+/// IDs are sent to Zulip as numbers, while names are sent as strings.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
 pub enum NameOrId {
     Name(String),
     Id(u64),
 }
 
+impl NameOrId {
+    /// Names are sent as strings, while IDs are sent as numbers.
+    fn operand(&self) -> Value {
+        match *self {
+            NameOrId::Name(ref name) => Value::from(name.as_str()),
+            NameOrId::Id(id) => Value::from(id),
+        }
+    }
+
+    fn from_operand(operand: &Value) -> Option<Self> {
+        match *operand {
+            Value::String(ref name) => Some(NameOrId::Name(name.clone())),
+            Value::Number(ref id) => id.as_u64().map(NameOrId::Id),
+            _ => None,
+        }
+    }
+}
+
 /// Some NarrowKinds can take one or more parameters. This structure avoids
 /// allocating a vector each time you make one of these kinds.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
@@ -127,6 +861,66 @@ pub enum OneOrMany<T> {
     Many(Vec<T>),
 }
 
+impl<T> OneOrMany<T> {
+    /// Borrows the contained value(s) as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        match *self {
+            OneOrMany::One(ref one) => std::slice::from_ref(one),
+            OneOrMany::Many(ref many) => many,
+        }
+    }
+}
+
+impl OneOrMany<NameOrId> {
+    /// Zulip wants a list of IDs or a comma-separated string of emails here.
+    fn dm_operand(&self) -> Value {
+        let users = self.as_slice();
+
+        let ids = users
+            .iter()
+            .map(|user| match *user {
+                NameOrId::Id(id) => Some(Value::from(id)),
+                NameOrId::Name(_) => None,
+            })
+            .collect::<Option<Vec<_>>>();
+
+        ids.map_or_else(
+            || {
+                let names = users
+                    .iter()
+                    .map(|user| match *user {
+                        NameOrId::Name(ref name) => name.clone(),
+                        NameOrId::Id(id) => id.to_string(),
+                    })
+                    .collect::<Vec<_>>();
+                Value::from(names.join(","))
+            },
+            Value::from,
+        )
+    }
+
+    fn from_dm_operand(operand: &Value) -> Option<Self> {
+        let mut users = match *operand {
+            Value::Array(ref ids) => ids
+                .iter()
+                .map(NameOrId::from_operand)
+                .collect::<Option<Vec<_>>>()?,
+            Value::String(ref emails) => emails
+                .split(',')
+                .map(|email| NameOrId::Name(email.trim().to_string()))
+                .collect(),
+            Value::Number(_) => vec![NameOrId::from_operand(operand)?],
+            _ => return None,
+        };
+
+        if users.len() == 1 {
+            users.pop().map(OneOrMany::One)
+        } else {
+            Some(OneOrMany::Many(users))
+        }
+    }
+}
+
 /// An input for the `NarrowKind::Channels` variant. This seems like it may
 /// grow in the future based on how it's placed in the API, so here's an enum.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
@@ -139,17 +933,81 @@ pub enum ChannelAttribute {
     // Attractive,
 }
 
+impl ChannelAttribute {
+    fn as_operand(&self) -> &'static str {
+        match *self {
+            ChannelAttribute::Public => "public",
+        }
+    }
+
+    fn from_operand(operand: &str) -> Option<Self> {
+        match operand {
+            "public" => Some(ChannelAttribute::Public),
+            _ => None,
+        }
+    }
+}
+
 /// An input for the `NarrowKind::Sender` variant.
 ///
 /// `Other` represents another person, while `Me` represents the current user.
+/// Zulip takes `me` as the operand for that, and works out who you are from
+/// the request.
+///
+/// ```
+/// use libzulip::narrow::{self, MessageSender, Narrow, NarrowBuilder};
+/// use serde_json::json;
+///
+/// let narrows = NarrowBuilder::new().sender(MessageSender::Me).build();
+/// assert_eq!(
+///     serde_json::to_value(narrow::to_terms(&narrows)).unwrap(),
+///     json!([{"operator": "sender", "operand": "me", "negated": false}])
+/// );
+///
+/// let parsed: Narrow = serde_json::from_str(r#"{"operator": "sender", "operand": "me"}"#).unwrap();
+/// assert_eq!(parsed, narrows[0]);
+/// ```
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
 pub enum MessageSender {
     Other(NameOrId),
     Me,
 }
 
+impl MessageSender {
+    fn operand(&self) -> Value {
+        match *self {
+            MessageSender::Other(ref sender) => sender.operand(),
+            MessageSender::Me => Value::from("me"),
+        }
+    }
+}
+
 /// An input for the `NarrowKind::Has` variant, representing the various kinds
 /// of multimedia a message can contain.
+///
+/// Each one becomes a `has` term, with the kind as its operand.
+///
+/// ```
+/// use libzulip::narrow::{self, MessageMediaKind, NarrowBuilder};
+/// use serde_json::json;
+///
+/// let narrows = NarrowBuilder::new()
+///     .has(MessageMediaKind::Link)
+///     .has(MessageMediaKind::Attachment)
+///     .has(MessageMediaKind::Image)
+///     .has(MessageMediaKind::Reaction)
+///     .build();
+///
+/// assert_eq!(
+///     serde_json::to_value(narrow::to_terms(&narrows)).unwrap(),
+///     json!([
+///         {"operator": "has", "operand": "link", "negated": false},
+///         {"operator": "has", "operand": "attachment", "negated": false},
+///         {"operator": "has", "operand": "image", "negated": false},
+///         {"operator": "has", "operand": "reaction", "negated": false},
+///     ])
+/// );
+/// ```
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
 pub enum MessageMediaKind {
     /// The message contains a URL.
@@ -161,13 +1019,69 @@ pub enum MessageMediaKind {
     /// Yes, you read that right - "image" also contains "videos" - be careful
     /// with that!
     Image,
-    /// Someone reacted to the message. This feels unique from the other
-    /// variants...
+    /// Someone reacted to the message.
+    ///
+    /// This isn't really media, but Zulip filters on it with `has`, too.
     Reaction,
 }
 
+impl MessageMediaKind {
+    fn as_operand(&self) -> &'static str {
+        match *self {
+            MessageMediaKind::Link => "link",
+            MessageMediaKind::Attachment => "attachment",
+            MessageMediaKind::Image => "image",
+            MessageMediaKind::Reaction => "reaction",
+        }
+    }
+
+    fn from_operand(operand: &str) -> Option<Self> {
+        match operand {
+            "link" => Some(MessageMediaKind::Link),
+            "attachment" => Some(MessageMediaKind::Attachment),
+            "image" => Some(MessageMediaKind::Image),
+            "reaction" => Some(MessageMediaKind::Reaction),
+            _ => None,
+        }
+    }
+}
+
 /// An input for the `NarrowKind::Is` variant, representing the various
 /// statuses a message may have.
+///
+/// Each one becomes an `is` term, with the status as its operand.
+///
+/// ```
+/// use libzulip::narrow::{self, MessageStatusKind, Narrow, NarrowBuilder};
+/// use serde_json::json;
+///
+/// let narrows = NarrowBuilder::new()
+///     .is(MessageStatusKind::Alerted)
+///     .is(MessageStatusKind::Mentioned)
+///     .is(MessageStatusKind::Starred)
+///     .is(MessageStatusKind::Followed)
+///     .is(MessageStatusKind::Resolved)
+///     .is(MessageStatusKind::Unread)
+///     .is(MessageStatusKind::Muted)
+///     .build();
+///
+/// let terms = serde_json::to_value(narrow::to_terms(&narrows)).unwrap();
+/// assert_eq!(
+///     terms,
+///     json!([
+///         {"operator": "is", "operand": "alerted", "negated": false},
+///         {"operator": "is", "operand": "mentioned", "negated": false},
+///         {"operator": "is", "operand": "starred", "negated": false},
+///         {"operator": "is", "operand": "followed", "negated": false},
+///         {"operator": "is", "operand": "resolved", "negated": false},
+///         {"operator": "is", "operand": "unread", "negated": false},
+///         {"operator": "is", "operand": "muted", "negated": false},
+///     ])
+/// );
+///
+/// let parsed: Vec<Narrow> = serde_json::from_value(terms).unwrap();
+/// assert_eq!(parsed, narrows);
+/// ```
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
 pub enum MessageStatusKind {
     /// Contains an [alert word](https://zulip.com/help/dm-mention-alert-notifications#alert-words)
@@ -183,4 +1097,33 @@ pub enum MessageStatusKind {
     Resolved,
     /// The user hasn't yet read this message.
     Unread,
+    /// This message is in a muted channel or topic.
+    Muted,
+}
+
+impl MessageStatusKind {
+    fn as_operand(&self) -> &'static str {
+        match *self {
+            MessageStatusKind::Alerted => "alerted",
+            MessageStatusKind::Mentioned => "mentioned",
+            MessageStatusKind::Starred => "starred",
+            MessageStatusKind::Followed => "followed",
+            MessageStatusKind::Resolved => "resolved",
+            MessageStatusKind::Unread => "unread",
+            MessageStatusKind::Muted => "muted",
+        }
+    }
+
+    fn from_operand(operand: &str) -> Option<Self> {
+        match operand {
+            "alerted" => Some(MessageStatusKind::Alerted),
+            "mentioned" => Some(MessageStatusKind::Mentioned),
+            "starred" => Some(MessageStatusKind::Starred),
+            "followed" => Some(MessageStatusKind::Followed),
+            "resolved" => Some(MessageStatusKind::Resolved),
+            "unread" => Some(MessageStatusKind::Unread),
+            "muted" => Some(MessageStatusKind::Muted),
+            _ => None,
+        }
+    }
 }
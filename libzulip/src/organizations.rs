@@ -1,34 +1,393 @@
 //! Info and settings on a server.
 
-use reqwest::{Client as ReqwestClient, Url};
+use reqwest::{
+    header::{HeaderValue, ETAG, IF_NONE_MATCH},
+    Client as ReqwestClient, Response, StatusCode, Url,
+};
 use tokio::sync::RwLock;
 
-use std::sync::Arc;
+use std::path::Path;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 use std::{collections::HashMap, time::Duration};
 
 use std::time::Instant;
 
-use crate::{error::ZulipError, Client};
+use crate::{
+    error::{FileError, OrganizationError, ResponseError, ZulipError, ZulipErrorCode},
+    messages::upload_file::file_form,
+    Client,
+};
 
 impl Client {
-    /// Grabs the API URL for this
+    /// Grabs the organization's linkifiers.
+    ///
+    /// After the first call, we ask the server to skip sending them again if
+    /// they haven't changed (using its `ETag`), so calling this often is
+    /// cheap. Use [`Client::refresh_linkifiers`] to grab them no matter what.
     #[tracing::instrument(skip(self))]
     pub async fn linkifiers(&self) -> Result<LinkifiersResponse, ZulipError> {
-        let url = self.api_url().join("realm/linkifiers").unwrap();
+        let url = self.api_url().join("realm/linkifiers")?;
+
+        let cached = self.linkifiers_cache.read().await.clone();
+        let mut request = self.auth(self.reqwest_client().get(url));
+        if let Some(ref cached) = cached {
+            request = request.header(IF_NONE_MATCH, cached.etag.clone());
+        }
+
+        let resp = self.send_request(request).await?.error_for_status()?;
+
+        if let (StatusCode::NOT_MODIFIED, Some(cached)) = (resp.status(), cached) {
+            tracing::trace!("the linkifiers haven't changed. using cached ones.");
+            return Ok(cached.linkifiers);
+        }
+
+        self.store_linkifiers(resp).await
+    }
+
+    /// Grabs the organization's linkifiers directly from Zulip, updating the
+    /// cache used by [`Client::linkifiers`].
+    #[tracing::instrument(skip(self))]
+    pub async fn refresh_linkifiers(&self) -> Result<LinkifiersResponse, ZulipError> {
+        let url = self.api_url().join("realm/linkifiers")?;
 
         let resp = self
-            .auth(self.reqwest_client().get(url))
-            .send()
+            .send_request(self.auth(self.reqwest_client().get(url)))
             .await?
             .error_for_status()?;
 
+        self.store_linkifiers(resp).await
+    }
+
+    /// Adds a linkifier to the organization, returning it (with its new ID).
+    ///
+    /// - `pattern`: a Python-style regex, like `#(?P<id>[0-9]+)`.
+    /// - `url_template`: an RFC 6570 URL template, like
+    ///   `https://github.com/zulip/zulip/issues/{id}`.
+    #[tracing::instrument(skip(self))]
+    pub async fn create_linkifier<S>(
+        &self,
+        pattern: S,
+        url_template: S,
+    ) -> Result<Linkifier, ZulipError>
+    where
+        S: AsRef<str> + std::fmt::Debug + Send,
+    {
+        let url = self.api_url().join("realm/filters")?;
+
+        let (pattern, url_template) = (pattern.as_ref(), url_template.as_ref());
+        let parameters = HashMap::from([("pattern", pattern), ("url_template", url_template)]);
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().post(url)).form(&parameters))
+            .await?;
+
+        let resp = match Self::read_json::<CreateLinkifierResponse>(resp).await? {
+            Ok(CreateLinkifierResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(OrganizationError::CreateLinkifierFailed {
+                    pattern: pattern.to_string(),
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        self.forget_linkifiers().await;
+        tracing::trace!("created linkifier with id `{}`!", resp.id);
+        Ok(Linkifier {
+            pattern: pattern.to_string(),
+            url_template: url_template.to_string(),
+            id: resp.id,
+        })
+    }
+
+    /// Replaces the pattern and URL template of the linkifier with the given
+    /// ID.
+    ///
+    /// Requires feature level 57.
+    #[tracing::instrument(skip(self))]
+    pub async fn update_linkifier<S>(
+        &self,
+        id: u64,
+        pattern: S,
+        url_template: S,
+    ) -> Result<(), ZulipError>
+    where
+        S: AsRef<str> + std::fmt::Debug + Send,
+    {
+        self.require_feature_level(57).await?;
+        let url = self.api_url().join(&format!("realm/filters/{id}"))?;
+
+        let parameters = HashMap::from([
+            ("pattern", pattern.as_ref()),
+            ("url_template", url_template.as_ref()),
+        ]);
+
+        let resp = self
+            .send_request(
+                self.auth(self.reqwest_client().patch(url))
+                    .form(&parameters),
+            )
+            .await?;
+
+        if let Ok(LinkifierChangeResponse { error: Some(error) }) | Err(error) =
+            Self::read_json::<LinkifierChangeResponse>(resp).await?
+        {
+            error.warn_ignored();
+            return Err(OrganizationError::UpdateLinkifierFailed {
+                id,
+                code: error.code(),
+                error: error.to_string(),
+            }
+            .into());
+        }
+
+        self.forget_linkifiers().await;
+        tracing::trace!("updated linkifier `{id}`!");
+        Ok(())
+    }
+
+    /// Removes the linkifier with the given ID from the organization.
+    #[tracing::instrument(skip(self))]
+    pub async fn delete_linkifier(&self, id: u64) -> Result<(), ZulipError> {
+        let url = self.api_url().join(&format!("realm/filters/{id}"))?;
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().delete(url)))
+            .await?;
+
+        if let Ok(LinkifierChangeResponse { error: Some(error) }) | Err(error) =
+            Self::read_json::<LinkifierChangeResponse>(resp).await?
+        {
+            error.warn_ignored();
+            return Err(OrganizationError::DeleteLinkifierFailed {
+                id,
+                code: error.code(),
+                error: error.to_string(),
+            }
+            .into());
+        }
+
+        self.forget_linkifiers().await;
+        tracing::trace!("deleted linkifier `{id}`!");
+        Ok(())
+    }
+
+    /// Grabs all the custom emoji in the organization, keyed by their ID.
+    ///
+    /// Use this to resolve the `emoji_code` of reactions with the
+    /// `ReactionType::RealmEmoji` type. Note that deactivated emoji are
+    /// included, as old messages may still use them.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_realm_emoji(&self) -> Result<HashMap<String, RealmEmoji>, ZulipError> {
+        let url = self.api_url().join("realm/emoji")?;
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().get(url)))
+            .await?;
+
+        let resp = match Self::read_json::<RealmEmojiResponse>(resp).await? {
+            Ok(RealmEmojiResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(OrganizationError::GetRealmEmojiFailed {
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        tracing::trace!("grabbed {} realm emoji!", resp.emoji.len());
+        Ok(resp.emoji)
+    }
+
+    /// Grabs the names of every Unicode emoji the server accepts.
+    ///
+    /// `url` is the `server_emoji_data_url` from
+    /// [`Client::register_queue`](crate::events::register::RegisterResponse::server_emoji_data_url).
+    /// Use the result to turn the `emoji_code` of a reaction into its name,
+    /// or the other way around. Custom emoji aren't included - see
+    /// [`Client::get_realm_emoji`] for those.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_server_emoji_reaction_data(
+        &self,
+        url: &str,
+    ) -> Result<ServerEmojiData, ZulipError> {
+        // it's usually absolute, but this handles paths on the server, too
+        let url = self.conf.server_address.join(url)?;
+
+        // it's a static file, so there's no need to log in
+        let resp = self.send_request(self.reqwest_client().get(url)).await?;
+
+        let resp = match Self::read_json::<ServerEmojiData>(resp).await {
+            Ok(Ok(resp)) => resp,
+            Ok(Err(error)) => {
+                error.warn_ignored();
+                return Err(OrganizationError::GetServerEmojiDataFailed {
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            // whatever serves the file might not answer like zulip does
+            Err(ZulipError::ReqwestError(e)) if e.is_status() => {
+                return Err(OrganizationError::GetServerEmojiDataFailed {
+                    code: ZulipErrorCode::BadRequest,
+                    error: e.to_string(),
+                }
+                .into());
+            }
+            Err(e) => return Err(e),
+        };
+
+        tracing::trace!("grabbed names for {} emoji!", resp.code_to_names.len());
+        Ok(resp)
+    }
+
+    /// Uploads the image at `path` as a new custom emoji called `name`.
+    ///
+    /// Emoji names may only contain lowercase letters, numbers, dashes, and
+    /// underscores. If the server rejects the image (for example, it's too
+    /// large or isn't an image at all), you'll get a
+    /// `FileError::UploadRejected`. Other failures, like a name that's taken
+    /// or missing permissions, give an
+    /// `OrganizationError::UploadRealmEmojiFailed` with Zulip's code.
+    #[tracing::instrument(skip(self))]
+    pub async fn upload_realm_emoji<P>(&self, name: &str, path: P) -> Result<RealmEmoji, ZulipError>
+    where
+        P: AsRef<Path> + std::fmt::Debug + Send,
+    {
+        let path = path.as_ref().to_path_buf();
+        self.check_upload_size(&path).await?;
+
+        let url = self
+            .api_url()
+            .join(&format!("realm/emoji/{}", urlencoding::encode(name)))?;
+
+        let resp = self
+            .send_request(
+                self.auth(self.reqwest_client().post(url))
+                    .multipart(file_form(&path).await?),
+            )
+            .await?;
+
+        if let Ok(UploadRealmEmojiResponse { error: Some(error) }) | Err(error) =
+            Self::read_json::<UploadRealmEmojiResponse>(resp).await?
+        {
+            error.warn_ignored();
+
+            if Self::is_upload_rejected(&error) {
+                return Err(FileError::UploadRejected {
+                    path: path.display().to_string(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+
+            return Err(OrganizationError::UploadRealmEmojiFailed {
+                name: name.to_string(),
+                code: error.code(),
+                error: error.to_string(),
+            }
+            .into());
+        }
+
+        tracing::trace!("uploaded realm emoji `{name}`! grabbing its info...");
+
+        // zulip doesn't tell us anything about the new emoji, so go find it
+        self.get_realm_emoji()
+            .await?
+            .into_values()
+            .find(|emoji| emoji.name == name && !emoji.deactivated)
+            .ok_or_else(|| {
+                OrganizationError::UploadRealmEmojiFailed {
+                    name: name.to_string(),
+                    code: ZulipErrorCode::BadRequest,
+                    error: "the uploaded emoji wasn't listed by the server".into(),
+                }
+                .into()
+            })
+    }
+
+    /// Grabs the organization's custom profile fields, in display order.
+    ///
+    /// Users' values for these come with `get_user` and `get_users` when
+    /// `include_custom_profile_fields` is set.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_custom_profile_fields(&self) -> Result<Vec<ProfileField>, ZulipError> {
+        let url = self.api_url().join("realm/profile_fields")?;
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().get(url)))
+            .await?;
+
+        let resp = match Self::read_json::<ProfileFieldsResponse>(resp).await? {
+            Ok(ProfileFieldsResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(OrganizationError::GetProfileFieldsFailed {
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        let mut fields = resp.custom_fields;
+        fields.sort_by_key(|field| field.order);
+
+        tracing::trace!("grabbed {} custom profile fields!", fields.len());
+        Ok(fields)
+    }
+}
+
+// private
+impl Client {
+    /// Reads the linkifiers out of a response, caching them if the server
+    /// gave us an `ETag` to check them with later.
+    async fn store_linkifiers(&self, resp: Response) -> Result<LinkifiersResponse, ZulipError> {
+        let etag = resp.headers().get(ETAG).cloned();
+        let linkifiers = serde_json::from_str::<LinkifiersResponse>(&resp.text().await?)?;
+
+        *self.linkifiers_cache.write().await = etag.map(|etag| CachedLinkifiers {
+            etag,
+            linkifiers: linkifiers.clone(),
+        });
+
         tracing::trace!("grabbed the linkifers!");
-        Ok(serde_json::from_str::<LinkifiersResponse>(
-            &resp.text().await?,
-        )?)
+        Ok(linkifiers)
+    }
+
+    /// Drops the cached linkifiers after we change them ourselves.
+    ///
+    /// The `ETag` would catch this anyway, but there's no need to ask.
+    async fn forget_linkifiers(&self) {
+        *self.linkifiers_cache.write().await = None;
     }
 }
 
+/// The linkifiers we last grabbed, alongside the `ETag` they came with.
+#[derive(Clone, Debug)]
+pub(crate) struct CachedLinkifiers {
+    etag: HeaderValue,
+    linkifiers: LinkifiersResponse,
+}
+
 /// A cache of the server settings with a required update time.
 #[derive(Debug)]
 pub struct ServerSettingsCache {
@@ -40,9 +399,46 @@ pub struct ServerSettingsCache {
 
     /// the max amount of time we'll wait before updating the server settings
     refresh_interval: Arc<RwLock<Duration>>,
+
+    /// the list of server settings, alongside when they were grabbed.
+    ///
+    /// this is behind a lock so the cache can be read (and refreshed) from
+    /// `&self`, which lets many tasks share one `Client`.
+    state: Arc<RwLock<CachedSettings>>,
+    /// set when the cached settings can't be trusted anymore, no matter how
+    /// fresh they are. the next `get` refreshes them
+    invalidated: AtomicBool,
+
+    /// where we get the time from. this is only swapped out in tests
+    clock: Arc<dyn Clock>,
+}
+
+/// A source of the current time.
+///
+/// The settings cache uses this to tell when it's expired. You won't need
+/// it unless you're testing that - swap in a clock you can move forward
+/// yourself with [`ServerSettingsCache::new_with_clock`], and you won't have
+/// to wait around.
+pub trait Clock: std::fmt::Debug + Send + Sync {
+    /// Returns the current time.
+    fn now(&self) -> Instant;
+}
+
+/// The real time, from [`Instant::now`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// The cached settings and the time they were grabbed.
+#[derive(Debug)]
+struct CachedSettings {
     /// the time this cache was last updated
     last_updated: Instant,
-
     /// the list of server settings
     settings: ServerSettings,
 }
@@ -55,9 +451,26 @@ impl ServerSettingsCache {
         reqwest_client: ReqwestClient,
         api_url: &Url,
         refresh_interval: Option<Arc<RwLock<Duration>>>,
+    ) -> Result<Self, ZulipError> {
+        Self::new_with_clock(
+            reqwest_client,
+            api_url,
+            refresh_interval,
+            Arc::new(SystemClock),
+        )
+        .await
+    }
+
+    /// Like [`ServerSettingsCache::new`], but tells the time with the given
+    /// [`Clock`].
+    pub async fn new_with_clock(
+        reqwest_client: ReqwestClient,
+        api_url: &Url,
+        refresh_interval: Option<Arc<RwLock<Duration>>>,
+        clock: Arc<dyn Clock>,
     ) -> Result<Self, ZulipError> {
         let settings = Self::server_settings(&reqwest_client, api_url).await?;
-        let last_updated = Instant::now();
+        let last_updated = clock.now();
 
         // use the default if the user didn't provide one
         let refresh_interval = if let Some(itvl) = refresh_interval {
@@ -73,31 +486,86 @@ impl ServerSettingsCache {
             api_url: api_url.clone(),
 
             refresh_interval,
-            last_updated,
 
-            settings,
+            state: Arc::new(RwLock::new(CachedSettings {
+                last_updated,
+                settings,
+            })),
+            invalidated: AtomicBool::new(false),
+
+            clock,
         })
     }
 
     /// Grabs the server settings directly from Zulip.
-    pub async fn get_without_cache(&mut self) -> Result<ServerSettings, ZulipError> {
-        self.update().await?;
-        Ok(self.settings.clone())
+    pub async fn get_without_cache(&self) -> Result<ServerSettings, ZulipError> {
+        let mut state = self.state.write().await;
+        self.update(&mut state).await?;
+        Ok(state.settings.clone())
+    }
+
+    /// Grabs the server settings directly from Zulip, leaving the cache
+    /// alone.
+    pub(crate) async fn fetch_uncached(&self) -> Result<ServerSettings, ZulipError> {
+        Self::server_settings(&self.reqwest_client, &self.api_url).await
     }
 
     /// Grabs the server settings. This value may be cached if it has expired.
-    pub async fn get(&mut self) -> Result<ServerSettings, ZulipError> {
-        // we'll check if the cache has expired and update if needed
-        if Instant::now().duration_since(self.last_updated) > *self.refresh_interval.read().await {
-            self.update().await?;
+    pub async fn get(&self) -> Result<ServerSettings, ZulipError> {
+        let refresh_interval = *self.refresh_interval.read().await;
+
+        // most of the time, the cache is fresh, so we only need to read it
+        {
+            let state = self.state.read().await;
+            if !self.is_expired(&state, refresh_interval) {
+                return Ok(state.settings.clone());
+            }
         }
 
-        Ok(self.settings.clone())
+        // otherwise, we'll take the write lock. other tasks that also saw an
+        // expired cache will wait here, then see that we've already refreshed
+        // it - so only one request is made
+        let mut state = self.state.write().await;
+        if self.is_expired(&state, refresh_interval) {
+            self.update(&mut state).await?;
+        }
+
+        Ok(state.settings.clone())
+    }
+
+    /// Returns how long the settings are cached before they're refreshed.
+    pub async fn refresh_interval(&self) -> Duration {
+        *self.refresh_interval.read().await
+    }
+
+    /// Changes how long the settings are cached before they're refreshed.
+    ///
+    /// This takes effect on the next call to `get`. Note that a very small
+    /// interval (like `Duration::ZERO`) effectively disables caching, as every
+    /// `get` will hit the server.
+    pub async fn set_refresh_interval(&self, interval: Duration) {
+        *self.refresh_interval.write().await = interval;
+        tracing::trace!("server settings cache refresh interval is now {interval:?}.");
     }
 }
 
 // private
 impl ServerSettingsCache {
+    /// Marks the cached settings as stale, so the next
+    /// [`ServerSettingsCache::get`] grabs them again no matter how fresh they
+    /// are.
+    pub fn invalidate(&self) {
+        self.invalidated.store(true, Ordering::Release);
+    }
+
+    /// Points future refreshes at a different API URL.
+    ///
+    /// This doesn't touch the cached settings, so call
+    /// [`ServerSettingsCache::invalidate`], too.
+    pub(crate) fn set_api_url(&mut self, api_url: Url) {
+        self.api_url = api_url;
+    }
+
     /// Grabs the server settings from the Zulip server at `api_url`.
     async fn server_settings(
         reqwest_client: &ReqwestClient,
@@ -106,15 +574,33 @@ impl ServerSettingsCache {
         let url = api_url.join("server_settings").unwrap();
 
         // get em
-        let resp = reqwest_client.get(url).send().await?.error_for_status()?;
+        let resp = Client::check_rate_limit(reqwest_client.get(url).send().await?)
+            .await?
+            .error_for_status()?;
 
         tracing::trace!("grabbed the server settings!");
         Ok(serde_json::from_str::<ServerSettings>(&resp.text().await?)?)
     }
 
+    /// Whether the cached settings are older than the refresh interval, or
+    /// have been invalidated.
+    fn is_expired(&self, state: &CachedSettings, refresh_interval: Duration) -> bool {
+        self.invalidated.load(Ordering::Acquire)
+            || self
+                .clock
+                .now()
+                .saturating_duration_since(state.last_updated)
+                > refresh_interval
+    }
+
     /// Updates the cache unconditionally.
-    async fn update(&mut self) -> Result<(), ZulipError> {
-        self.settings = Self::server_settings(&self.reqwest_client, &self.api_url).await?;
+    ///
+    /// You need to be holding the write lock to call this.
+    async fn update(&self, state: &mut CachedSettings) -> Result<(), ZulipError> {
+        state.settings = Self::server_settings(&self.reqwest_client, &self.api_url).await?;
+        state.last_updated = self.clock.now();
+        self.invalidated.store(false, Ordering::Release);
+        tracing::trace!("refreshed the server settings cache.");
         Ok(())
     }
 }
@@ -126,21 +612,55 @@ pub struct ServerSettings {
     pub external_authentication_methods: Vec<ExternalAuthenticationMethod>,
     pub zulip_feature_level: u64,
     pub zulip_version: String,
+    /// only sent by servers at feature level 88 and up. empty otherwise
+    #[serde(default)]
     pub zulip_merge_base: String,
     pub push_notifications_enabled: bool,
     pub is_incompatible: bool,
     pub email_auth_enabled: bool,
     pub require_email_format_usernames: bool,
-    realm_uri: String, // TODO: this is deprecated. will it be removed?
+    /// the old name for `realm_url`. newer servers send both, but it'll be
+    /// removed eventually
+    realm_uri: Option<String>,
+    realm_url: Option<String>,
     pub realm_name: String,
     pub realm_icon: String,
     pub realm_description: String,
+    /// only sent by servers at feature level 116 and up. `false` otherwise
+    #[serde(default)]
     pub realm_web_public_access_enabled: bool,
+
+    /// the largest file, in MiB, that can be uploaded. `None` when the server
+    /// doesn't say
+    pub max_file_upload_size_mib: Option<u64>,
+    /// the URL of the Jitsi server used for video calls, if there is one
+    pub jitsi_server_url: Option<String>,
+    /// the longest message, in characters, that the server accepts. `None`
+    /// when the server doesn't say. see [`ServerSettings::max_message_length`]
+    pub max_message_length: Option<u64>,
 }
 
 impl ServerSettings {
+    /// Zulip's default limit on message length, used when the server doesn't
+    /// tell us its own.
+    pub const DEFAULT_MAX_MESSAGE_LENGTH: u64 = 10_000;
+
+    /// Returns the longest message, in characters, that the server accepts.
+    pub fn max_message_length(&self) -> u64 {
+        self.max_message_length
+            .unwrap_or(Self::DEFAULT_MAX_MESSAGE_LENGTH)
+    }
+
+    /// Returns the URL of the organization.
+    ///
+    /// This uses `realm_url` when the server sends it, and falls back to the
+    /// deprecated `realm_uri` for older servers.
     pub fn realm_url(&self) -> String {
-        self.realm_uri.clone()
+        self.realm_url
+            .as_ref()
+            .or(self.realm_uri.as_ref())
+            .cloned()
+            .unwrap_or_default()
     }
 }
 
@@ -163,9 +683,238 @@ pub struct LinkifiersResponse {
 
 pub type Linkifiers = Vec<Linkifier>;
 
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct CreateLinkifierResponse {
+    #[serde(flatten)]
+    pub error: Option<ResponseError>,
+    /// The ID of the new linkifier.
+    pub id: u64,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct LinkifierChangeResponse {
+    #[serde(flatten)]
+    pub error: Option<ResponseError>,
+}
+
 #[derive(Clone, Debug, serde::Deserialize)]
 pub struct Linkifier {
     pub pattern: String,
     pub url_template: String,
     pub id: u64,
 }
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct RealmEmojiResponse {
+    #[serde(flatten)]
+    pub error: Option<ResponseError>,
+    pub emoji: HashMap<String, RealmEmoji>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct UploadRealmEmojiResponse {
+    #[serde(flatten)]
+    pub error: Option<ResponseError>,
+}
+
+/// A custom emoji uploaded to the organization.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[non_exhaustive]
+pub struct RealmEmoji {
+    /// The ID of the emoji. This is the `emoji_code` used in reactions.
+    pub id: String,
+    /// The name of the emoji.
+    pub name: String,
+    /// The path to the emoji's image, relative to the server's URL.
+    pub source_url: String,
+    /// For animated emoji, the path to a still version of the image.
+    pub still_url: Option<String>,
+    /// Whether the emoji has been deactivated. Deactivated emoji can't be used
+    /// in new messages or reactions.
+    pub deactivated: bool,
+    /// The user ID of whoever uploaded the emoji, if known.
+    pub author_id: Option<u64>,
+}
+
+/// The names of the Unicode emoji a server accepts.
+///
+/// ```
+/// use libzulip::organizations::ServerEmojiData;
+///
+/// let data: ServerEmojiData = serde_json::from_str(
+///     r#"{"code_to_names": {"1f600": ["grinning"], "1f44d": ["+1", "thumbs_up", "like"]}}"#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(data.name("1f44d"), Some("+1"));
+/// assert_eq!(data.code("like"), Some("1f44d"));
+/// assert_eq!(data.name("1f4a9"), None);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[non_exhaustive]
+pub struct ServerEmojiData {
+    /// Each emoji's `emoji_code`, mapped to its names. The first name is the
+    /// one Zulip uses, and the rest are aliases.
+    pub code_to_names: HashMap<String, Vec<String>>,
+}
+
+impl ServerEmojiData {
+    /// Grabs the name Zulip uses for the emoji with this `emoji_code`.
+    pub fn name(&self, emoji_code: &str) -> Option<&str> {
+        self.code_to_names
+            .get(emoji_code)?
+            .first()
+            .map(String::as_str)
+    }
+
+    /// Grabs the `emoji_code` of the emoji with this name (or alias).
+    pub fn code(&self, emoji_name: &str) -> Option<&str> {
+        self.code_to_names
+            .iter()
+            .find(|(_, names)| names.iter().any(|name| name == emoji_name))
+            .map(|(code, _)| code.as_str())
+    }
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct ProfileFieldsResponse {
+    #[serde(flatten)]
+    pub error: Option<ResponseError>,
+    pub custom_fields: Vec<ProfileField>,
+}
+
+/// A custom profile field, set up by the organization's administrators.
+///
+/// ```
+/// use libzulip::organizations::{ProfileField, ProfileFieldType};
+///
+/// let fields: Vec<ProfileField> = serde_json::from_str(r#"[
+///     {"id": 1, "type": 1, "name": "Phone", "hint": "", "field_data": "", "order": 1},
+///     {"id": 2, "type": 4, "name": "Birthday", "hint": "", "field_data": "", "order": 2},
+///     {
+///         "id": 3,
+///         "type": 3,
+///         "name": "Editor",
+///         "hint": "What do you use?",
+///         "field_data": "{\"0\": {\"text\": \"Vim\", \"order\": \"1\"}, \"1\": {\"text\": \"Emacs\", \"order\": \"2\"}}",
+///         "order": 3
+///     }
+/// ]"#).unwrap();
+///
+/// assert_eq!(fields[0].typ, ProfileFieldType::ShortText);
+/// assert_eq!(fields[1].typ, ProfileFieldType::Date);
+/// assert_eq!(fields[2].typ, ProfileFieldType::Choice);
+///
+/// let choices = fields[2].choices();
+/// assert_eq!(choices[0].text, "Vim");
+/// assert_eq!(choices[1].id, "1");
+/// assert!(fields[0].choices().is_empty());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[non_exhaustive]
+pub struct ProfileField {
+    /// The ID of the field. Users' values are keyed by this.
+    pub id: u64,
+    /// What kind of value the field holds.
+    #[serde(rename = "type")]
+    pub typ: ProfileFieldType,
+    /// The name of the field, like "Phone number".
+    pub name: String,
+    /// A short explanation of the field, shown when users fill it in.
+    pub hint: String,
+    /// Extra info about the field, as a JSON string. For choice fields, it
+    /// holds the choices - see [`ProfileField::choices`].
+    pub field_data: String,
+    /// Where the field goes in the list. Lower comes first.
+    pub order: u64,
+    /// Whether the field is shown in users' profile summaries.
+    #[serde(default)]
+    pub display_in_profile_summary: bool,
+}
+
+impl ProfileField {
+    /// The options for a choice field, in order.
+    ///
+    /// This is empty for other kinds of fields.
+    pub fn choices(&self) -> Vec<ProfileFieldChoice> {
+        if self.typ != ProfileFieldType::Choice {
+            return Vec::new();
+        }
+
+        let Ok(data) =
+            serde_json::from_str::<HashMap<String, RawProfileFieldChoice>>(&self.field_data)
+        else {
+            tracing::warn!("couldn't parse the choices for profile field `{}`", self.id);
+            return Vec::new();
+        };
+
+        let mut choices = data
+            .into_iter()
+            .map(|(id, raw)| ProfileFieldChoice {
+                id,
+                text: raw.text,
+                order: raw.order.parse().unwrap_or(u64::MAX),
+            })
+            .collect::<Vec<_>>();
+        choices.sort_by_key(|choice| choice.order);
+
+        choices
+    }
+}
+
+/// The kind of value a custom profile field holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize)]
+#[serde(from = "u64")]
+pub enum ProfileFieldType {
+    /// A single line of text.
+    ShortText,
+    /// A paragraph of text.
+    LongText,
+    /// One of a list of choices.
+    Choice,
+    /// A date, like `2024-10-17`.
+    Date,
+    /// A link.
+    Link,
+    /// A list of user IDs, as a JSON string.
+    User,
+    /// An account on another service, like GitHub.
+    ExternalAccount,
+    /// The user's pronouns.
+    Pronouns,
+    /// A type we don't know about. It's kept as-is.
+    Other(u64),
+}
+
+impl From<u64> for ProfileFieldType {
+    fn from(value: u64) -> Self {
+        match value {
+            1 => ProfileFieldType::ShortText,
+            2 => ProfileFieldType::LongText,
+            3 => ProfileFieldType::Choice,
+            4 => ProfileFieldType::Date,
+            5 => ProfileFieldType::Link,
+            6 => ProfileFieldType::User,
+            7 => ProfileFieldType::ExternalAccount,
+            8 => ProfileFieldType::Pronouns,
+            other => ProfileFieldType::Other(other),
+        }
+    }
+}
+
+/// One of the options for a choice field.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ProfileFieldChoice {
+    /// The ID of the choice. Users' values hold this.
+    pub id: String,
+    /// The text shown for the choice.
+    pub text: String,
+    /// Where the choice goes in the list. Lower comes first.
+    pub order: u64,
+}
+
+#[derive(serde::Deserialize)]
+struct RawProfileFieldChoice {
+    text: String,
+    order: String,
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::{StreamError, ZulipError},
+    Client,
+};
+
+use super::get_streams::GetStreamsOptions;
+
+impl Client {
+    /// Finds the ID of the channel with the given name, using the cached
+    /// [`ChannelDirectory`].
+    ///
+    /// Names are matched without caring about case, like Zulip does. If the
+    /// name isn't in the cache, the cache is refreshed once before giving up
+    /// with a `StreamError::StreamNotFound`.
+    #[tracing::instrument(skip(self))]
+    pub async fn channel_id_for_name(&self, name: &str) -> Result<u64, ZulipError> {
+        if let Some(id) = self.channel_directory.read().await.id_for_name(name) {
+            return Ok(id);
+        }
+
+        tracing::debug!("channel `{name}` isn't in the directory. refreshing it...");
+        self.refresh_channel_directory()
+            .await?
+            .id_for_name(name)
+            .ok_or_else(|| {
+                StreamError::StreamNotFound {
+                    stream: name.to_string(),
+                }
+                .into()
+            })
+    }
+
+    /// Finds the name of the channel with the given ID, using the cached
+    /// [`ChannelDirectory`].
+    ///
+    /// Like [`Client::channel_id_for_name`], a miss refreshes the cache once.
+    #[tracing::instrument(skip(self))]
+    pub async fn channel_name_for_id(&self, stream_id: u64) -> Result<String, ZulipError> {
+        if let Some(name) = self.channel_directory.read().await.name_for_id(stream_id) {
+            return Ok(name.to_string());
+        }
+
+        tracing::debug!("channel `{stream_id}` isn't in the directory. refreshing it...");
+        self.refresh_channel_directory()
+            .await?
+            .name_for_id(stream_id)
+            .map(String::from)
+            .ok_or_else(|| {
+                StreamError::StreamNotFound {
+                    stream: stream_id.to_string(),
+                }
+                .into()
+            })
+    }
+
+    /// Grabs the channels the user can see, replacing the cached
+    /// [`ChannelDirectory`] with them.
+    ///
+    /// That's every public channel, plus the ones the user is subscribed to.
+    /// Call this after renaming a channel, as the old name stays cached
+    /// otherwise.
+    #[tracing::instrument(skip(self))]
+    pub async fn refresh_channel_directory(&self) -> Result<ChannelDirectory, ZulipError> {
+        let streams = self.get_streams(GetStreamsOptions::default()).await?;
+        let directory = ChannelDirectory::new(
+            streams
+                .into_iter()
+                .map(|stream| (stream.stream_id, stream.name)),
+        );
+
+        tracing::trace!("the channel directory has {} channels!", directory.len());
+        *self.channel_directory.write().await = directory.clone();
+        Ok(directory)
+    }
+}
+
+/// Channel names and IDs, looked up either way.
+///
+/// The `Client` keeps one of these around so it doesn't have to ask the
+/// server every time. See [`Client::channel_id_for_name`].
+///
+/// ```
+/// use libzulip::streams::channel_directory::ChannelDirectory;
+///
+/// let directory = ChannelDirectory::new([(1, "general".into()), (7, "Lunch".into())]);
+/// assert_eq!(directory.id_for_name("lunch"), Some(7));
+/// assert_eq!(directory.name_for_id(7), Some("Lunch"));
+/// assert_eq!(directory.name_for_id(2), None);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChannelDirectory {
+    /// channel IDs, keyed by their lowercase names
+    ids: HashMap<String, u64>,
+    /// channel names, as the server has them, keyed by ID
+    names: HashMap<u64, String>,
+}
+
+impl ChannelDirectory {
+    /// Makes a directory from `(id, name)` pairs.
+    pub fn new<I: IntoIterator<Item = (u64, String)>>(channels: I) -> Self {
+        let mut directory = Self::default();
+        for (id, name) in channels {
+            directory.ids.insert(name.to_lowercase(), id);
+            directory.names.insert(id, name);
+        }
+        directory
+    }
+
+    /// The ID of the channel with the given name, ignoring case.
+    pub fn id_for_name(&self, name: &str) -> Option<u64> {
+        self.ids.get(&name.to_lowercase()).copied()
+    }
+
+    /// The name of the channel with the given ID.
+    pub fn name_for_id(&self, stream_id: u64) -> Option<&str> {
+        self.names.get(&stream_id).map(String::as_str)
+    }
+
+    /// How many channels are in the directory.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Whether the directory has no channels.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::{ResponseError, StreamError, ZulipError, ZulipErrorCode},
+    Client,
+};
+
+impl Client {
+    /// Deletes every message in a topic. Only administrators can do this.
+    ///
+    /// Zulip deletes big topics in batches, and may stop partway through,
+    /// returning `false`. Call this again until it returns `true` to finish
+    /// the job.
+    ///
+    /// If you aren't allowed to delete topics, this returns a
+    /// `StreamError::DeleteTopicNotAllowed`.
+    #[tracing::instrument(skip(self))]
+    pub async fn delete_topic<S: AsRef<str> + std::fmt::Debug + Send>(
+        &self,
+        stream_id: u64,
+        topic: S,
+    ) -> Result<bool, ZulipError> {
+        let topic = topic.as_ref();
+        let url = self
+            .api_url()
+            .join(&format!("streams/{stream_id}/delete_topic"))?;
+        let parameters = HashMap::from([("topic_name", topic)]);
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().post(url)).form(&parameters))
+            .await?;
+
+        let resp = match Self::read_json::<DeleteTopicResponse>(resp).await? {
+            Ok(DeleteTopicResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+
+                if matches!(
+                    error.code(),
+                    ZulipErrorCode::Unauthorized | ZulipErrorCode::UnauthorizedPrincipal
+                ) {
+                    return Err(StreamError::DeleteTopicNotAllowed {
+                        stream_id,
+                        topic: topic.to_string(),
+                    }
+                    .into());
+                }
+
+                return Err(StreamError::DeleteTopicFailed {
+                    stream_id,
+                    topic: topic.to_string(),
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        if resp.complete {
+            tracing::trace!("deleted topic `{topic}`!");
+        } else {
+            tracing::debug!("only part of topic `{topic}` was deleted. call again to finish!");
+        }
+
+        Ok(resp.complete)
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct DeleteTopicResponse {
+    #[serde(flatten)]
+    error: Option<ResponseError>,
+    /// Whether the whole topic was deleted. Older servers don't send this,
+    /// and always finish the job.
+    #[serde(default = "DeleteTopicResponse::default_complete")]
+    complete: bool,
+}
+
+impl DeleteTopicResponse {
+    fn default_complete() -> bool {
+        true
+    }
+}
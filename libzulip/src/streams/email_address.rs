@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::{ResponseError, StreamError, ZulipError},
+    Client,
+};
+
+impl Client {
+    /// Grabs the email address that posts to a channel.
+    ///
+    /// Emails sent there show up as messages in the channel, sent by the
+    /// "Email gateway" bot. The address has a secret token in it, so anyone
+    /// who has it can post - treat it like a password.
+    ///
+    /// Zulip's API can't make a new token. If the address leaks, an
+    /// administrator can regenerate it from the channel's settings in the
+    /// web app, after which this grabs the new one.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_stream_email_address(&self, stream_id: u64) -> Result<String, ZulipError> {
+        self.request_stream_email_address(stream_id, None).await
+    }
+
+    /// Like [`Client::get_stream_email_address`], but emails to the address
+    /// are sent as the given user (or bot) instead of the email gateway bot.
+    ///
+    /// Needs feature level 335. Older servers ignore `sender_id`.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_stream_email_address_for_sender(
+        &self,
+        stream_id: u64,
+        sender_id: u64,
+    ) -> Result<String, ZulipError> {
+        self.request_stream_email_address(stream_id, Some(sender_id))
+            .await
+    }
+}
+
+// private
+impl Client {
+    /// Asks for a channel's email address, optionally for a specific sender.
+    async fn request_stream_email_address(
+        &self,
+        stream_id: u64,
+        sender_id: Option<u64>,
+    ) -> Result<String, ZulipError> {
+        let url = self
+            .api_url()
+            .join(&format!("streams/{stream_id}/email_address"))?;
+        let mut parameters = HashMap::new();
+        if let Some(sender_id) = sender_id {
+            parameters.insert("sender_id", sender_id.to_string());
+        }
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().get(url)).query(&parameters))
+            .await?;
+
+        let resp = match Self::read_json::<StreamEmailAddressResponse>(resp).await? {
+            Ok(StreamEmailAddressResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(StreamError::GetEmailAddressFailed {
+                    stream_id,
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        tracing::trace!("grabbed the email address for channel `{stream_id}`!");
+        Ok(resp.email)
+    }
+}
+
+/// What `GET /streams/{stream_id}/email_address` sends back.
+///
+/// ```
+/// use libzulip::streams::email_address::StreamEmailAddressResponse;
+///
+/// let resp: StreamEmailAddressResponse = serde_json::from_str(
+///     r#"{"result": "success", "msg": "", "email": "general.abcd1234@zulip.example.com"}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(resp.email, "general.abcd1234@zulip.example.com");
+/// ```
+#[derive(Debug, serde::Deserialize)]
+pub struct StreamEmailAddressResponse {
+    #[serde(flatten)]
+    pub error: Option<ResponseError>,
+    /// The channel's email address.
+    #[serde(default)]
+    pub email: String,
+}
@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::{ResponseError, StreamError, ZulipError, ZulipErrorCode},
+    users::get_users::UserRole,
+    Client,
+};
+
+impl Client {
+    /// Grabs all the info about a channel, given its ID.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_stream(&self, stream_id: u64) -> Result<Stream, ZulipError> {
+        let url = self.api_url().join(&format!("streams/{stream_id}"))?;
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().get(url)))
+            .await?;
+
+        let resp = match Self::read_json::<GetStreamResponse>(resp).await? {
+            Ok(GetStreamResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(StreamError::GetStreamFailed {
+                    stream: stream_id.to_string(),
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        tracing::trace!("grabbed channel `{}`!", resp.stream.name);
+        Ok(resp.stream)
+    }
+
+    /// Finds the ID of the channel with the given name.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_stream_id<S: AsRef<str> + std::fmt::Debug + Send>(
+        &self,
+        name: S,
+    ) -> Result<u64, ZulipError> {
+        let name = name.as_ref();
+        let url = self.api_url().join("get_stream_id")?;
+        let parameters = HashMap::from([("stream", name)]);
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().get(url)).query(&parameters))
+            .await?;
+
+        let resp = match Self::read_json::<GetStreamIdResponse>(resp).await? {
+            Ok(GetStreamIdResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+
+                if Self::is_invalid_channel_name(&error) {
+                    tracing::debug!("the channel `{name}` wasn't found.");
+                    return Err(StreamError::StreamNotFound {
+                        stream: name.to_string(),
+                    }
+                    .into());
+                }
+
+                return Err(StreamError::GetStreamFailed {
+                    stream: name.to_string(),
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        tracing::trace!("channel `{name}` has id `{}`!", resp.stream_id);
+        Ok(resp.stream_id)
+    }
+}
+
+// private
+impl Client {
+    /// Whether the server turned down a channel lookup because no channel
+    /// has that name.
+    ///
+    /// Zulip says "Invalid channel name" (or "Invalid stream name", on older
+    /// servers) with a plain `BAD_REQUEST` or `STREAM_DOES_NOT_EXIST`.
+    fn is_invalid_channel_name(error: &ResponseError) -> bool {
+        let msg = error.msg().to_lowercase();
+        matches!(
+            error.code(),
+            ZulipErrorCode::BadRequest | ZulipErrorCode::StreamDoesNotExist
+        ) && (msg.contains("invalid channel name") || msg.contains("invalid stream name"))
+    }
+}
+
+/// A channel, and all its settings.
+///
+/// ```
+/// use libzulip::streams::get_stream::Stream;
+///
+/// let private = r#"{
+///     "stream_id": 12,
+///     "name": "secret plans",
+///     "description": "**shh**",
+///     "rendered_description": "<p><strong>shh</strong></p>",
+///     "invite_only": true,
+///     "is_web_public": false,
+///     "history_public_to_subscribers": false,
+///     "stream_post_policy": 1,
+///     "message_retention_days": null,
+///     "date_created": 1691057093,
+///     "stream_weekly_traffic": null
+/// }"#;
+///
+/// let stream = serde_json::from_str::<Stream>(private).unwrap();
+/// assert!(stream.invite_only);
+/// assert_eq!(stream.message_retention_days, None);
+///
+/// // it's too new to have any traffic yet
+/// assert_eq!(stream.stream_weekly_traffic, None);
+///
+/// let busy = private.replace(r#""stream_weekly_traffic": null"#, r#""stream_weekly_traffic": 84"#);
+/// let stream = serde_json::from_str::<Stream>(&busy).unwrap();
+/// assert_eq!(stream.stream_weekly_traffic, Some(84));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[non_exhaustive]
+pub struct Stream {
+    /// The channel's ID.
+    pub stream_id: u64,
+    /// The channel's name.
+    pub name: String,
+    /// The channel's description, in Markdown.
+    pub description: String,
+    /// The channel's description, rendered as HTML.
+    pub rendered_description: String,
+    /// Whether the channel is private.
+    pub invite_only: bool,
+    /// Whether people without an account can see the channel.
+    pub is_web_public: bool,
+    /// Whether new subscribers can see messages sent before they joined.
+    pub history_public_to_subscribers: bool,
+    /// Who can post in the channel. See [`Stream::can_post`].
+    ///
+    /// Deprecated by Zulip in favor of permission groups.
+    pub stream_post_policy: Option<StreamPostPolicy>,
+    /// How many days messages are kept for. `None` means the organization's
+    /// default, and `-1` means forever.
+    pub message_retention_days: Option<i64>,
+    /// When the channel was made, as a UNIX timestamp. Missing on older
+    /// servers.
+    pub date_created: Option<u64>,
+    /// About how many messages are sent in the channel each week. `None` for
+    /// channels that are too new to tell.
+    pub stream_weekly_traffic: Option<u64>,
+    /// How many users are subscribed to the channel. Needs feature level
+    /// 394.
+    pub subscriber_count: Option<u64>,
+}
+
+impl Stream {
+    /// Whether a user with the given role may post in the channel, going by
+    /// its `stream_post_policy`. Handy for turning off the compose box.
+    ///
+    /// Channels without a policy (or with one we don't know) allow anyone.
+    /// The server has the final say, though: new members can't post in
+    /// `FullMembers` channels, and that isn't checked here.
+    ///
+    /// ```
+    /// use libzulip::{streams::get_stream::{Stream, StreamPostPolicy}, users::get_users::UserRole};
+    ///
+    /// let announcements = r#"{
+    ///     "stream_id": 1, "name": "announce", "description": "", "rendered_description": "",
+    ///     "invite_only": false, "is_web_public": false, "history_public_to_subscribers": true,
+    ///     "stream_post_policy": 2, "message_retention_days": null, "stream_weekly_traffic": 3
+    /// }"#;
+    /// let stream = serde_json::from_str::<Stream>(announcements).unwrap();
+    /// assert_eq!(stream.stream_post_policy, Some(StreamPostPolicy::AdminsOnly));
+    /// assert!(stream.can_post(UserRole::Owner));
+    /// assert!(!stream.can_post(UserRole::Moderator));
+    ///
+    /// let mods = announcements.replace(r#""stream_post_policy": 2"#, r#""stream_post_policy": 4"#);
+    /// let stream = serde_json::from_str::<Stream>(&mods).unwrap();
+    /// assert!(stream.can_post(UserRole::Moderator));
+    /// assert!(!stream.can_post(UserRole::Member));
+    /// ```
+    pub fn can_post(&self, role: UserRole) -> bool {
+        match self.stream_post_policy {
+            Some(StreamPostPolicy::AdminsOnly) => role >= UserRole::Admin,
+            Some(StreamPostPolicy::Moderators) => role >= UserRole::Moderator,
+            Some(StreamPostPolicy::FullMembers) => role > UserRole::Guest,
+            Some(StreamPostPolicy::Everyone | StreamPostPolicy::Other(_)) | None => true,
+        }
+    }
+}
+
+/// Who can post in a channel.
+///
+/// ```
+/// use libzulip::streams::get_stream::StreamPostPolicy;
+///
+/// let parse = |n: &str| serde_json::from_str::<StreamPostPolicy>(n).unwrap();
+/// assert_eq!(parse("1"), StreamPostPolicy::Everyone);
+/// assert_eq!(parse("2"), StreamPostPolicy::AdminsOnly);
+/// assert_eq!(parse("3"), StreamPostPolicy::FullMembers);
+/// assert_eq!(parse("4"), StreamPostPolicy::Moderators);
+/// assert_eq!(parse("9"), StreamPostPolicy::Other(9));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(from = "u64")]
+#[non_exhaustive]
+pub enum StreamPostPolicy {
+    /// Anyone can post, even guests.
+    Everyone,
+    /// Only organization owners and admins can post.
+    AdminsOnly,
+    /// Only members who aren't new can post. Guests can't.
+    FullMembers,
+    /// Only owners, admins, and moderators can post.
+    Moderators,
+    /// A policy we don't know about yet.
+    Other(u64),
+}
+
+impl From<u64> for StreamPostPolicy {
+    fn from(policy: u64) -> Self {
+        match policy {
+            1 => StreamPostPolicy::Everyone,
+            2 => StreamPostPolicy::AdminsOnly,
+            3 => StreamPostPolicy::FullMembers,
+            4 => StreamPostPolicy::Moderators,
+            other => StreamPostPolicy::Other(other),
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GetStreamResponse {
+    #[serde(flatten)]
+    error: Option<ResponseError>,
+    stream: Stream,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GetStreamIdResponse {
+    #[serde(flatten)]
+    error: Option<ResponseError>,
+    stream_id: u64,
+}
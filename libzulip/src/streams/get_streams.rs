@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::{ResponseError, StreamError, ZulipError},
+    Client,
+};
+
+use super::get_stream::Stream;
+
+impl Client {
+    /// Grabs every channel matching the given options.
+    ///
+    /// Unlike the user's subscriptions, this can list channels they haven't
+    /// joined, which is handy for browsing.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_streams(&self, options: GetStreamsOptions) -> Result<Vec<Stream>, ZulipError> {
+        let url = self.api_url().join("streams")?;
+        let parameters = options.make_parameters();
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().get(url)).query(&parameters))
+            .await?;
+
+        let resp = match Self::read_json::<GetStreamsResponse>(resp).await? {
+            Ok(GetStreamsResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(StreamError::GetStreamsFailed {
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        tracing::trace!("grabbed {} channels!", resp.streams.len());
+        Ok(resp.streams)
+    }
+}
+
+/// Which channels [`Client::get_streams`] should list.
+///
+/// By default, that's all public channels and the ones the user is
+/// subscribed to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GetStreamsOptions {
+    /// Include all public channels.
+    pub include_public: bool,
+    /// Include all channels the user is subscribed to.
+    pub include_subscribed: bool,
+    /// Include every active channel. Only administrators can use this.
+    pub include_all_active: bool,
+    /// Include the organization's default channels.
+    pub include_default: bool,
+    /// If the user is a bot, include the channels its owner is subscribed to.
+    pub include_owner_subscribed: bool,
+}
+
+impl GetStreamsOptions {
+    fn make_parameters(&self) -> HashMap<&str, String> {
+        HashMap::from([
+            ("include_public", self.include_public.to_string()),
+            ("include_subscribed", self.include_subscribed.to_string()),
+            ("include_all_active", self.include_all_active.to_string()),
+            ("include_default", self.include_default.to_string()),
+            (
+                "include_owner_subscribed",
+                self.include_owner_subscribed.to_string(),
+            ),
+        ])
+    }
+}
+
+impl Default for GetStreamsOptions {
+    fn default() -> Self {
+        Self {
+            include_public: true,
+            include_subscribed: true,
+            include_all_active: false,
+            include_default: false,
+            include_owner_subscribed: false,
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GetStreamsResponse {
+    #[serde(flatten)]
+    error: Option<ResponseError>,
+    streams: Vec<Stream>,
+}
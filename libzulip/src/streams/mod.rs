@@ -0,0 +1,9 @@
+// like `messages`, each module here represents one API call (or a small
+// family of them)
+
+pub mod channel_directory;
+pub mod delete_topic;
+pub mod email_address;
+pub mod get_stream;
+pub mod get_streams;
+pub mod subscriptions;
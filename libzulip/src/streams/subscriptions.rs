@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::{ResponseError, StreamError, ZulipError},
+    Client,
+};
+
+use super::get_stream::Stream;
+
+impl Client {
+    /// Grabs the channels the current user is subscribed to, along with
+    /// their personal settings for each.
+    ///
+    /// Set `include_subscribers` to grab who's subscribed to each channel,
+    /// too. That can be a lot of data in big organizations!
+    #[tracing::instrument(skip(self))]
+    pub async fn get_subscribed_streams(
+        &self,
+        include_subscribers: bool,
+    ) -> Result<Vec<Subscription>, ZulipError> {
+        let url = self.api_url().join("users/me/subscriptions")?;
+        let parameters = HashMap::from([("include_subscribers", include_subscribers.to_string())]);
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().get(url)).query(&parameters))
+            .await?;
+
+        let resp = match Self::read_json::<SubscriptionsResponse>(resp).await? {
+            Ok(SubscriptionsResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(StreamError::GetSubscriptionsFailed {
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        tracing::trace!("grabbed {} subscriptions!", resp.subscriptions.len());
+        Ok(resp.subscriptions)
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct SubscriptionsResponse {
+    #[serde(flatten)]
+    error: Option<ResponseError>,
+    #[serde(default)]
+    subscriptions: Vec<Subscription>,
+}
+
+/// A channel the user is subscribed to.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[non_exhaustive]
+pub struct Subscription {
+    /// The channel itself.
+    #[serde(flatten)]
+    pub stream: Stream,
+    /// The color the user picked for the channel, like `#76ce90`.
+    pub color: String,
+    /// Whether the user muted the channel.
+    #[serde(default)]
+    pub is_muted: bool,
+    /// Whether the user pinned the channel to the top of their list.
+    #[serde(default)]
+    pub pin_to_top: bool,
+    /// The IDs of everyone subscribed to the channel. Only sent when
+    /// `include_subscribers` was set.
+    pub subscribers: Option<Vec<u64>>,
+}
+
+impl Subscription {
+    /// How many users are subscribed to the channel, if we know.
+    pub fn subscriber_count(&self) -> Option<u64> {
+        self.stream
+            .subscriber_count
+            .or_else(|| self.subscribers.as_ref().map(|subs| subs.len() as u64))
+    }
+}
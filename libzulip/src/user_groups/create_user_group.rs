@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::{ResponseError, UserGroupError, ZulipError},
+    Client,
+};
+
+impl Client {
+    /// Makes a new user group with the given members, returning its ID.
+    #[tracing::instrument(skip(self))]
+    pub async fn create_user_group<S: AsRef<str> + std::fmt::Debug + Send>(
+        &self,
+        name: S,
+        description: S,
+        members: &[u64],
+    ) -> Result<u64, ZulipError> {
+        let name = name.as_ref();
+        let url = self.api_url().join("user_groups/create")?;
+
+        let parameters = HashMap::from([
+            ("name", name.to_string()),
+            ("description", description.as_ref().to_string()),
+            ("members", serde_json::to_string(members)?),
+        ]);
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().post(url)).form(&parameters))
+            .await?;
+
+        let resp = match Self::read_json::<CreateUserGroupResponse>(resp).await? {
+            Ok(CreateUserGroupResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(UserGroupError::CreateUserGroupFailed {
+                    name: name.to_string(),
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        if let Some(group_id) = resp.group_id {
+            tracing::trace!("created user group `{name}`!");
+            return Ok(group_id);
+        }
+
+        // older servers don't tell us the id, so go find it
+        tracing::trace!("created user group `{name}`! grabbing its id...");
+        self.get_user_groups()
+            .await?
+            .into_iter()
+            .find(|group| group.name == name)
+            .map(|group| group.id)
+            .ok_or_else(|| UserGroupError::UserGroupNotFound { name: name.into() }.into())
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct CreateUserGroupResponse {
+    #[serde(flatten)]
+    error: Option<ResponseError>,
+    /// Only sent by servers at feature level 130 or above.
+    group_id: Option<u64>,
+}
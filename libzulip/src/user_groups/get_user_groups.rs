@@ -0,0 +1,59 @@
+use crate::{
+    error::{ResponseError, UserGroupError, ZulipError},
+    Client,
+};
+
+impl Client {
+    /// Grabs every user group in the organization.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_user_groups(&self) -> Result<Vec<UserGroup>, ZulipError> {
+        let url = self.api_url().join("user_groups")?;
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().get(url)))
+            .await?;
+
+        let resp = match Self::read_json::<GetUserGroupsResponse>(resp).await? {
+            Ok(GetUserGroupsResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(UserGroupError::GetUserGroupsFailed {
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        tracing::trace!("grabbed {} user groups!", resp.user_groups.len());
+        Ok(resp.user_groups)
+    }
+}
+
+/// A group of users, which can be mentioned all at once or given
+/// permissions.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[non_exhaustive]
+pub struct UserGroup {
+    /// The group's ID.
+    pub id: u64,
+    /// The group's name.
+    pub name: String,
+    /// The group's description.
+    pub description: String,
+    /// The IDs of the users directly in the group.
+    pub members: Vec<u64>,
+    /// Whether Zulip made this group itself, like `role:administrators`.
+    /// These can't be changed.
+    pub is_system_group: bool,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GetUserGroupsResponse {
+    #[serde(flatten)]
+    error: Option<ResponseError>,
+    user_groups: Vec<UserGroup>,
+}
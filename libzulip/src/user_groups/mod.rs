@@ -0,0 +1,6 @@
+// like `messages`, each module here represents one API call (or a small
+// family of them)
+
+pub mod create_user_group;
+pub mod get_user_groups;
+pub mod update_user_group; // contains both the info and members calls
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::{ResponseError, UserGroupError, ZulipError},
+    Client,
+};
+
+impl Client {
+    /// Changes a user group's name and/or description. `None` leaves that
+    /// field alone.
+    #[tracing::instrument(skip(self))]
+    pub async fn update_user_group(
+        &self,
+        group_id: u64,
+        name: Option<String>,
+        description: Option<String>,
+    ) -> Result<(), ZulipError> {
+        let url = self.api_url().join(&format!("user_groups/{group_id}"))?;
+
+        let mut parameters = HashMap::new();
+        if let Some(name) = name {
+            parameters.insert("name", name);
+        }
+        if let Some(description) = description {
+            parameters.insert("description", description);
+        }
+
+        let resp = self
+            .send_request(
+                self.auth(self.reqwest_client().patch(url))
+                    .form(&parameters),
+            )
+            .await?;
+
+        if let Ok(UpdateUserGroupResponse { error: Some(error) }) | Err(error) =
+            Self::read_json::<UpdateUserGroupResponse>(resp).await?
+        {
+            error.warn_ignored();
+            return Err(UserGroupError::UpdateUserGroupFailed {
+                group_id,
+                code: error.code(),
+                error: error.to_string(),
+            }
+            .into());
+        }
+
+        tracing::trace!("updated user group `{group_id}`!");
+        Ok(())
+    }
+
+    /// Adds and removes members of a user group, given their user IDs.
+    #[tracing::instrument(skip(self))]
+    pub async fn update_user_group_members(
+        &self,
+        group_id: u64,
+        add: &[u64],
+        remove: &[u64],
+    ) -> Result<(), ZulipError> {
+        let url = self
+            .api_url()
+            .join(&format!("user_groups/{group_id}/members"))?;
+
+        let mut parameters = HashMap::new();
+        if !add.is_empty() {
+            parameters.insert("add", serde_json::to_string(add)?);
+        }
+        if !remove.is_empty() {
+            parameters.insert("delete", serde_json::to_string(remove)?);
+        }
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().post(url)).form(&parameters))
+            .await?;
+
+        if let Ok(UpdateUserGroupResponse { error: Some(error) }) | Err(error) =
+            Self::read_json::<UpdateUserGroupResponse>(resp).await?
+        {
+            error.warn_ignored();
+            return Err(UserGroupError::UpdateMembersFailed {
+                group_id,
+                code: error.code(),
+                error: error.to_string(),
+            }
+            .into());
+        }
+
+        tracing::trace!("updated the members of user group `{group_id}`!");
+        Ok(())
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct UpdateUserGroupResponse {
+    #[serde(flatten)]
+    error: Option<ResponseError>,
+}
@@ -0,0 +1,96 @@
+use std::path::Path;
+
+use reqwest::Url;
+
+use crate::{
+    error::{FileError, ResponseError, UserError, ZulipError},
+    messages::upload_file::file_form,
+    Client,
+};
+
+/// The image types Zulip accepts for avatars.
+const AVATAR_EXTENSIONS: [&str; 5] = ["png", "jpg", "jpeg", "gif", "webp"];
+
+impl Client {
+    /// Sets the current user's avatar to the image at `path`, returning the
+    /// new avatar's URL.
+    ///
+    /// Only PNG, JPEG, GIF, and WebP images are allowed. Anything else gives
+    /// a `FileError::UnsupportedImageFormat` before uploading. If the server
+    /// doesn't like the image, you'll get a `FileError::UploadRejected`.
+    /// Other failures, like bad credentials or avatar changes being turned
+    /// off, give a `UserError::UploadAvatarFailed` with Zulip's code.
+    #[tracing::instrument(skip(self))]
+    pub async fn upload_own_avatar<P>(&self, path: P) -> Result<Url, ZulipError>
+    where
+        P: AsRef<Path> + std::fmt::Debug + Send,
+    {
+        let path = path.as_ref().to_path_buf();
+
+        let is_image = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .is_some_and(|ext| AVATAR_EXTENSIONS.contains(&ext.as_str()));
+        if !is_image {
+            return Err(FileError::UnsupportedImageFormat(path.display().to_string()).into());
+        }
+
+        self.check_upload_size(&path).await?;
+
+        let url = self.api_url().join("users/me/avatar")?;
+        let resp = self
+            .send_request(
+                self.auth(self.reqwest_client().post(url))
+                    .multipart(file_form(&path).await?),
+            )
+            .await?;
+
+        let resp = match Self::read_json::<UploadAvatarResponse>(resp).await? {
+            Ok(UploadAvatarResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+
+                if Self::is_upload_rejected(&error) {
+                    return Err(FileError::UploadRejected {
+                        path: path.display().to_string(),
+                        error: error.to_string(),
+                    }
+                    .into());
+                }
+
+                return Err(UserError::UploadAvatarFailed {
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        tracing::trace!("uploaded new avatar!");
+        Ok(self.conf.server_address.join(&resp.avatar_url)?)
+    }
+
+    /// Grabs the URL of a user's avatar.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_avatar_url(&self, user_id: u64) -> Result<Url, ZulipError> {
+        let user = self.get_user(user_id, false).await?;
+
+        // if the server leaves it out, it has a url that redirects there
+        let avatar_url = user
+            .avatar_url
+            .unwrap_or_else(|| format!("/avatar/{user_id}"));
+
+        // this handles both absolute and relative urls
+        Ok(self.conf.server_address.join(&avatar_url)?)
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct UploadAvatarResponse {
+    #[serde(flatten)]
+    error: Option<ResponseError>,
+    avatar_url: String,
+}
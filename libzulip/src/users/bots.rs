@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+
+use crate::{
+    config::ApiKey,
+    error::{ResponseError, UserError, ZulipError},
+    Client,
+};
+
+impl Client {
+    /// Makes a new bot, owned by the current user.
+    ///
+    /// The returned [`Bot`] holds everything the bot needs to log in.
+    #[tracing::instrument(skip(self))]
+    pub async fn create_bot(&self, bot: CreateBot) -> Result<Bot, ZulipError> {
+        let url = self.api_url().join("bots")?;
+        let parameters = bot.make_parameters();
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().post(url)).form(&parameters))
+            .await?;
+
+        let resp = match Self::read_json::<CreateBotResponse>(resp).await? {
+            Ok(CreateBotResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(UserError::CreateBotFailed {
+                    name: bot.short_name,
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        // the server doesn't say what the bot's email is, so ask
+        let email = self.get_user(resp.user_id, false).await?.email;
+
+        tracing::trace!("created bot `{email}`!");
+        Ok(Bot {
+            user_id: resp.user_id,
+            email,
+            api_key: ApiKey::from_owned(resp.api_key),
+        })
+    }
+
+    /// Grabs the API key of a bot owned by the current user.
+    ///
+    /// If the current user doesn't own a bot with this ID, this returns a
+    /// `UserError::BotNotFound`.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_bot_api_key(&self, bot_id: u64) -> Result<ApiKey, ZulipError> {
+        let url = self.api_url().join("bots")?;
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().get(url)))
+            .await?;
+
+        let resp = match Self::read_json::<GetBotsResponse>(resp).await? {
+            Ok(GetBotsResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(UserError::GetBotApiKeyFailed {
+                    bot_id,
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        let Some(bot) = resp.bots.into_iter().find(|bot| bot.user_id == bot_id) else {
+            tracing::debug!("we don't own a bot with ID `{bot_id}`.");
+            return Err(UserError::BotNotFound { bot_id }.into());
+        };
+
+        tracing::trace!("grabbed the api key for bot `{bot_id}`!");
+        Ok(ApiKey::from_owned(bot.api_key))
+    }
+
+    /// Makes a new API key for a bot, returning it.
+    ///
+    /// The old key stops working right away!
+    #[tracing::instrument(skip(self))]
+    pub async fn regenerate_bot_api_key(&self, bot_id: u64) -> Result<ApiKey, ZulipError> {
+        let url = self
+            .api_url()
+            .join(&format!("bots/{bot_id}/api_key/regenerate"))?;
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().post(url)))
+            .await?;
+
+        let resp = match Self::read_json::<RegenerateBotApiKeyResponse>(resp).await? {
+            Ok(RegenerateBotApiKeyResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(UserError::RegenerateBotApiKeyFailed {
+                    bot_id,
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        tracing::trace!("regenerated the api key for bot `{bot_id}`!");
+        Ok(ApiKey::from_owned(resp.api_key))
+    }
+}
+
+/// A bot to make with [`Client::create_bot`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreateBot {
+    /// The bot's display name.
+    pub full_name: String,
+    /// Used to make the bot's email, like `{short_name}-bot@example.com`.
+    pub short_name: String,
+    /// What kind of bot to make.
+    pub bot_type: BotType,
+    /// Where Zulip sends messages for the bot. Required for
+    /// `BotType::Outgoing`.
+    pub payload_url: Option<String>,
+    /// The name of the bot's service. Used for `BotType::Outgoing` and
+    /// `BotType::Embedded`.
+    pub service_name: Option<String>,
+}
+
+impl CreateBot {
+    /// Describes a bot with only the required fields set.
+    pub fn new<S: AsRef<str>>(full_name: S, short_name: S, bot_type: BotType) -> Self {
+        Self {
+            full_name: full_name.as_ref().into(),
+            short_name: short_name.as_ref().into(),
+            bot_type,
+            payload_url: None,
+            service_name: None,
+        }
+    }
+
+    fn make_parameters(&self) -> HashMap<&str, String> {
+        let mut parameters = HashMap::from([
+            ("full_name", self.full_name.clone()),
+            ("short_name", self.short_name.clone()),
+            ("bot_type", self.bot_type.code().to_string()),
+        ]);
+
+        if let Some(ref payload_url) = self.payload_url {
+            parameters.insert("payload_url", payload_url.clone());
+        }
+        if let Some(ref service_name) = self.service_name {
+            parameters.insert("service_name", service_name.clone());
+        }
+
+        parameters
+    }
+}
+
+/// The kinds of bots Zulip has.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BotType {
+    /// Acts like a normal user.
+    Generic,
+    /// Can only send messages. Good for integrations.
+    Incoming,
+    /// Gets sent messages that mention it over HTTP.
+    Outgoing,
+    /// Runs on the Zulip server itself.
+    Embedded,
+}
+
+impl BotType {
+    /// The number Zulip uses for this kind of bot.
+    pub fn code(&self) -> u8 {
+        match *self {
+            BotType::Generic => 1,
+            BotType::Incoming => 2,
+            BotType::Outgoing => 3,
+            BotType::Embedded => 4,
+        }
+    }
+}
+
+/// A newly made bot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bot {
+    /// The bot's user ID.
+    pub user_id: u64,
+    /// The bot's Zulip API email address.
+    pub email: String,
+    /// The bot's API key. Use this with `email` to log in as the bot.
+    pub api_key: ApiKey,
+}
+
+#[derive(serde::Deserialize)]
+struct CreateBotResponse {
+    #[serde(flatten)]
+    error: Option<ResponseError>,
+    user_id: u64,
+    api_key: String,
+}
+
+#[derive(serde::Deserialize)]
+struct GetBotsResponse {
+    #[serde(flatten)]
+    error: Option<ResponseError>,
+    bots: Vec<OwnedBot>,
+}
+
+#[derive(serde::Deserialize)]
+struct OwnedBot {
+    user_id: u64,
+    api_key: String,
+}
+
+#[derive(serde::Deserialize)]
+struct RegenerateBotApiKeyResponse {
+    #[serde(flatten)]
+    error: Option<ResponseError>,
+    api_key: String,
+}
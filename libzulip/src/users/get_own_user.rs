@@ -0,0 +1,84 @@
+use crate::{
+    error::{ResponseError, UserError, ZulipError},
+    Client,
+};
+
+use super::get_users::UserRole;
+
+impl Client {
+    /// Grabs info about the current user.
+    ///
+    /// This value is cached after the first call, so feel free to call it
+    /// often. Use [`Client::refresh_own_user`] if you need the latest info
+    /// from the server.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_own_user(&self) -> Result<OwnUser, ZulipError> {
+        if let Some(own_user) = self.own_user_cache.read().await.clone() {
+            tracing::trace!("using cached own user.");
+            return Ok(own_user);
+        }
+
+        self.refresh_own_user().await
+    }
+
+    /// Grabs info about the current user directly from Zulip, updating the
+    /// cache used by [`Client::get_own_user`].
+    #[tracing::instrument(skip(self))]
+    pub async fn refresh_own_user(&self) -> Result<OwnUser, ZulipError> {
+        let url = self.api_url().join("users/me")?;
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().get(url)))
+            .await?;
+
+        let resp = match Self::read_json::<OwnUserResponse>(resp).await? {
+            Ok(OwnUserResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(UserError::GetOwnUserFailed {
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        tracing::trace!("grabbed own user! (id: {})", resp.own_user.user_id);
+        *self.own_user_cache.write().await = Some(resp.own_user.clone());
+        Ok(resp.own_user)
+    }
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct OwnUserResponse {
+    #[serde(flatten)]
+    pub error: Option<ResponseError>,
+    /// Zulip puts the user's info at the top level of the response.
+    #[serde(flatten)]
+    pub own_user: OwnUser,
+}
+
+/// Info about the current user.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[non_exhaustive]
+pub struct OwnUser {
+    /// The current user's ID.
+    pub user_id: u64,
+    /// The current user's Zulip API email address.
+    pub email: String,
+    /// The current user's full name.
+    pub full_name: String,
+    /// Whether the current user is an organization administrator.
+    pub is_admin: bool,
+    /// Whether the current user is an organization owner.
+    pub is_owner: bool,
+    /// The current user's role in the organization.
+    pub role: UserRole,
+    /// The highest message ID the current user can see.
+    pub max_message_id: u64,
+    /// The URL of the current user's avatar.
+    pub avatar_url: Option<String>,
+}
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::{ResponseError, UserError, ZulipError, ZulipErrorCode},
+    Client,
+};
+
+use super::get_users::User;
+
+impl Client {
+    /// Grabs a single user by their ID.
+    ///
+    /// If no user has the given ID, this returns a `UserError::UserNotFound`.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_user(
+        &self,
+        user_id: u64,
+        include_custom_profile_fields: bool,
+    ) -> Result<User, ZulipError> {
+        self.get_single_user(user_id.to_string(), include_custom_profile_fields)
+            .await
+    }
+
+    /// Grabs a single user by their Zulip API email address.
+    ///
+    /// If no user has the given email, this returns a `UserError::UserNotFound`.
+    ///
+    /// Requires feature level 39.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_user_by_email(
+        &self,
+        email: &str,
+        include_custom_profile_fields: bool,
+    ) -> Result<User, ZulipError> {
+        self.require_feature_level(39).await?;
+        self.get_single_user(email.to_string(), include_custom_profile_fields)
+            .await
+    }
+}
+
+// private
+impl Client {
+    /// Performs the lookup for both `get_user` and `get_user_by_email`, as the
+    /// endpoint accepts either an ID or an email.
+    async fn get_single_user(
+        &self,
+        user: String,
+        include_custom_profile_fields: bool,
+    ) -> Result<User, ZulipError> {
+        let url = self
+            .api_url()
+            .join(&format!("users/{}", urlencoding::encode(&user)))?;
+
+        let parameters = HashMap::from([(
+            "include_custom_profile_fields",
+            include_custom_profile_fields.to_string(),
+        )]);
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().get(url)).query(&parameters))
+            .await?;
+
+        let resp = match Self::read_json::<GetUserResponse>(resp).await? {
+            Ok(GetUserResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+
+                if Self::is_no_such_user(&error) {
+                    tracing::debug!("the user `{user}` wasn't found.");
+                    return Err(UserError::UserNotFound { user }.into());
+                }
+
+                return Err(UserError::GetUserFailed {
+                    user,
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        tracing::trace!("grabbed user `{user}`!");
+        Ok(resp.user)
+    }
+
+    /// Whether the server turned down a user lookup because there's no such
+    /// user.
+    ///
+    /// Zulip says so with a plain `BAD_REQUEST`, so we have to check the
+    /// message, too.
+    fn is_no_such_user(error: &ResponseError) -> bool {
+        error.code() == ZulipErrorCode::BadRequest
+            && error.msg().to_lowercase().contains("no such user")
+    }
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct GetUserResponse {
+    #[serde(flatten)]
+    pub error: Option<ResponseError>,
+    /// The requested user.
+    pub user: User,
+}
@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::{ResponseError, UserError, ZulipError},
+    Client,
+};
+
+impl Client {
+    /// Grabs every user in the organization, including bots and deactivated
+    /// users.
+    ///
+    /// - `client_gravatar`: if `true`, the server won't compute Gravatar URLs
+    ///   for users without a custom avatar. Their `avatar_url` will instead
+    ///   be `None`, and you'll need to make the URL yourself.
+    /// - `include_custom_profile_fields`: whether each user's custom profile
+    ///   field data should be included.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_users(
+        &self,
+        client_gravatar: bool,
+        include_custom_profile_fields: bool,
+    ) -> Result<Vec<User>, ZulipError> {
+        let parameters = HashMap::from([
+            ("client_gravatar", client_gravatar.to_string()),
+            (
+                "include_custom_profile_fields",
+                include_custom_profile_fields.to_string(),
+            ),
+        ]);
+
+        self.request_users(&parameters).await
+    }
+
+    /// Grabs the users with the given IDs, keyed by ID.
+    ///
+    /// This is much faster than grabbing them one at a time, like when
+    /// showing the names of everyone in a list of messages.
+    ///
+    /// Servers before feature level 384 can't filter users, so we grab them
+    /// all and pick out the ones you asked for.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_users_by_ids(
+        &self,
+        user_ids: &[u64],
+    ) -> Result<HashMap<u64, User>, ZulipError> {
+        if user_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let feature_level = self.server_settings_cache.get().await?.zulip_feature_level;
+        let mut parameters = HashMap::new();
+        if feature_level >= 384 {
+            parameters.insert("user_ids", serde_json::to_string(user_ids)?);
+        }
+
+        let users = self
+            .request_users(&parameters)
+            .await?
+            .into_iter()
+            .filter(|user| user_ids.contains(&user.user_id))
+            .map(|user| (user.user_id, user))
+            .collect::<HashMap<_, _>>();
+
+        tracing::trace!("found {} of {} users!", users.len(), user_ids.len());
+        Ok(users)
+    }
+}
+
+// private
+impl Client {
+    /// Sends `GET /users` with the given parameters.
+    async fn request_users(
+        &self,
+        parameters: &HashMap<&str, String>,
+    ) -> Result<Vec<User>, ZulipError> {
+        let url = self.api_url().join("users")?;
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().get(url)).query(parameters))
+            .await?;
+
+        let resp = match Self::read_json::<GetUsersResponse>(resp).await? {
+            Ok(GetUsersResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(UserError::GetUsersFailed {
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        tracing::trace!("grabbed {} users!", resp.members.len());
+        Ok(resp.members)
+    }
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct GetUsersResponse {
+    #[serde(flatten)]
+    pub error: Option<ResponseError>,
+    /// Every user in the organization.
+    pub members: Vec<User>,
+}
+
+/// A user's role in the organization.
+///
+/// Zulip sends these as integers, like `100` for owners. Roles compare by
+/// privilege, so owners are the greatest and guests are the least.
+///
+/// ```
+/// use libzulip::users::get_users::UserRole;
+///
+/// let parse = |n: &str| serde_json::from_str::<UserRole>(n).unwrap();
+/// assert_eq!(parse("100"), UserRole::Owner);
+/// assert_eq!(parse("200"), UserRole::Admin);
+/// assert_eq!(parse("300"), UserRole::Moderator);
+/// assert_eq!(parse("400"), UserRole::Member);
+/// assert_eq!(parse("600"), UserRole::Guest);
+/// assert_eq!(parse("500"), UserRole::Other(500));
+///
+/// assert!(UserRole::Owner > UserRole::Admin);
+/// assert!(UserRole::Moderator >= UserRole::Member);
+/// assert!(UserRole::Other(500) > UserRole::Guest);
+/// assert!(UserRole::Other(500) < UserRole::Member);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(from = "u64")]
+#[non_exhaustive]
+pub enum UserRole {
+    /// An organization owner. They can do anything.
+    Owner,
+    /// An organization administrator.
+    Admin,
+    /// A moderator.
+    Moderator,
+    /// A regular member.
+    Member,
+    /// A guest, who can only see the channels they're added to.
+    Guest,
+    /// A role we don't know about yet.
+    Other(u64),
+}
+
+impl From<u64> for UserRole {
+    fn from(role: u64) -> Self {
+        match role {
+            100 => UserRole::Owner,
+            200 => UserRole::Admin,
+            300 => UserRole::Moderator,
+            400 => UserRole::Member,
+            600 => UserRole::Guest,
+            other => UserRole::Other(other),
+        }
+    }
+}
+
+impl UserRole {
+    /// Zulip's integer for the role.
+    fn code(self) -> u64 {
+        match self {
+            UserRole::Owner => 100,
+            UserRole::Admin => 200,
+            UserRole::Moderator => 300,
+            UserRole::Member => 400,
+            UserRole::Guest => 600,
+            UserRole::Other(code) => code,
+        }
+    }
+}
+
+impl PartialOrd for UserRole {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for UserRole {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // lower codes have more privileges
+        other.code().cmp(&self.code())
+    }
+}
+
+/// A user in the organization. This may be a person or a bot.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[non_exhaustive]
+pub struct User {
+    /// The unique ID of the user.
+    pub user_id: u64,
+    /// The Zulip API email address of the user. This might not be their real
+    /// email address, depending on the organization's email visibility
+    /// settings.
+    pub email: String,
+    /// The user's full name.
+    pub full_name: String,
+    /// Whether the user is active. Deactivated users are `false`.
+    pub is_active: bool,
+    /// Whether the user is a bot.
+    pub is_bot: bool,
+    /// The user's role in the organization.
+    pub role: UserRole,
+    /// The URL of the user's avatar.
+    ///
+    /// This is `None` when `client_gravatar` was set and the user doesn't
+    /// have a custom avatar.
+    pub avatar_url: Option<String>,
+    /// The user's IANA time zone name, like `America/New_York`.
+    ///
+    /// Empty if the user hasn't set one.
+    pub timezone: String,
+    /// The user's values for the organization's custom profile fields, keyed
+    /// by the field's ID.
+    ///
+    /// Only present when `include_custom_profile_fields` was set. See
+    /// [`Client::get_custom_profile_fields`](crate::Client::get_custom_profile_fields)
+    /// for what each field is.
+    pub profile_data: Option<HashMap<String, ProfileFieldValue>>,
+}
+
+/// A user's value for a custom profile field.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+pub struct ProfileFieldValue {
+    /// The value, as the user entered it. For choice fields, this is the
+    /// choice's ID.
+    pub value: String,
+    /// The value rendered as HTML. Only sent for fields that support
+    /// Markdown.
+    pub rendered_value: Option<String>,
+}
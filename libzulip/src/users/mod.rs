@@ -0,0 +1,10 @@
+// like `messages`, each module here represents one API call (or a small
+// family of them)
+
+pub mod avatar;
+pub mod bots;
+pub mod get_own_user;
+pub mod get_user;
+pub mod get_users;
+pub mod presence;
+pub mod update_status;
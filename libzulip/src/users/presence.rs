@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::{ResponseError, UserError, ZulipError},
+    Client,
+};
+
+impl Client {
+    /// Grabs the presence info for a user, given their ID or email.
+    ///
+    /// The returned map is keyed by the Zulip client (like `website` or
+    /// `ZulipMobile`) that reported the presence. There's also an
+    /// `aggregated` key, which summarizes all the user's clients.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_user_presence<S>(
+        &self,
+        user_id_or_email: S,
+    ) -> Result<HashMap<String, ClientPresence>, ZulipError>
+    where
+        S: AsRef<str> + std::fmt::Debug + Send,
+    {
+        let user = user_id_or_email.as_ref();
+        let url = self
+            .api_url()
+            .join(&format!("users/{}/presence", urlencoding::encode(user)))?;
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().get(url)))
+            .await?;
+
+        let resp = match Self::read_json::<UserPresenceResponse>(resp).await? {
+            Ok(UserPresenceResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(UserError::GetPresenceFailed {
+                    user: user.to_string(),
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        tracing::trace!("grabbed presence for user `{user}`!");
+        Ok(resp.presence)
+    }
+
+    /// Updates the current user's presence, and grabs the presence info for
+    /// everyone in the organization.
+    ///
+    /// - `status`: whether the user is active or idle.
+    /// - `ping_only`: if `true`, the server won't bother sending the presence
+    ///   info of other users. `presences` will be `None` in the response.
+    /// - `new_user_input`: whether the user has interacted with the client
+    ///   since the last presence update.
+    #[tracing::instrument(skip(self))]
+    pub async fn update_presence(
+        &self,
+        status: PresenceStatus,
+        ping_only: bool,
+        new_user_input: bool,
+    ) -> Result<UpdatePresenceResponse, ZulipError> {
+        let url = self.api_url().join("users/me/presence")?;
+
+        let parameters = HashMap::from([
+            ("status", status.to_string()),
+            ("ping_only", ping_only.to_string()),
+            ("new_user_input", new_user_input.to_string()),
+            // we only understand the modern format, keyed by user id
+            ("slim_presence", true.to_string()),
+        ]);
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().post(url)).form(&parameters))
+            .await?;
+
+        let resp = match Self::read_json::<UpdatePresenceResponse>(resp).await? {
+            Ok(UpdatePresenceResponse {
+                error: Some(error), ..
+            })
+            | Err(error) => {
+                error.warn_ignored();
+                return Err(UserError::UpdatePresenceFailed {
+                    code: error.code(),
+                    error: error.to_string(),
+                }
+                .into());
+            }
+            Ok(resp) => resp,
+        };
+
+        tracing::trace!("updated presence to `{status}`!");
+        Ok(resp)
+    }
+}
+
+/// Whether a user is currently using Zulip.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord, Hash, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresenceStatus {
+    /// The user is interacting with Zulip right now.
+    Active,
+    /// The user has Zulip open, but hasn't interacted with it recently.
+    Idle,
+}
+
+impl std::fmt::Display for PresenceStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            PresenceStatus::Active => f.write_str("active"),
+            PresenceStatus::Idle => f.write_str("idle"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct UserPresenceResponse {
+    #[serde(flatten)]
+    pub error: Option<ResponseError>,
+    /// The user's presence, keyed by client name.
+    pub presence: HashMap<String, ClientPresence>,
+}
+
+/// A user's presence, as reported by one of their Zulip clients.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+pub struct ClientPresence {
+    /// Whether the user was active or idle on this client.
+    pub status: PresenceStatus,
+    /// When this presence was last reported, as a UNIX timestamp.
+    pub timestamp: u64,
+    /// The name of the client. Not present in the `aggregated` entry.
+    pub client: Option<String>,
+    /// Whether the client is capable of showing mobile/push notifications.
+    /// Not present in the `aggregated` entry.
+    pub pushable: Option<bool>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct UpdatePresenceResponse {
+    #[serde(flatten)]
+    pub error: Option<ResponseError>,
+    /// The time the server made this response, as a UNIX timestamp.
+    pub server_timestamp: Option<f64>,
+    /// The presence info of everyone in the organization, keyed by user ID.
+    ///
+    /// This is `None` when `ping_only` was set.
+    pub presences: Option<HashMap<u64, UserPresence>>,
+}
+
+/// The last times a user was seen as active and idle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize)]
+pub struct UserPresence {
+    /// The last time the user was active, as a UNIX timestamp.
+    pub active_timestamp: u64,
+    /// The last time the user was active or idle, as a UNIX timestamp.
+    pub idle_timestamp: u64,
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::{ResponseError, UserError, ZulipError},
+    messages::emoji_reaction::ReactionType,
+    Client,
+};
+
+impl Client {
+    /// Changes the current user's status.
+    ///
+    /// Only the fields you set on the `StatusUpdate` are changed. To remove
+    /// the status entirely, use [`StatusUpdate::cleared`].
+    ///
+    /// Setting a status emoji requires feature level 86.
+    #[tracing::instrument(skip(self))]
+    pub async fn update_status(&self, update: StatusUpdate) -> Result<(), ZulipError> {
+        if update.emoji_name.is_some() {
+            self.require_feature_level(86).await?;
+        }
+
+        let url = self.api_url().join("users/me/status")?;
+
+        let parameters = update.make_parameters();
+
+        let resp = self
+            .send_request(self.auth(self.reqwest_client().post(url)).form(&parameters))
+            .await?;
+
+        if let Ok(UpdateStatusResponse { error: Some(error) }) | Err(error) =
+            Self::read_json::<UpdateStatusResponse>(resp).await?
+        {
+            error.warn_ignored();
+            return Err(UserError::UpdateStatusFailed {
+                code: error.code(),
+                error: error.to_string(),
+            }
+            .into());
+        }
+
+        tracing::trace!("updated status successfully!");
+        Ok(())
+    }
+}
+
+/// A change to the current user's status.
+///
+/// Fields left as `None` won't be changed.
+#[derive(Clone, Debug, Default, PartialEq, PartialOrd, Eq, Ord)]
+pub struct StatusUpdate {
+    /// The text of the status, like "on vacation". An empty string removes
+    /// the status text.
+    ///
+    /// This can be at most 60 characters long.
+    pub status_text: Option<String>,
+    /// Whether the user should be shown as away.
+    ///
+    /// Zulip has deprecated this in favor of the "invisible mode" setting,
+    /// but it's still accepted.
+    pub away: Option<bool>,
+    /// The name of the status emoji. An empty string removes the emoji.
+    pub emoji_name: Option<String>,
+    /// A unique identifier for the status emoji, within the namespace of the
+    /// `reaction_type`. See [`EmojiSelector`](crate::messages::emoji_reaction::EmojiSelector)
+    /// for more info.
+    pub emoji_code: Option<String>,
+    /// The type of the status emoji.
+    pub reaction_type: Option<ReactionType>,
+}
+
+impl StatusUpdate {
+    /// Creates a `StatusUpdate` that removes both the status text and emoji.
+    pub fn cleared() -> Self {
+        Self {
+            status_text: Some(String::new()),
+            emoji_name: Some(String::new()),
+            ..Self::default()
+        }
+    }
+
+    fn make_parameters(&self) -> HashMap<&str, String> {
+        let mut parameters = HashMap::new();
+
+        if let Some(ref status_text) = self.status_text {
+            parameters.insert("status_text", status_text.clone());
+        }
+        if let Some(away) = self.away {
+            parameters.insert("away", away.to_string());
+        }
+        if let Some(ref emoji_name) = self.emoji_name {
+            parameters.insert("emoji_name", emoji_name.clone());
+        }
+        if let Some(ref emoji_code) = self.emoji_code {
+            parameters.insert("emoji_code", emoji_code.clone());
+        }
+        if let Some(ref reaction_type) = self.reaction_type {
+            parameters.insert("reaction_type", reaction_type.to_string());
+        }
+
+        parameters
+    }
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct UpdateStatusResponse {
+    #[serde(flatten)]
+    pub error: Option<ResponseError>,
+}
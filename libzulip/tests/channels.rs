@@ -0,0 +1,66 @@
+//! Checks the channel endpoints that are easy to get subtly wrong.
+
+mod common;
+
+use libzulip::error::{StreamError, ZulipError};
+
+use common::{mock_client, mock_server, ok, SERVER_SETTINGS, STREAMS, STREAM_EMAIL_ADDRESS};
+
+#[tokio::test]
+async fn email_addresses_come_from_their_own_endpoint() {
+    let (address, mock) = mock_server(vec![
+        ok(SERVER_SETTINGS, ""),
+        ok(STREAM_EMAIL_ADDRESS, ""),
+        ok(STREAM_EMAIL_ADDRESS, ""),
+    ])
+    .await;
+    let client = mock_client(address).await;
+    let address = client.get_stream_email_address(7).await.unwrap();
+    assert_eq!(address, "general.abcd1234@zulip.example.com");
+    client
+        .get_stream_email_address_for_sender(7, 12)
+        .await
+        .unwrap();
+    let requests = mock.await.unwrap();
+    assert!(
+        requests[1].starts_with("get /api/v1/streams/7/email_address "),
+        "{requests:?}"
+    );
+    assert!(
+        requests[2].starts_with("get /api/v1/streams/7/email_address?sender_id=12 "),
+        "{requests:?}"
+    );
+}
+
+#[tokio::test]
+async fn channel_lookups_only_refresh_on_a_miss() {
+    let (address, mock) = mock_server(vec![
+        ok(SERVER_SETTINGS, ""),
+        ok(STREAMS, ""),
+        ok(STREAMS, ""),
+    ])
+    .await;
+    let client = mock_client(address).await;
+    assert_eq!(client.channel_id_for_name("general").await.unwrap(), 1);
+    assert_eq!(client.channel_name_for_id(7).await.unwrap(), "lunch");
+    assert_eq!(client.channel_id_for_name("Lunch").await.unwrap(), 7);
+    let err = client
+        .channel_id_for_name("this channel doesn't exist")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::StreamError(StreamError::StreamNotFound { .. })
+        ),
+        "{err}"
+    );
+    let requests = mock.await.unwrap();
+    assert_eq!(requests.len(), 3, "{requests:?}");
+    assert!(
+        requests[1..]
+            .iter()
+            .all(|r| r.starts_with("get /api/v1/streams?")),
+        "{requests:?}"
+    );
+}
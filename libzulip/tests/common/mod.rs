@@ -0,0 +1,274 @@
+//! A tiny fake Zulip server, plus the canned responses it gives.
+//!
+//! Each test binary only uses some of this, so the rest would be dead code.
+#![allow(dead_code)]
+
+use reqwest::Url;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use libzulip::{
+    config::{ApiKey, ClientConfig, ClientConfigBuilder, UserAgent},
+    Client,
+};
+
+/// What our fake server says its settings are.
+pub const SERVER_SETTINGS: &str = r#"{
+    "result": "success", "msg": "",
+    "authentication_methods": {}, "external_authentication_methods": [],
+    "zulip_feature_level": 300, "zulip_version": "9.0",
+    "push_notifications_enabled": false, "is_incompatible": false,
+    "email_auth_enabled": true, "require_email_format_usernames": true,
+    "realm_url": "http://localhost", "realm_name": "mock", "realm_icon": "",
+    "realm_description": ""
+}"#;
+
+/// The linkifiers our fake server has.
+pub const LINKIFIERS: &str = r##"{
+    "result": "success", "msg": "",
+    "linkifiers": [{"id": 1, "pattern": "#(?P<id>[0-9]+)", "url_template": "https://example.com/{id}"}]
+}"##;
+
+/// An empty batch of messages from our fake server.
+pub const NO_MESSAGES: &str = r#"{
+    "result": "success", "msg": "", "messages": [],
+    "anchor": 0, "found_oldest": true, "found_newest": true
+}"#;
+
+/// What our fake server says after something works, without anything else
+/// to say.
+pub const SUCCESS: &str = r#"{"result": "success", "msg": ""}"#;
+
+/// What our fake server says about a message that isn't there.
+pub const INVALID_MESSAGE: &str = r#"{
+    "result": "error", "msg": "Invalid message(s)", "code": "BAD_REQUEST"
+}"#;
+
+/// The email address our fake server gives its channels.
+pub const STREAM_EMAIL_ADDRESS: &str =
+    r#"{"result": "success", "msg": "", "email": "general.abcd1234@zulip.example.com"}"#;
+
+/// What our fake server says after sending a message.
+pub const SENT_MESSAGE: &str = r#"{"result": "success", "msg": "", "id": 42}"#;
+
+/// The channels our fake server has.
+pub const STREAMS: &str = r#"{
+    "result": "success", "msg": "",
+    "streams": [
+        {"stream_id": 1, "name": "general", "description": "", "rendered_description": "",
+         "invite_only": false, "is_web_public": false, "history_public_to_subscribers": true,
+         "stream_post_policy": 1, "message_retention_days": null, "stream_weekly_traffic": 12},
+        {"stream_id": 7, "name": "lunch", "description": "", "rendered_description": "",
+         "invite_only": false, "is_web_public": false, "history_public_to_subscribers": true,
+         "stream_post_policy": 1, "message_retention_days": null, "stream_weekly_traffic": null}
+    ]
+}"#;
+
+/// What our fake server says about a user that isn't there.
+pub const NO_SUCH_USER: &str =
+    r#"{"result": "error", "msg": "No such user", "code": "BAD_REQUEST"}"#;
+
+/// What our fake server says about a channel that isn't there.
+pub const INVALID_CHANNEL_NAME: &str =
+    r#"{"result": "error", "msg": "Invalid channel name 'nowhere'", "code": "BAD_REQUEST"}"#;
+
+/// What our fake server says when a deactivated user tries to log in.
+pub const USER_DEACTIVATED: &str = r#"{
+    "result": "error", "msg": "Your account has been deactivated",
+    "code": "USER_DEACTIVATED"
+}"#;
+
+/// What our fake server says about a wrong password.
+pub const AUTHENTICATION_FAILED: &str = r#"{
+    "result": "error", "msg": "Your username or password is incorrect",
+    "code": "AUTHENTICATION_FAILED"
+}"#;
+
+/// What our fake server says when we've made too many requests.
+pub const RATE_LIMIT_HIT: &str =
+    r#"{"result": "error", "msg": "API usage exceeded rate limit", "code": "RATE_LIMIT_HIT"}"#;
+
+/// What our fake server says about an image it can't read.
+pub const BAD_IMAGE: &str = r#"{
+    "result": "error", "msg": "Could not decode image; did you upload an image file?",
+    "code": "BAD_IMAGE"
+}"#;
+
+/// What our fake server says about a custom emoji name that's in use.
+pub const EMOJI_NAME_TAKEN: &str = r#"{
+    "result": "error", "msg": "A custom emoji with this name already exists.",
+    "code": "BAD_REQUEST"
+}"#;
+
+/// What our fake server says about a wrong API key.
+pub const INVALID_API_KEY: &str =
+    r#"{"result": "error", "msg": "Invalid API key", "code": "INVALID_API_KEY"}"#;
+
+/// What our fake server says once its organization is gone.
+pub const REALM_DEACTIVATED: &str = r#"{
+    "result": "error", "msg": "This organization has been deactivated",
+    "code": "REALM_DEACTIVATED"
+}"#;
+
+/// What our fake server says when an event queue has expired.
+pub const BAD_EVENT_QUEUE_ID: &str = r#"{
+    "result": "error", "msg": "Bad event queue ID: first",
+    "code": "BAD_EVENT_QUEUE_ID", "queue_id": "first"
+}"#;
+
+/// Starts building a config for a client that talks to our fake server.
+pub fn mock_config(address: Url) -> ClientConfigBuilder {
+    ClientConfig::builder()
+        .user_agent(UserAgent::new("client_name", "version"))
+        .api_key(ApiKey::new("not a real key"))
+        .email("me@example.com")
+        .server_address(address)
+        .allow_http(true)
+}
+
+/// Makes a client that talks to our fake server.
+///
+/// The client grabs the server settings first, so the server's first
+/// response should be [`SERVER_SETTINGS`].
+pub async fn mock_client(address: Url) -> Client {
+    Client::new(mock_config(address).build().unwrap())
+        .await
+        .unwrap()
+}
+
+/// Makes the body for registering an event queue.
+pub fn registered(queue_id: &str, last_event_id: i64) -> String {
+    format!(
+        r#"{{"result": "success", "msg": "", "queue_id": "{queue_id}", "last_event_id": {last_event_id}}}"#
+    )
+}
+
+/// Makes the body for grabbing some heartbeat events.
+pub fn heartbeats(ids: &[i64]) -> String {
+    let events = ids
+        .iter()
+        .map(|id| format!(r#"{{"id": {id}, "type": "heartbeat"}}"#))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(r#"{{"result": "success", "msg": "", "events": [{events}]}}"#)
+}
+
+/// Makes a `400 Bad Request` response with a JSON body.
+pub fn bad_request(body: &str) -> String {
+    respond("400 Bad Request", body, "")
+}
+
+/// Makes a `401 Unauthorized` response with a JSON body.
+pub fn unauthorized(body: &str) -> String {
+    respond("401 Unauthorized", body, "")
+}
+
+/// Makes a `429 Too Many Requests` response, asking us to wait
+/// `retry_after` seconds.
+pub fn rate_limited(retry_after: &str) -> String {
+    respond(
+        "429 Too Many Requests",
+        RATE_LIMIT_HIT,
+        &format!("retry-after: {retry_after}\r\n"),
+    )
+}
+
+/// Makes a `503 Service Unavailable` response.
+pub fn unavailable() -> String {
+    respond("503 Service Unavailable", "", "")
+}
+
+/// Makes a `200 OK` response with a JSON body and any extra headers.
+pub fn ok(body: &str, headers: &str) -> String {
+    respond("200 OK", body, headers)
+}
+
+/// Makes a response with the given status, JSON body, and extra headers.
+fn respond(status: &str, body: &str, headers: &str) -> String {
+    format!(
+        "HTTP/1.1 {status}\r\ncontent-type: application/json\r\n{headers}content-length: {}\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// Reads a whole request, including any body.
+async fn read_request(stream: &mut tokio::net::TcpStream) -> String {
+    let mut request = Vec::new();
+    let mut buf = vec![0; 8192];
+    loop {
+        let len = stream.read(&mut buf).await.unwrap();
+        request.extend_from_slice(&buf[..len]);
+        let text = String::from_utf8_lossy(&request).into_owned();
+
+        // stop once we've got the headers and as much body as they promise
+        let complete = text.find("\r\n\r\n").is_some_and(|end| {
+            let body_len = text
+                .lines()
+                .find_map(|line| {
+                    line.to_lowercase()
+                        .strip_prefix("content-length: ")?
+                        .parse()
+                        .ok()
+                })
+                .unwrap_or(0);
+            request.len() >= end + 4 + body_len
+        });
+        if complete || len == 0 {
+            return text;
+        }
+    }
+}
+
+/// Starts a tiny fake server that answers requests with `responses`, in
+/// order, then stops. An empty response hangs up without answering.
+///
+/// Returns its address and a handle to grab the (lowercased) requests it got.
+pub async fn mock_server(responses: Vec<String>) -> (Url, tokio::task::JoinHandle<Vec<String>>) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address =
+        Url::try_from(format!("http://{}", listener.local_addr().unwrap()).as_str()).unwrap();
+
+    let handle = tokio::spawn(async move {
+        let mut requests = Vec::new();
+        for response in responses {
+            // one request per connection keeps this simple
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let request = read_request(&mut stream).await;
+            requests.push(request.to_lowercase());
+            if response.is_empty() {
+                continue;
+            }
+
+            let response = response.replacen("\r\n", "\r\nconnection: close\r\n", 1);
+            let response = if response.contains("\r\n\r\n") {
+                response
+            } else {
+                format!("{response}\r\n")
+            };
+            stream.write_all(response.as_bytes()).await.unwrap();
+            stream.shutdown().await.unwrap();
+        }
+        requests
+    });
+
+    (address, handle)
+}
+
+/// Starts a fake server that reads a request, then sends `start` and stalls
+/// without ever finishing.
+pub async fn stalling_server(start: &'static [u8]) -> Url {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address =
+        Url::try_from(format!("http://{}", listener.local_addr().unwrap()).as_str()).unwrap();
+
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        read_request(&mut stream).await;
+        stream.write_all(start).await.unwrap();
+
+        // hold the connection open without sending the rest
+        std::future::pending::<()>().await;
+        drop(stream);
+    });
+
+    address
+}
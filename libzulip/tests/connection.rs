@@ -0,0 +1,201 @@
+//! Checks how the client connects to servers, and which configs it accepts.
+
+mod common;
+
+use reqwest::Url;
+
+use libzulip::{
+    config::{ClientConfig, ProxyConfig, RootCertificate},
+    error::{ConfigError, ZulipError},
+    messages::send_message::{ChannelMessageTarget, Message},
+    Client,
+};
+
+use common::{mock_client, mock_config, mock_server, ok, SENT_MESSAGE, SERVER_SETTINGS};
+
+/// Where nothing should be listening.
+const NOWHERE: &str = "http://127.0.0.1:9";
+
+#[tokio::test]
+async fn missing_servers_fail_to_connect() {
+    let err = Client::new(mock_config(Url::parse(NOWHERE).unwrap()).build().unwrap())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ZulipError::ConnectionFailed(_)), "{err}");
+}
+
+#[tokio::test]
+async fn missing_proxies_are_blamed() {
+    let err = Client::new(
+        mock_config(Url::parse("https://chat.example.com").unwrap())
+            .proxy(ProxyConfig::Custom {
+                url: Url::parse(NOWHERE).unwrap(),
+                auth: None,
+            })
+            .build()
+            .unwrap(),
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        matches!(err, ZulipError::ProxyConnectionFailed { .. }),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn broken_root_certificates_are_rejected() {
+    let err = Client::new(
+        mock_config(Url::parse("https://chat.example.com").unwrap())
+            .root_certificate(RootCertificate::Pem(b"not a certificate".to_vec()))
+            .build()
+            .unwrap(),
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::ConfigError(ConfigError::InvalidCertificate { .. })
+        ),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn default_headers_are_sent() {
+    let (address, mock) = mock_server(vec![ok(SERVER_SETTINGS, "")]).await;
+    Client::new(
+        mock_config(address)
+            .default_header("X-Gateway-Token", "open sesame")
+            .build()
+            .unwrap(),
+    )
+    .await
+    .unwrap();
+
+    let requests = mock.await.unwrap();
+    assert!(
+        requests[0].contains("x-gateway-token: open sesame"),
+        "{requests:?}"
+    );
+}
+
+#[test]
+fn default_header_values_stay_out_of_logs() {
+    let builder = mock_config(Url::parse("https://chat.example.com").unwrap())
+        .default_header("X-Gateway-Token", "open sesame");
+    let conf = builder.clone().build().unwrap();
+
+    for debugged in [format!("{builder:?}"), format!("{conf:?}")] {
+        assert!(debugged.contains("X-Gateway-Token"), "{debugged}");
+        assert!(!debugged.contains("open sesame"), "{debugged}");
+    }
+    assert_eq!(conf.default_headers["X-Gateway-Token"].get(), "open sesame");
+}
+
+#[test]
+fn bad_default_headers_are_rejected() {
+    let err = mock_config(Url::parse("https://chat.example.com").unwrap())
+        .default_header("bad header", "value")
+        .build()
+        .unwrap_err();
+    assert!(matches!(err, ConfigError::InvalidHeader { .. }), "{err}");
+}
+
+#[tokio::test]
+async fn plaintext_needs_to_be_allowed() {
+    let (address, mock) = mock_server(vec![ok(SERVER_SETTINGS, "")]).await;
+    let err = mock_config(address.clone())
+        .allow_http(false)
+        .build()
+        .unwrap_err();
+    assert!(
+        matches!(err, ConfigError::PlaintextServerAddress { .. }),
+        "{err}"
+    );
+
+    // ...and then it works, even over plain old HTTP/1.1
+    Client::new(mock_config(address).http1_only(true).build().unwrap())
+        .await
+        .unwrap();
+    let requests = mock.await.unwrap();
+    assert!(
+        requests[0].starts_with("get /api/v1/server_settings http/1.1"),
+        "{requests:?}"
+    );
+}
+
+#[tokio::test]
+async fn hand_made_configs_are_checked_too() {
+    let mut conf: ClientConfig = mock_config(Url::parse("http://localhost:9991").unwrap())
+        .build()
+        .unwrap();
+
+    // plaintext is still off unless asked for
+    conf.allow_http = false;
+    let err = Client::new(conf.clone()).await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::ConfigError(ConfigError::PlaintextServerAddress { .. })
+        ),
+        "{err}"
+    );
+
+    // and other schemes never work
+    conf.allow_http = true;
+    conf.server_address = Url::parse("ftp://localhost:9991").unwrap();
+    let err = Client::new(conf).await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::ConfigError(ConfigError::UnsupportedScheme { .. })
+        ),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn switching_servers_forgets_the_old_one() {
+    let (first_address, first) = mock_server(vec![ok(SERVER_SETTINGS, "")]).await;
+    let newer_settings = SERVER_SETTINGS.replace(
+        r#""zulip_feature_level": 300"#,
+        r#""zulip_feature_level": 400"#,
+    );
+    let (second_address, second) =
+        mock_server(vec![ok(&newer_settings, ""), ok(SENT_MESSAGE, "")]).await;
+    let mut client = mock_client(first_address).await;
+
+    client.set_server_address(second_address.clone());
+    assert_eq!(client.api_url(), second_address.join("api/v1/").unwrap());
+    let sent = client
+        .send_message(&Message::Channel {
+            to: ChannelMessageTarget::Id(3),
+            topic: "mocks".into(),
+            content: "hi from a test!".into(),
+            queue_id: "".into(),
+            local_id: "".into(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(sent.id, 42);
+
+    // the first server's settings shouldn't stick around
+    let settings = client.server_settings_cache.get().await.unwrap();
+    assert_eq!(settings.zulip_feature_level, 400);
+    assert_eq!(first.await.unwrap().len(), 1);
+    let requests = second.await.unwrap();
+    assert!(
+        requests[0].starts_with("get /api/v1/server_settings "),
+        "{requests:?}"
+    );
+    assert!(
+        requests[1].starts_with("post /api/v1/messages "),
+        "{requests:?}"
+    );
+    assert!(
+        requests[1].contains("content=hi+from+a+test"),
+        "{requests:?}"
+    );
+}
@@ -0,0 +1,431 @@
+//! Checks that Zulip's errors reach callers with enough detail to act on.
+
+mod common;
+
+use libzulip::{
+    config::ApiKey,
+    error::{
+        AuthError, FileError, OrganizationError, ResponseError, StreamError, UserError, ZulipError,
+        ZulipErrorCode,
+    },
+    users::{presence::PresenceStatus, update_status::StatusUpdate},
+};
+
+use common::{
+    bad_request, mock_client, mock_config, mock_server, ok, unauthorized, AUTHENTICATION_FAILED,
+    BAD_IMAGE, EMOJI_NAME_TAKEN, INVALID_API_KEY, INVALID_CHANNEL_NAME, NO_SUCH_USER,
+    REALM_DEACTIVATED, SERVER_SETTINGS, USER_DEACTIVATED,
+};
+
+#[test]
+fn error_codes_parse() {
+    let parse = |body: &str| serde_json::from_str::<ResponseError>(body).unwrap();
+
+    let error = parse(INVALID_API_KEY);
+    assert_eq!(error.code(), ZulipErrorCode::InvalidApiKey);
+    assert_eq!(error.raw_code(), "INVALID_API_KEY");
+    assert_eq!(error.msg(), "Invalid API key");
+    assert_eq!(
+        parse(REALM_DEACTIVATED).code(),
+        ZulipErrorCode::RealmDeactivated
+    );
+    assert_eq!(parse(BAD_IMAGE).code(), ZulipErrorCode::BadImage);
+
+    // codes we don't know about are kept as they are
+    let error = parse(r#"{"result": "error", "msg": "hm", "code": "SOMETHING_NEW"}"#);
+    assert_eq!(error.code(), ZulipErrorCode::Other("SOMETHING_NEW".into()));
+    assert_eq!(error.code().as_str(), "SOMETHING_NEW");
+    assert_eq!(error.raw_code(), "SOMETHING_NEW");
+}
+
+#[tokio::test]
+async fn only_missing_users_look_missing() {
+    let (address, _mock) = mock_server(vec![
+        ok(SERVER_SETTINGS, ""),
+        bad_request(NO_SUCH_USER),
+        bad_request(REALM_DEACTIVATED),
+    ])
+    .await;
+    let client = mock_client(address).await;
+
+    let err = client.get_user(404, false).await.unwrap_err();
+    assert!(
+        matches!(err, ZulipError::UserError(UserError::UserNotFound { .. })),
+        "{err}"
+    );
+    let err = client.get_user(8, false).await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::UserError(UserError::GetUserFailed {
+                code: ZulipErrorCode::RealmDeactivated,
+                ..
+            })
+        ),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn only_missing_channels_look_missing() {
+    let (address, _mock) = mock_server(vec![
+        ok(SERVER_SETTINGS, ""),
+        bad_request(INVALID_CHANNEL_NAME),
+        bad_request(REALM_DEACTIVATED),
+    ])
+    .await;
+    let client = mock_client(address).await;
+
+    let err = client.get_stream_id("nowhere").await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::StreamError(StreamError::StreamNotFound { .. })
+        ),
+        "{err}"
+    );
+    let err = client.get_stream_id("general").await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::StreamError(StreamError::GetStreamFailed {
+                code: ZulipErrorCode::RealmDeactivated,
+                ..
+            })
+        ),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn user_endpoints_keep_error_codes() {
+    let (address, _mock) = mock_server(vec![
+        ok(SERVER_SETTINGS, ""),
+        bad_request(REALM_DEACTIVATED),
+        bad_request(REALM_DEACTIVATED),
+        bad_request(REALM_DEACTIVATED),
+        bad_request(REALM_DEACTIVATED),
+    ])
+    .await;
+    let client = mock_client(address).await;
+
+    let err = client.get_users(true, false).await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::UserError(UserError::GetUsersFailed {
+                code: ZulipErrorCode::RealmDeactivated,
+                ..
+            })
+        ),
+        "{err}"
+    );
+    let err = client.get_user_presence("8").await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::UserError(UserError::GetPresenceFailed {
+                code: ZulipErrorCode::RealmDeactivated,
+                ..
+            })
+        ),
+        "{err}"
+    );
+    let err = client
+        .update_presence(PresenceStatus::Active, true, false)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::UserError(UserError::UpdatePresenceFailed {
+                code: ZulipErrorCode::RealmDeactivated,
+                ..
+            })
+        ),
+        "{err}"
+    );
+    let err = client
+        .update_status(StatusUpdate::cleared())
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::UserError(UserError::UpdateStatusFailed {
+                code: ZulipErrorCode::RealmDeactivated,
+                ..
+            })
+        ),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn fetching_api_keys_keeps_zulips_reasons() {
+    let (address, mock) = mock_server(vec![
+        bad_request(USER_DEACTIVATED),
+        bad_request(AUTHENTICATION_FAILED),
+    ])
+    .await;
+
+    // logging in should use the builder's settings too
+    let builder = mock_config(address).default_header("X-Gateway-Token", "open sesame");
+    let err = ApiKey::fetch(&builder, "me@example.com", "hunter2")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::AuthError(AuthError::FetchApiKeyFailed {
+                code: ZulipErrorCode::UserDeactivated,
+                ..
+            })
+        ),
+        "{err}"
+    );
+    let err = ApiKey::fetch(&builder, "me@example.com", "hunter3")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::AuthError(AuthError::InvalidCredentials { .. })
+        ),
+        "{err}"
+    );
+
+    let requests = mock.await.unwrap();
+    assert!(
+        requests[0].starts_with("post /api/v1/fetch_api_key "),
+        "{requests:?}"
+    );
+    assert!(
+        requests[0].contains("x-gateway-token: open sesame"),
+        "{requests:?}"
+    );
+}
+
+#[tokio::test]
+async fn avatars_are_only_blamed_when_zulip_says_so() {
+    let avatar = tempfile::Builder::new().suffix(".png").tempfile().unwrap();
+    std::fs::write(avatar.path(), b"not really a png").unwrap();
+    let (address, _mock) = mock_server(vec![
+        ok(SERVER_SETTINGS, ""),
+        bad_request(BAD_IMAGE),
+        unauthorized(INVALID_API_KEY),
+    ])
+    .await;
+    let client = mock_client(address).await;
+
+    let err = client.upload_own_avatar(avatar.path()).await.unwrap_err();
+    assert!(
+        matches!(err, ZulipError::FileError(FileError::UploadRejected { .. })),
+        "{err}"
+    );
+    let err = client.upload_own_avatar(avatar.path()).await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::UserError(UserError::UploadAvatarFailed {
+                code: ZulipErrorCode::InvalidApiKey,
+                ..
+            })
+        ),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn emoji_are_only_blamed_when_zulip_says_so() {
+    let emoji = tempfile::Builder::new().suffix(".png").tempfile().unwrap();
+    std::fs::write(emoji.path(), b"not really a png").unwrap();
+    let (address, _mock) = mock_server(vec![
+        ok(SERVER_SETTINGS, ""),
+        bad_request(BAD_IMAGE),
+        bad_request(EMOJI_NAME_TAKEN),
+        unauthorized(INVALID_API_KEY),
+    ])
+    .await;
+    let client = mock_client(address).await;
+
+    let err = client
+        .upload_realm_emoji("party", emoji.path())
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, ZulipError::FileError(FileError::UploadRejected { .. })),
+        "{err}"
+    );
+    for code in [ZulipErrorCode::BadRequest, ZulipErrorCode::InvalidApiKey] {
+        let err = client
+            .upload_realm_emoji("party", emoji.path())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ZulipError::OrganizationError(OrganizationError::UploadRealmEmojiFailed {
+                    code: ref got,
+                    ..
+                }) if *got == code
+            ),
+            "{err}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn oversized_emoji_arent_sent() {
+    let settings = SERVER_SETTINGS.replace(
+        r#""realm_description": """#,
+        r#""realm_description": "", "max_file_upload_size_mib": 0"#,
+    );
+    let (address, mock) = mock_server(vec![ok(&settings, "")]).await;
+    let client = mock_client(address).await;
+
+    let emoji = tempfile::Builder::new().suffix(".png").tempfile().unwrap();
+    std::fs::write(emoji.path(), b"too big!").unwrap();
+    let err = client
+        .upload_realm_emoji("party", emoji.path())
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, ZulipError::FileError(FileError::FileTooLarge { .. })),
+        "{err}"
+    );
+    assert_eq!(mock.await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn own_user_failures_keep_error_codes() {
+    let (address, _mock) =
+        mock_server(vec![ok(SERVER_SETTINGS, ""), unauthorized(INVALID_API_KEY)]).await;
+    let client = mock_client(address).await;
+
+    let err = client.get_own_user().await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::UserError(UserError::GetOwnUserFailed {
+                code: ZulipErrorCode::InvalidApiKey,
+                ..
+            })
+        ),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn linkifier_failures_keep_zulips_reasons() {
+    let invalid_template = r#"{
+        "result": "error", "msg": "Invalid URL template.", "code": "BAD_REQUEST"
+    }"#;
+    let (address, _mock) = mock_server(vec![
+        ok(SERVER_SETTINGS, ""),
+        bad_request(invalid_template),
+        bad_request(invalid_template),
+        unauthorized(INVALID_API_KEY),
+    ])
+    .await;
+    let client = mock_client(address).await;
+
+    let err = client
+        .create_linkifier("#(?P<id>[0-9]+)", "not a template")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::OrganizationError(OrganizationError::CreateLinkifierFailed {
+                code: ZulipErrorCode::BadRequest,
+                ref error,
+                ..
+            }) if error.contains("Invalid URL template")
+        ),
+        "{err}"
+    );
+    let err = client
+        .update_linkifier(1, "#(?P<id>[0-9]+)", "not a template")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::OrganizationError(OrganizationError::UpdateLinkifierFailed {
+                id: 1,
+                code: ZulipErrorCode::BadRequest,
+                ..
+            })
+        ),
+        "{err}"
+    );
+    let err = client.delete_linkifier(1).await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::OrganizationError(OrganizationError::DeleteLinkifierFailed {
+                id: 1,
+                code: ZulipErrorCode::InvalidApiKey,
+                ..
+            })
+        ),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn emoji_lists_keep_zulips_reasons() {
+    let (address, _mock) = mock_server(vec![
+        ok(SERVER_SETTINGS, ""),
+        bad_request(REALM_DEACTIVATED),
+        unauthorized(INVALID_API_KEY),
+        "HTTP/1.1 404 Not Found\r\ncontent-length: 9\r\n\r\nnot found".into(),
+    ])
+    .await;
+    let client = mock_client(address).await;
+
+    let err = client.get_realm_emoji().await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::OrganizationError(OrganizationError::GetRealmEmojiFailed {
+                code: ZulipErrorCode::RealmDeactivated,
+                ..
+            })
+        ),
+        "{err}"
+    );
+
+    // the server's emoji data usually comes from zulip...
+    let err = client
+        .get_server_emoji_reaction_data("/static/emoji.json")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::OrganizationError(OrganizationError::GetServerEmojiDataFailed {
+                code: ZulipErrorCode::InvalidApiKey,
+                ..
+            })
+        ),
+        "{err}"
+    );
+
+    // ...but not always
+    let err = client
+        .get_server_emoji_reaction_data("/static/emoji.json")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ZulipError::OrganizationError(OrganizationError::GetServerEmojiDataFailed {
+                code: ZulipErrorCode::BadRequest,
+                ..
+            })
+        ),
+        "{err}"
+    );
+}
@@ -0,0 +1,82 @@
+//! Checks that event streams keep going when the connection doesn't.
+
+mod common;
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures::StreamExt as _;
+
+use libzulip::events::{
+    event_stream::{ConnectionState, ReconnectBackoff, ResilientEvent},
+    register::RegisterQueue,
+};
+
+use common::{
+    bad_request, heartbeats, mock_client, mock_server, ok, registered, BAD_EVENT_QUEUE_ID,
+    SERVER_SETTINGS,
+};
+
+#[tokio::test]
+async fn streams_survive_hangups_and_expired_queues() {
+    let (address, mock) = mock_server(vec![
+        ok(SERVER_SETTINGS, ""),
+        ok(&registered("first", -1), ""),
+        ok(&heartbeats(&[0]), ""),
+        String::new(), // hang up
+        ok(&heartbeats(&[1]), ""),
+        bad_request(BAD_EVENT_QUEUE_ID),
+        ok(&registered("second", 5), ""),
+        ok(&heartbeats(&[6]), ""),
+    ])
+    .await;
+    let client = mock_client(address).await;
+    let states = Arc::new(Mutex::new(Vec::new()));
+    let items = client
+        .resilient_event_stream(RegisterQueue::default())
+        .backoff(ReconnectBackoff {
+            base_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(10),
+        })
+        .on_state_change({
+            let states = Arc::clone(&states);
+            move |state| states.lock().unwrap().push(state)
+        })
+        .take(4)
+        .map(Result::unwrap)
+        .collect::<Vec<_>>()
+        .await;
+    let ids = items
+        .iter()
+        .map(|item| match *item {
+            ResilientEvent::Event(ref event) => Some(event.id),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec![Some(0), Some(1), None, Some(6)]);
+    assert!(
+        matches!(items[2], ResilientEvent::Gap(ref snapshot) if snapshot.last_event_id == 5),
+        "{items:?}"
+    );
+    assert_eq!(
+        *states.lock().unwrap(),
+        vec![
+            ConnectionState::Connected,
+            ConnectionState::Reconnecting {
+                attempt: 1,
+                retry_in: Duration::from_millis(10)
+            },
+            ConnectionState::Connected,
+        ]
+    );
+
+    // after hanging up, it should pick up right where it left off
+    let requests = mock.await.unwrap();
+    assert!(
+        requests[4].contains("queue_id=first") && requests[4].contains("last_event_id=0"),
+        "{requests:?}"
+    );
+    assert!(requests[7].contains("queue_id=second"), "{requests:?}");
+}
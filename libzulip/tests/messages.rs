@@ -0,0 +1,237 @@
+//! Checks that message endpoints send what we asked for, and nothing else.
+
+mod common;
+
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio_util::sync::CancellationToken;
+use tracing_subscriber::EnvFilter;
+
+use libzulip::{
+    error::{MessageError, ZulipError},
+    messages::{
+        edit_message::PropagateMode, emoji_reaction::EmojiSelector,
+        fetch_single_message::ContentFormat, get_messages::GetMessages,
+    },
+    narrow::{
+        ChannelAttribute, NameOrId, Narrow, NarrowBuilder, NarrowKind, NarrowListExt as _,
+        NarrowNegation,
+    },
+};
+
+use common::{
+    bad_request, mock_client, mock_server, ok, stalling_server, INVALID_MESSAGE, NO_MESSAGES,
+    SERVER_SETTINGS, SUCCESS,
+};
+
+#[tokio::test]
+async fn moving_messages_leaves_their_content_alone() {
+    let (address, mock) = mock_server(vec![
+        ok(SERVER_SETTINGS, ""),
+        ok(
+            r#"{"result": "success", "msg": "", "detached_uploads": []}"#,
+            "",
+        ),
+    ])
+    .await;
+    let client = mock_client(address).await;
+    let moved = client
+        .move_message(
+            42,
+            Some(7),
+            Some("elsewhere".into()),
+            PropagateMode::ChangeOne,
+        )
+        .await
+        .unwrap();
+    assert_eq!(moved.moved, 1);
+    let requests = mock.await.unwrap();
+    assert!(
+        requests[1].starts_with("patch /api/v1/messages/42 "),
+        "{requests:?}"
+    );
+    assert!(requests[1].contains("topic=elsewhere"), "{requests:?}");
+    assert!(requests[1].contains("stream_id=7"), "{requests:?}");
+    assert!(!requests[1].contains("content="), "{requests:?}");
+}
+
+#[tokio::test]
+async fn queries_reach_the_server_as_asked() {
+    let (address, mock) = mock_server(vec![
+        ok(SERVER_SETTINGS, ""),
+        ok(NO_MESSAGES, ""),
+        ok(NO_MESSAGES, ""),
+    ])
+    .await;
+    let client = mock_client(address).await;
+    let narrow = NarrowBuilder::new().is_resolved().build();
+    client
+        .get_messages(GetMessages {
+            narrow: narrow.clone(),
+            ..GetMessages::default()
+        })
+        .await
+        .unwrap();
+    client
+        .get_messages(GetMessages {
+            format: ContentFormat::RenderedHtml,
+            ..GetMessages::default()
+        })
+        .await
+        .unwrap();
+    let requests = mock.await.unwrap();
+    let encoded = url::form_urlencoded::byte_serialize(narrow.to_query_param().as_bytes())
+        .collect::<String>()
+        .to_lowercase();
+    assert!(
+        requests[1].contains(&format!("narrow={encoded}")),
+        "{requests:?}"
+    );
+    assert!(
+        encoded.contains("%22operator%22%3a%22is%22%2c%22operand%22%3a%22resolved%22"),
+        "{encoded}"
+    );
+
+    // ...along with the content format we asked for
+    assert!(requests[1].contains("apply_markdown=false"), "{requests:?}");
+    assert!(requests[2].contains("apply_markdown=true"), "{requests:?}");
+}
+
+#[tokio::test]
+async fn older_servers_get_older_operators() {
+    let older_settings = SERVER_SETTINGS.replace(
+        r#""zulip_feature_level": 300"#,
+        r#""zulip_feature_level": 249"#,
+    );
+    let (address, mock) = mock_server(vec![ok(&older_settings, ""), ok(NO_MESSAGES, "")]).await;
+    let client = mock_client(address).await;
+    client
+        .get_messages(GetMessages {
+            narrow: vec![
+                Narrow::new(NarrowKind::Channel(NameOrId::Id(3)), NarrowNegation::Normal),
+                Narrow::new(
+                    NarrowKind::Channels(ChannelAttribute::Public),
+                    NarrowNegation::Normal,
+                ),
+            ],
+            ..GetMessages::default()
+        })
+        .await
+        .unwrap();
+    let requests = mock.await.unwrap();
+    assert!(
+        requests[1].contains("%22operator%22%3a%22stream%22%2c%22operand%22%3a3"),
+        "{requests:?}"
+    );
+    assert!(
+        requests[1].contains("%22operator%22%3a%22streams%22%2c%22operand%22%3a%22public%22"),
+        "{requests:?}"
+    );
+}
+
+#[tokio::test]
+async fn batch_deletes_keep_going_past_failures() {
+    let (address, mock) = mock_server(vec![
+        ok(SERVER_SETTINGS, ""),
+        ok(SUCCESS, ""),
+        bad_request(INVALID_MESSAGE),
+    ])
+    .await;
+    let client = mock_client(address).await;
+    let results = client.delete_messages(&[10, 20]).await;
+    let requests = mock.await.unwrap();
+    assert_eq!(results.len(), 2);
+
+    // the requests might arrive in either order, so see who got the error
+    let (deleted, failed) = if requests[1].starts_with("delete /api/v1/messages/10 ") {
+        (10, 20)
+    } else {
+        (20, 10)
+    };
+    assert!(results[&deleted].is_ok(), "{results:?}");
+    assert!(
+        matches!(
+            results[&failed],
+            Err(ZulipError::MessageError(MessageError::DeletionFailed { id, .. })) if id == failed
+        ),
+        "{results:?}"
+    );
+}
+
+#[tokio::test]
+async fn emoji_names_are_only_encoded_once() {
+    let (address, mock) = mock_server(vec![ok(SERVER_SETTINGS, ""), ok(SUCCESS, "")]).await;
+    let client = mock_client(address).await;
+    client
+        .add_emoji_reaction(5, EmojiSelector::from_unicode('👍').unwrap())
+        .await
+        .unwrap();
+    let requests = mock.await.unwrap();
+    assert!(
+        requests[1].starts_with("post /api/v1/messages/5/reactions "),
+        "{requests:?}"
+    );
+    let (_, body) = requests[1].split_once("\r\n\r\n").unwrap();
+    assert!(
+        body.split('&').any(|field| field == "emoji_name=%2b1"),
+        "{requests:?}"
+    );
+}
+
+/// Collects log lines, so tests can see what the client was up to.
+#[derive(Clone, Default)]
+struct Logs(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Logs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn cancelled_downloads_clean_up_after_themselves() {
+    let (address, _mock) = mock_server(vec![ok(SERVER_SETTINGS, "")]).await;
+    let client = mock_client(address).await;
+    let slow_address =
+        stalling_server(b"HTTP/1.1 200 OK\r\ncontent-length: 1000000\r\n\r\nthe start of it").await;
+
+    // the temp file's path is only logged, so listen for it
+    let logs = Logs::default();
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::new("libzulip=trace"))
+        .with_ansi(false)
+        .with_writer({
+            let logs = logs.clone();
+            move || logs.clone()
+        })
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let cancel = CancellationToken::new();
+    let cancel_soon = async {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        cancel.cancel();
+    };
+    let (result, ()) = tokio::join!(
+        client.download_file_with_cancel(slow_address.join("big.txt").unwrap(), &cancel),
+        cancel_soon,
+    );
+    assert!(matches!(result, Err(ZulipError::Cancelled)), "{result:?}");
+
+    let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+    let path = logs
+        .split_once("writing to disk at path `")
+        .and_then(|(_, rest)| rest.split_once('`'))
+        .map(|(path, _)| path)
+        .unwrap_or_else(|| panic!("the download never started: {logs}"));
+    assert!(!std::path::Path::new(path).exists(), "{path}");
+}
@@ -0,0 +1,156 @@
+//! Checks how the client copes with rate limits, flaky servers, and slow
+//! ones.
+
+mod common;
+
+use std::time::Duration;
+
+use libzulip::{config::RetryConfig, error::ZulipError, Client};
+
+use common::{
+    mock_client, mock_config, mock_server, ok, rate_limited, stalling_server, unavailable,
+    SERVER_SETTINGS, STREAM_EMAIL_ADDRESS,
+};
+
+/// Retries quickly, so the tests don't take forever.
+fn quick_retries() -> RetryConfig {
+    RetryConfig {
+        max_retries: 3,
+        base_delay: Duration::from_millis(10),
+        max_delay: Duration::from_secs(1),
+        respect_retry_after: true,
+        retry_non_idempotent: false,
+    }
+}
+
+#[tokio::test]
+async fn rate_limits_say_how_long_to_wait() {
+    let body = r#"{"result": "error", "msg": "", "code": "RATE_LIMIT_HIT", "retry-after": 2.5}"#;
+    let (address, _mock) = mock_server(vec![
+        ok(SERVER_SETTINGS, ""),
+        rate_limited("5"),
+        format!(
+            "HTTP/1.1 429 Too Many Requests\r\ncontent-length: {}\r\n\r\n{body}",
+            body.len()
+        ),
+    ])
+    .await;
+    let client = mock_client(address).await;
+
+    // the header comes first...
+    let err = client.get_stream_email_address(7).await.unwrap_err();
+    assert!(
+        matches!(err, ZulipError::RateLimited { retry_after } if retry_after == Duration::from_secs(5)),
+        "{err}"
+    );
+
+    // ...but the body works too
+    let err = client.get_stream_email_address(7).await.unwrap_err();
+    assert!(
+        matches!(err, ZulipError::RateLimited { retry_after } if retry_after == Duration::from_millis(2500)),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn silly_retry_afters_dont_panic() {
+    let (address, _mock) = mock_server(vec![rate_limited("1e300")]).await;
+    let err = Client::new(mock_config(address).build().unwrap())
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, ZulipError::RateLimited { retry_after } if retry_after == Duration::MAX),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn flaky_requests_are_retried() {
+    let (address, mock) = mock_server(vec![
+        ok(SERVER_SETTINGS, ""),
+        unavailable(),
+        rate_limited("0"),
+        ok(STREAM_EMAIL_ADDRESS, ""),
+    ])
+    .await;
+    let client = Client::new(mock_config(address).retry(quick_retries()).build().unwrap())
+        .await
+        .unwrap();
+
+    let address = client.get_stream_email_address(7).await.unwrap();
+    assert_eq!(address, "general.abcd1234@zulip.example.com");
+    assert_eq!(mock.await.unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn sends_arent_retried_unless_asked() {
+    let (address, mock) = mock_server(vec![ok(SERVER_SETTINGS, ""), rate_limited("0")]).await;
+    let client = Client::new(mock_config(address).retry(quick_retries()).build().unwrap())
+        .await
+        .unwrap();
+
+    // a `POST` could do the thing twice, so we only try once
+    let err = client.render_message("hi!").await.unwrap_err();
+    assert!(matches!(err, ZulipError::RateLimited { .. }), "{err}");
+    assert_eq!(mock.await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn giving_up_keeps_the_last_error() {
+    let (address, _mock) = mock_server(vec![
+        ok(SERVER_SETTINGS, ""),
+        rate_limited("0"),
+        rate_limited("0"),
+    ])
+    .await;
+    let retry = RetryConfig {
+        max_retries: 1,
+        ..quick_retries()
+    };
+    let client = Client::new(mock_config(address).retry(retry).build().unwrap())
+        .await
+        .unwrap();
+
+    let err = client.get_stream_email_address(7).await.unwrap_err();
+    assert!(matches!(err, ZulipError::RateLimited { .. }), "{err}");
+}
+
+#[tokio::test]
+async fn long_retry_afters_arent_waited_out() {
+    let (address, mock) = mock_server(vec![
+        ok(SERVER_SETTINGS, ""),
+        rate_limited("3600"),
+        rate_limited("1e300"),
+    ])
+    .await;
+    let client = Client::new(mock_config(address).retry(quick_retries()).build().unwrap())
+        .await
+        .unwrap();
+
+    // these are way past `max_delay`, so we should hear about them right away
+    for expected in [Duration::from_secs(3600), Duration::MAX] {
+        let err = tokio::time::timeout(Duration::from_secs(5), client.get_stream_email_address(7))
+            .await
+            .expect("we waited for the server")
+            .unwrap_err();
+        assert!(
+            matches!(err, ZulipError::RateLimited { retry_after } if retry_after == expected),
+            "{err}"
+        );
+    }
+    assert_eq!(mock.await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn slow_servers_time_out() {
+    let address = stalling_server(b"").await;
+    let err = Client::new(
+        mock_config(address)
+            .timeout(Duration::from_millis(200))
+            .build()
+            .unwrap(),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, ZulipError::Timeout), "{err}");
+}
@@ -0,0 +1,115 @@
+//! Checks that the server settings (and other cached bits) are only grabbed
+//! when they need to be.
+
+mod common;
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use reqwest::Client as ReqwestClient;
+use tokio::sync::RwLock;
+
+use libzulip::{
+    organizations::{Clock, ServerSettingsCache},
+    Client,
+};
+
+use common::{mock_client, mock_server, ok, LINKIFIERS, SERVER_SETTINGS};
+
+/// A clock that only moves when we tell it to.
+#[derive(Debug)]
+struct FakeClock {
+    start: Instant,
+    elapsed: Mutex<Duration>,
+}
+
+impl FakeClock {
+    fn advance(&self, by: Duration) {
+        *self.elapsed.lock().unwrap() += by;
+    }
+}
+
+impl Default for FakeClock {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: Mutex::new(Duration::ZERO),
+        }
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> Instant {
+        self.start + *self.elapsed.lock().unwrap()
+    }
+}
+
+/// Makes a cache that refreshes every minute, going by `clock`.
+async fn cache_with_clock(address: &reqwest::Url, clock: &Arc<FakeClock>) -> ServerSettingsCache {
+    ServerSettingsCache::new_with_clock(
+        ReqwestClient::new(),
+        &Client::api_url_for(address),
+        Some(Arc::new(RwLock::new(Duration::from_secs(60)))),
+        Arc::clone(clock) as Arc<dyn Clock>,
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn settings_refresh_right_after_the_interval() {
+    let clock = Arc::new(FakeClock::default());
+    let (address, mock) = mock_server(vec![ok(SERVER_SETTINGS, ""), ok(SERVER_SETTINGS, "")]).await;
+    let cache = cache_with_clock(&address, &clock).await;
+
+    clock.advance(Duration::from_secs(60));
+    cache.get().await.unwrap(); // still fresh!
+    clock.advance(Duration::from_millis(1));
+    cache.get().await.unwrap(); // refreshed
+    cache.get().await.unwrap(); // fresh again
+    assert_eq!(mock.await.unwrap().len(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_gets_only_refresh_once() {
+    let clock = Arc::new(FakeClock::default());
+    let (address, mock) = mock_server(vec![ok(SERVER_SETTINGS, ""), ok(SERVER_SETTINGS, "")]).await;
+    let cache = Arc::new(cache_with_clock(&address, &clock).await);
+
+    // everyone notices the settings are stale at once. the mock only has one
+    // more response, so a second refresh would fail
+    clock.advance(Duration::from_secs(61));
+    let tasks = (0..8)
+        .map(|_| {
+            let cache = Arc::clone(&cache);
+            tokio::spawn(async move { cache.get().await })
+        })
+        .collect::<Vec<_>>();
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+    assert_eq!(mock.await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn unchanged_linkifiers_are_reused() {
+    let (address, mock) = mock_server(vec![
+        ok(SERVER_SETTINGS, ""),
+        ok(LINKIFIERS, "etag: \"v1\"\r\n"),
+        "HTTP/1.1 304 Not Modified\r\netag: \"v1\"\r\n".into(),
+    ])
+    .await;
+    let client = mock_client(address).await;
+
+    let first = client.linkifiers().await.unwrap();
+    let second = client.linkifiers().await.unwrap();
+    assert_eq!(second.linkifiers.len(), 1);
+    assert_eq!(first.linkifiers[0].id, second.linkifiers[0].id);
+    let requests = mock.await.unwrap();
+    assert!(
+        requests[2].contains("if-none-match: \"v1\""),
+        "{requests:?}"
+    );
+}